@@ -0,0 +1,143 @@
+//! Compression of the messages.
+
+use crate::Result;
+
+/// Character that starts a run, e.g. `~3x2:ab` is `ababab`.
+const RUN_MARKER: char = '~';
+/// Character that separates the number of repetitions from the length of the pattern.
+const COUNT_SEPARATOR: char = 'x';
+/// Character that ends the length of the pattern.
+const LENGTH_TERMINATOR: char = ':';
+/// Maximum number of characters in a repeated pattern.
+///
+/// It is long enough for a styled grid cell, e.g. `|\x1b[4m  \x1b[0m`.
+const MAX_PATTERN_LENGTH: usize = 16;
+
+/// Encodes the repeated patterns of the given string with [run-length encoding].
+///
+/// Each run is written as the marker, the number of repetitions, the length of the pattern
+/// and the pattern itself. Runs are only written if they are shorter than the repetitions,
+/// the marker itself is always written as a run.
+/// The rendered grids are mostly repeated cells of water so they shrink considerably.
+///
+/// [run-length encoding]: https://en.wikipedia.org/wiki/Run-length_encoding
+pub fn encode_rle(s: &str) -> String {
+    let chars = s.chars().collect::<Vec<char>>();
+    let mut encoded = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let mut best_run = None;
+        let mut best_saving = 0;
+        for length in 1..=MAX_PATTERN_LENGTH.min(chars.len() - i) {
+            let pattern = &chars[i..i + length];
+            let count = chars[i..]
+                .chunks(length)
+                .take_while(|chunk| *chunk == pattern)
+                .count();
+            let run = format_run(pattern, count);
+            let saving = pattern.iter().map(|c| c.len_utf8()).sum::<usize>() * count;
+            if saving > run.len() && saving - run.len() > best_saving {
+                best_saving = saving - run.len();
+                best_run = Some((run, length * count));
+            }
+        }
+        match best_run {
+            Some((run, run_length)) => {
+                encoded += &run;
+                i += run_length;
+            }
+            None => {
+                if chars[i] == RUN_MARKER {
+                    encoded += &format_run(&chars[i..=i], 1);
+                } else {
+                    encoded.push(chars[i]);
+                }
+                i += 1;
+            }
+        }
+    }
+    encoded
+}
+
+/// Returns the encoded run of the pattern that is repeated the given number of times.
+fn format_run(pattern: &[char], count: usize) -> String {
+    format!(
+        "{}{}{}{}{}{}",
+        RUN_MARKER,
+        count,
+        COUNT_SEPARATOR,
+        pattern.len(),
+        LENGTH_TERMINATOR,
+        pattern.iter().collect::<String>()
+    )
+}
+
+/// Decodes the given run-length encoded string.
+///
+/// Returns an error if a run is not complete or its numbers are invalid.
+/// Also see [`encode_rle`].
+pub fn decode_rle(s: &str) -> Result<String> {
+    let mut decoded = String::new();
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != RUN_MARKER {
+            decoded.push(c);
+            continue;
+        }
+        let mut parse_number = |terminator: char| {
+            chars
+                .by_ref()
+                .take_while(|c| *c != terminator)
+                .collect::<String>()
+                .parse::<usize>()
+                .map_err(|_| "[!] Invalid run.")
+        };
+        let count = parse_number(COUNT_SEPARATOR)?;
+        let length = parse_number(LENGTH_TERMINATOR)?;
+        let pattern = chars.by_ref().take(length).collect::<String>();
+        if pattern.chars().count() != length {
+            return Err("[!] Incomplete run.".into());
+        }
+        decoded += &pattern.repeat(count);
+    }
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+    use crate::ship::STANDARD_FLEET;
+    use fastrand::Rng;
+
+    #[test]
+    fn test_rle() -> Result<()> {
+        let test_cases = [
+            ("", ""),
+            ("abc", "abc"),
+            ("aaaaab", "aaaaab"),
+            ("aaaaaaab", "~7x1:ab"),
+            ("~", "~1x1:~"),
+            ("x~~y", "x~1x1:~~1x1:~y"),
+            ("|  |  |  |  |  |  ", "~6x3:|  "),
+            ("🌀🌀🌀", "~3x1:🌀"),
+        ];
+        for (decoded, encoded) in test_cases.iter() {
+            assert_eq!(*encoded, encode_rle(decoded));
+            assert_eq!(*decoded, decode_rle(encoded)?);
+        }
+        assert!(decode_rle("~").is_err());
+        assert!(decode_rle("~2x").is_err());
+        assert!(decode_rle("~2x3:ab").is_err());
+        assert!(decode_rle("~ax1:b").is_err());
+
+        let grid = Grid::new_random_with_fleet(26, 26, &STANDARD_FLEET, &Rng::with_seed(42))?;
+        for show_ships in [true, false].iter() {
+            let grid_str = grid.as_string(*show_ships)?;
+            let encoded = encode_rle(&grid_str);
+            assert!(encoded.len() < grid_str.len() / 2);
+            assert_eq!(grid_str, decode_rle(&encoded)?);
+        }
+        Ok(())
+    }
+}
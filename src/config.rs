@@ -0,0 +1,400 @@
+//! Game configuration.
+
+use crate::grid::{GlyphSet, PlacementStyle, RowOrigin};
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Default width of the grids.
+pub const DEFAULT_GRID_WIDTH: u8 = 10;
+/// Default height of the grids.
+pub const DEFAULT_GRID_HEIGHT: u8 = 10;
+/// Default number of players in a game.
+pub const DEFAULT_PLAYERS_PER_GAME: usize = 3;
+/// Default number of hints for each player in practice mode.
+pub const DEFAULT_HINT_COUNT: usize = 3;
+/// Default number of shots that each player fires per turn.
+pub const DEFAULT_SHOTS_PER_TURN: usize = 1;
+/// Default number of inputs in a turn that do not fire a shot before the turn is forfeited.
+pub const DEFAULT_REPROMPT_LIMIT: usize = 20;
+/// Default maximum number of characters in a player name.
+pub const DEFAULT_MAX_NAME_LENGTH: usize = 32;
+
+/// Policy for handling the invalid inputs during a turn.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InputPolicy {
+    /// Player is asked again until a valid shot is given.
+    Lenient,
+    /// Player loses the turn.
+    Strict,
+}
+
+impl Default for InputPolicy {
+    fn default() -> Self {
+        Self::Lenient
+    }
+}
+
+/// Parses the policy from its name, e.g. `strict`.
+impl FromStr for InputPolicy {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "lenient" => Ok(Self::Lenient),
+            "strict" => Ok(Self::Strict),
+            _ => Err(format!("invalid input policy: {}", s)),
+        }
+    }
+}
+
+/// Penalty for reaching the limit of consecutive invalid inputs.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InvalidInputPenalty {
+    /// Player loses the turn.
+    Forfeit,
+    /// Player is disconnected from the game.
+    Disconnect,
+}
+
+impl Default for InvalidInputPenalty {
+    fn default() -> Self {
+        Self::Forfeit
+    }
+}
+
+/// Parses the penalty from its name, e.g. `disconnect`.
+impl FromStr for InvalidInputPenalty {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "forfeit" => Ok(Self::Forfeit),
+            "disconnect" => Ok(Self::Disconnect),
+            _ => Err(format!("invalid input penalty: {}", s)),
+        }
+    }
+}
+
+/// Policy for displaying the grids of the opponents.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DisplayPolicy {
+    /// Ships of the opponents are hidden until they are hit.
+    Fog,
+    /// Ships of the opponents are visible during the whole game, e.g. for teaching.
+    AllVisible,
+}
+
+impl Default for DisplayPolicy {
+    fn default() -> Self {
+        Self::Fog
+    }
+}
+
+/// Parses the policy from its name, e.g. `all-visible`.
+impl FromStr for DisplayPolicy {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "fog" => Ok(Self::Fog),
+            "all-visible" => Ok(Self::AllVisible),
+            _ => Err(format!("invalid display policy: {}", s)),
+        }
+    }
+}
+
+/// Game mode that the players can prefer when they join.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GameMode {
+    /// Game is played with the configuration of the server.
+    Standard,
+    /// Players can request hints, see [`Config::practice_mode`].
+    Practice,
+    /// Misses report the number of ship cells nearby, see [`Config::radar`].
+    Radar,
+    /// Ships of the opponents are visible, see [`DisplayPolicy::AllVisible`].
+    Teaching,
+}
+
+impl GameMode {
+    /// All of the game modes.
+    pub const ALL: [GameMode; 4] = [Self::Standard, Self::Practice, Self::Radar, Self::Teaching];
+
+    /// Applies the rules of the mode to the given configuration.
+    pub fn apply(self, config: &mut Config) {
+        match self {
+            Self::Standard => {}
+            Self::Practice => config.practice_mode = true,
+            Self::Radar => config.radar = true,
+            Self::Teaching => config.display_policy = DisplayPolicy::AllVisible,
+        }
+    }
+}
+
+impl Default for GameMode {
+    fn default() -> Self {
+        Self::Standard
+    }
+}
+
+impl fmt::Display for GameMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Standard => "standard",
+            Self::Practice => "practice",
+            Self::Radar => "radar",
+            Self::Teaching => "teaching",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Parses the mode from its name, e.g. `practice`.
+impl FromStr for GameMode {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_lowercase();
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|mode| mode.to_string() == name)
+            .ok_or_else(|| format!("invalid game mode: {}", s))
+    }
+}
+
+/// Configuration of the game.
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// Width of the grids.
+    pub grid_width: u8,
+    /// Height of the grids.
+    pub grid_height: u8,
+    /// Sizes (width, height) of the grids for each player in turn order.
+    ///
+    /// It can be used for handicapping the stronger players with a bigger grid,
+    /// the players that are not listed get the default grid size.
+    pub grid_sizes: Vec<(u8, u8)>,
+    /// Number of players in a game, up to [`MAX_PLAYERS`].
+    ///
+    /// [`MAX_PLAYERS`]: crate::game::MAX_PLAYERS
+    pub players_per_game: usize,
+    /// Seed used for generating the random grids.
+    ///
+    /// A new seed is picked for each game if it is not set.
+    pub seed: Option<u64>,
+    /// Whether if the game is played in practice mode.
+    ///
+    /// Players can request hints in practice mode.
+    pub practice_mode: bool,
+    /// Number of hints for each player in practice mode.
+    pub hint_count: usize,
+    /// Number of bonus hints for each player in turn order.
+    ///
+    /// It can be used for balancing the advantage of going first.
+    pub handicaps: Vec<usize>,
+    /// Fraction of the cells that should be occupied by ships, between 0 and 1.
+    ///
+    /// The fleet is scaled with the board size if it is set,
+    /// otherwise a random number of ships is placed.
+    pub fleet_density: Option<f32>,
+    /// Distribution of the randomly placed ships.
+    ///
+    /// The fleet is scaled with the board size or the standard fleet is used
+    /// if it is not uniform.
+    pub placement_style: PlacementStyle,
+    /// Origin of the row numbers for displaying and entering the coordinates.
+    pub row_origin: RowOrigin,
+    /// Whether if the players place their ships manually.
+    pub manual_placement: bool,
+    /// Whether if the number of remaining ships is hidden.
+    ///
+    /// Only the sunk ships are announced if it is set.
+    pub hide_ship_count: bool,
+    /// Whether if the distance to the nearest ship is told on misses.
+    ///
+    /// See [`Grid::proximity_hint`].
+    ///
+    /// [`Grid::proximity_hint`]: crate::grid::Grid::proximity_hint
+    pub proximity_hints: bool,
+    /// Whether if the number of ship cells next to the shot is told on misses.
+    ///
+    /// See [`Grid::adjacent_ship_count`].
+    ///
+    /// [`Grid::adjacent_ship_count`]: crate::grid::Grid::adjacent_ship_count
+    pub radar: bool,
+    /// Whether if the shooter is told how many shots they fired at the opponent on each sink.
+    pub sink_shot_count: bool,
+    /// Whether if ships are not allowed to touch each other, even diagonally.
+    ///
+    /// Manually placed fleets are rejected and random grids are generated again.
+    pub no_touch: bool,
+    /// Whether if the cells around a sunk ship are marked as missed shots.
+    ///
+    /// It only takes effect together with [`Config::no_touch`]
+    /// since the surrounding cells are only guaranteed to be water then.
+    pub reveal_on_sink: bool,
+    /// Whether if each player can reveal a column of their grid at the start of the game.
+    pub column_reveal: bool,
+    /// Whether if each player has a decoy on their grid.
+    pub decoys: bool,
+    /// Policy for handling the shots that cannot be parsed.
+    pub invalid_input: InputPolicy,
+    /// Whether if the first player is picked randomly instead of the first to join.
+    ///
+    /// The pick is made with the game seed so that the game can be reproduced.
+    pub random_first_player: bool,
+    /// Whether if the players confirm each shot before it is fired.
+    pub confirm_shots: bool,
+    /// Number of shots that each player fires per turn.
+    ///
+    /// With more than one shot, hits do not grant extra shots and the turn passes
+    /// after all of the shots are fired.
+    pub shots_per_turn: usize,
+    /// Number of consecutive invalid inputs that triggers the penalty.
+    ///
+    /// The player is warned one input before the limit.
+    /// There is no limit if it is not set.
+    pub invalid_input_limit: Option<usize>,
+    /// Penalty for reaching [`Config::invalid_input_limit`].
+    pub invalid_input_penalty: InvalidInputPenalty,
+    /// Number of invalid inputs in a turn before the turn is forfeited.
+    ///
+    /// The commands are not counted, so that a client that streams garbage cannot stall
+    /// the game regardless of the penalty. There is no limit if it is not set.
+    pub reprompt_limit: Option<usize>,
+    /// Delay between the turns so that the observers can follow the game.
+    ///
+    /// It is zero by default so that the games are not slowed down.
+    pub turn_delay: Duration,
+    /// Whether if the spectators get a replay of the moves so far when they join.
+    pub spectator_replay: bool,
+    /// Whether if the players are told how many spectators are watching when it changes.
+    ///
+    /// It is off by default for the privacy of the spectators.
+    pub announce_spectators: bool,
+    /// Whether if the players that are turned away at [`Config::max_games`] watch a game instead.
+    pub spectate_when_full: bool,
+    /// Policy for displaying the grids of the opponents.
+    pub display_policy: DisplayPolicy,
+    /// Whether if the tracking grid and the own grid are shown side by side.
+    ///
+    /// They are stacked vertically by default.
+    pub side_by_side: bool,
+    /// Whether if a status line is sent to the player at the start of each turn.
+    ///
+    /// It shows the turn number, the remaining ships and the accuracy of the player,
+    /// see [`Game::status_line`].
+    ///
+    /// [`Game::status_line`]: crate::game::Game::status_line
+    pub status_line: bool,
+    /// Message of the day that is sent to the connecting players before the name prompt.
+    ///
+    /// Nothing is sent if it is empty.
+    pub motd: String,
+    /// Maximum number of characters in a player name.
+    ///
+    /// Longer names are truncated, control characters are removed before counting.
+    pub max_name_length: usize,
+    /// Whether if the joining players are asked for their preferred game mode.
+    ///
+    /// The first player of a game decides the mode, see [`Game::add_player`].
+    ///
+    /// [`Game::add_player`]: crate::game::Game::add_player
+    pub ask_game_mode: bool,
+    /// Glyphs that are used for displaying the grids to the players.
+    pub glyphs: GlyphSet,
+    /// Path of the file that the results of the completed games are appended to.
+    ///
+    /// Results are not logged if it is not set.
+    pub game_log_path: Option<String>,
+    /// Path of the file that the game in progress is saved to after each turn.
+    ///
+    /// The saved game is restored when the server starts, see [`Game::load`].
+    /// A saved game that cannot be restored is renamed with the `.invalid` suffix.
+    /// It requires [`Config::max_games`] to be 1 so that the saves of the games do not overlap.
+    /// Games are not saved if it is not set.
+    ///
+    /// [`Game::load`]: crate::game::Game::load
+    pub save_path: Option<String>,
+    /// Maximum number of games that are played at the same time.
+    ///
+    /// Players are turned away when it is reached, there is no limit if it is not set.
+    pub max_games: Option<usize>,
+    /// Time to wait for the other players before filling the game with bots.
+    ///
+    /// Players wait until the game is full if it is not set.
+    pub bot_backfill_timeout: Option<Duration>,
+    /// Interval of the keepalive messages to the idle players.
+    ///
+    /// Dead connections are only detected on the next message if it is not set.
+    pub keepalive_interval: Option<Duration>,
+    /// Time to hold the place of a disconnected player during a game for reconnecting.
+    ///
+    /// They reconnect with the same name and their reconnection token, see
+    /// [`Game::reconnection_token`]. Their turns are skipped in the meantime
+    /// and they are eliminated afterwards.
+    /// The disconnected players are eliminated right away if it is not set.
+    ///
+    /// [`Game::reconnection_token`]: crate::game::Game::reconnection_token
+    pub disconnect_grace: Option<Duration>,
+    /// Time that a player has for entering their shot.
+    ///
+    /// The shot is forfeited as a miss once it runs out, the commands and the confirmation
+    /// of the shot count towards the same time. Players have no time limit if it is not set.
+    pub turn_timeout: Option<Duration>,
+    /// TCP socket address of the HTTP status endpoint.
+    ///
+    /// The endpoint is disabled if it is not set.
+    pub status_socket_addr: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            grid_width: DEFAULT_GRID_WIDTH,
+            grid_height: DEFAULT_GRID_HEIGHT,
+            grid_sizes: Vec::new(),
+            players_per_game: DEFAULT_PLAYERS_PER_GAME,
+            seed: None,
+            practice_mode: false,
+            hint_count: DEFAULT_HINT_COUNT,
+            handicaps: Vec::new(),
+            fleet_density: None,
+            placement_style: PlacementStyle::default(),
+            row_origin: RowOrigin::default(),
+            manual_placement: false,
+            hide_ship_count: false,
+            proximity_hints: false,
+            radar: false,
+            sink_shot_count: false,
+            no_touch: false,
+            reveal_on_sink: false,
+            column_reveal: false,
+            decoys: false,
+            invalid_input: InputPolicy::default(),
+            random_first_player: false,
+            confirm_shots: false,
+            shots_per_turn: DEFAULT_SHOTS_PER_TURN,
+            invalid_input_limit: None,
+            invalid_input_penalty: InvalidInputPenalty::default(),
+            reprompt_limit: Some(DEFAULT_REPROMPT_LIMIT),
+            turn_delay: Duration::from_secs(0),
+            spectator_replay: false,
+            announce_spectators: false,
+            spectate_when_full: false,
+            display_policy: DisplayPolicy::default(),
+            side_by_side: false,
+            status_line: false,
+            motd: String::new(),
+            max_name_length: DEFAULT_MAX_NAME_LENGTH,
+            ask_game_mode: false,
+            glyphs: GlyphSet::default(),
+            game_log_path: None,
+            save_path: None,
+            max_games: None,
+            bot_backfill_timeout: None,
+            keepalive_interval: None,
+            disconnect_grace: None,
+            turn_timeout: None,
+            status_socket_addr: None,
+        }
+    }
+}
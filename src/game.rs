@@ -1,16 +1,215 @@
 //! Main game.
 
+use crate::config::{Config, DisplayPolicy, GameMode, InputPolicy, InvalidInputPenalty};
 use crate::grid::Coordinate;
-use crate::grid::Grid;
-use crate::player::Player;
-use crate::ship::Ship;
+use crate::grid::{Grid, PlacementStyle, ShotOutcome, ALPHABET, MAX_RANDOM_SHIP_COUNT};
+use crate::player::{AbsentPlayer, BotPlayer, Participant, Player};
+use crate::rating::SharedRatings;
+use crate::share;
+use crate::ship::{self, Orientation, Placement, Ship, ShipType, STANDARD_FLEET};
+use crate::spectator::{Move, SharedGallery};
 use crate::Result;
+use fastrand::Rng;
+use std::collections::{HashMap, HashSet};
 use std::convert::TryFrom;
+use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::{Error as IoError, ErrorKind, Write};
+use std::iter;
+use std::mem;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-/// Maximum number of players.
-pub const MAX_PLAYERS: usize = 3;
+/// Maximum number of players in a game.
+pub const MAX_PLAYERS: usize = 8;
+/// Maximum number of characters in a message that is sent with the `say` command.
+pub const MAX_SAY_LENGTH: usize = 100;
+/// Time to wait between the turns if every player that is left is disconnected.
+const RECONNECT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// Time to wait for the players of a restored game to reconnect
+/// if [`Config::disconnect_grace`] is not set, see [`Game::load`].
+pub const DEFAULT_RESTORE_GRACE: Duration = Duration::from_secs(120);
+/// Fraction of the cells occupied by ships above which the players are warned at the start.
+///
+/// Nearly every shot hits on such boards, see [`Grid::coverage_ratio`].
+pub const HIGH_COVERAGE_RATIO: f32 = 0.9;
+
+/// Reconnection token of a disconnected player and their new connection once they reconnect.
+///
+/// The new connection of the player is put here by the lobby and picked up on their next turn,
+/// see [`Config::disconnect_grace`] and [`Game::reconnection_token`].
+pub type Reconnection = (String, Option<Box<dyn Participant>>);
+
+/// Players that are waiting to reconnect to their games, keyed by their names.
+pub type SharedReconnections = Arc<Mutex<HashMap<String, Reconnection>>>;
+
+/// Progress of the game.
+///
+/// It is shared with the server so that it can be read while the game is running.
+#[derive(Clone, Debug, Default)]
+pub struct Progress {
+    /// Whether if the game is in progress.
+    pub in_progress: bool,
+    /// Names of the players.
+    pub players: Vec<String>,
+    /// Total number of ships that are not sunk yet.
+    pub ships_remaining: usize,
+    /// Number of games that are completed.
+    pub games_completed: usize,
+    /// Number of shots that are fired in the current game.
+    pub turn_count: usize,
+    /// Name of the player whose turn it is.
+    pub current_player: Option<String>,
+    /// Summary of the last completed game, it is kept until the next game starts.
+    pub last_game: Option<Summary>,
+}
+
+impl Progress {
+    /// Returns the message for the players that cannot join the game.
+    pub fn lobby_full_message(&self) -> String {
+        if self.in_progress {
+            format!(
+                "Lobby is full. Game in progress between {}, ~{} ships remaining. Please try again later.\n",
+                self.players.join(", "),
+                self.ships_remaining
+            )
+        } else {
+            String::from("Lobby is full. Please wait.\n")
+        }
+    }
+}
+
+/// Summary of a completed game.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Summary {
+    /// Names of the players.
+    pub players: Vec<String>,
+    /// Name of the winner, `None` on a draw.
+    pub winner: Option<String>,
+    /// Number of shots that are fired in the game.
+    pub turn_count: usize,
+    /// Moves of the game, the players are given by their indices in [`Summary::players`].
+    pub moves: Vec<Move>,
+    /// Cells of the moves as they are shown to the players, in the order of [`Summary::moves`].
+    ///
+    /// The cells are labelled as on the grids of the targets, see [`Grid::coordinate_label`].
+    pub labels: Vec<String>,
+    /// Time that each player took for their turns, in the order of [`Summary::players`].
+    pub turn_times: Vec<TurnTimes>,
+}
+
+impl Summary {
+    /// Returns the human-readable summary with the stats of each player and the sunk ships.
+    pub fn describe(&self) -> String {
+        let name = |i: usize| self.players.get(i).map_or("unknown player", String::as_str);
+
+        let mut s = match &self.winner {
+            Some(winner) => format!("Last game: {} won after {} turns.\n", winner, self.turn_count),
+            None => format!("Last game: draw after {} turns.\n", self.turn_count),
+        };
+        for (i, player) in self.players.iter().enumerate() {
+            let shots = self.moves.iter().filter(|m| m.shooter == i).collect::<Vec<_>>();
+            let hits = shots.iter().filter(|m| m.is_hit()).count();
+            let sunk = shots.iter().filter(|m| m.is_sunk()).count();
+            s += &format!(
+                "{}: {} shots, {} hits, {} ships sunk.\n",
+                player,
+                shots.len(),
+                hits,
+                sunk
+            );
+            let times = self.turn_times.get(i).copied().unwrap_or_default();
+            if let Some(average) = times.average() {
+                s += &format!(
+                    "{}: {:.1}s per turn on average, {:.1}s at most.\n",
+                    player,
+                    average.as_secs_f64(),
+                    times.longest.as_secs_f64()
+                );
+            }
+        }
+        for (i, m) in self.moves.iter().enumerate().filter(|(_, m)| m.is_sunk()) {
+            let label = self.labels.get(i).cloned().unwrap_or_else(|| m.coordinate.to_string());
+            s += &format!(
+                "Turn {}: {} sank {}'s ship at {}.\n",
+                m.turn,
+                name(m.shooter),
+                name(m.target),
+                label
+            );
+        }
+        s
+    }
+}
+
+/// Time that a player took for their turns.
+///
+/// Each prompt of the turn is timed from sending it until the input is read.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TurnTimes {
+    /// Number of the inputs that are timed.
+    pub count: u32,
+    /// Total time of the inputs.
+    pub total: Duration,
+    /// Longest time of a single input.
+    pub longest: Duration,
+}
+
+impl TurnTimes {
+    /// Records the time of an input.
+    pub fn record(&mut self, elapsed: Duration) {
+        self.count += 1;
+        self.total += elapsed;
+        self.longest = self.longest.max(elapsed);
+    }
+
+    /// Returns the average time of the inputs, `None` if nothing is recorded.
+    pub fn average(&self) -> Option<Duration> {
+        self.total.checked_div(self.count)
+    }
+}
+
+/// Outcome of a single turn.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TurnOutcome {
+    /// Shot hit a ship.
+    Hit,
+    /// Shot hit and sunk a ship.
+    Sunk,
+    /// Shot missed.
+    Missed,
+    /// Input was not a valid coordinate.
+    Invalid {
+        /// Whether if the turn is lost, see [`InputPolicy`].
+        advances: bool,
+    },
+    /// Input was a command (e.g. `fleet` or `hint`) instead of a shot.
+    Command {
+        /// Whether if the command consumed the turn.
+        advances: bool,
+    },
+    /// All of the shots of the turn are fired, see [`Config::shots_per_turn`].
+    Volley {
+        /// Number of the shots that hit a ship.
+        hits: usize,
+    },
+}
+
+impl TurnOutcome {
+    /// Checks if the turn passes to the next player.
+    ///
+    /// Players keep shooting as long as they hit.
+    /// Invalid inputs only pass the turn with the strict [`InputPolicy`].
+    pub fn advances_turn(&self) -> bool {
+        match self {
+            Self::Missed | Self::Volley { .. } => true,
+            Self::Command { advances } | Self::Invalid { advances } => *advances,
+            Self::Hit | Self::Sunk => false,
+        }
+    }
+}
 
 /// Representation of the Battleship game.
 ///
@@ -18,31 +217,805 @@ pub const MAX_PLAYERS: usize = 3;
 #[derive(Default, Debug)]
 pub struct Game {
     /// Players of the game.
-    pub players: Vec<Player>,
+    pub players: Vec<Box<dyn Participant>>,
+    /// Seed that is used for generating the grids.
+    pub seed: u64,
+    /// Game configuration.
+    pub config: Config,
+    /// Shared progress of the game.
+    pub progress: Arc<Mutex<Progress>>,
+    /// Shared spectators and move history of the game.
+    pub gallery: SharedGallery,
+    /// Ratings of the players that are shared between the games.
+    pub ratings: SharedRatings,
+    /// Last shot of each (shooter, target) pair.
+    pub last_shots: HashMap<(usize, usize), Coordinate>,
+    /// Targets that the players picked with the `target` command.
+    pub targets: HashMap<usize, usize>,
+    /// Players that see the ships of their opponent in practice mode, see the `reveal` command.
+    pub revealed: HashSet<usize>,
+    /// Turn count when each defeated player was eliminated, see [`Game::ranking`].
+    pub eliminated_at: HashMap<usize, usize>,
+    /// Number of shots that are fired in the game.
+    pub turn_count: usize,
+    /// Number of invalid inputs in the current turn, see [`Config::reprompt_limit`].
+    pub reprompts: usize,
+    /// Time when the first player joined the game that is not started yet.
+    pub waiting_since: Option<Instant>,
+    /// Game mode that is decided by the first player, see [`Game::add_player`].
+    pub mode: Option<GameMode>,
+    /// Configuration before the game mode is applied.
+    base_config: Option<Config>,
+    /// Time when each disconnected player left, see [`Config::disconnect_grace`].
+    pub disconnected_since: HashMap<usize, Instant>,
+    /// Reconnections of the disconnected players that are shared between the games.
+    pub reconnections: SharedReconnections,
+    /// Name of the winner of the last game, see [`Game::winner`].
+    winner: Option<String>,
+    /// Number of spectators that the players are told about last.
+    spectator_count: usize,
+    /// Time that each player took for their turns, see [`Summary::turn_times`].
+    pub turn_times: HashMap<usize, TurnTimes>,
+    /// Players whose shots are fired at random cells, see the `autofinish` command.
+    pub autoplay: HashSet<usize>,
+    /// Index of the player whose turn it is, see [`Game::save`].
+    pub current_player: usize,
+    /// Grace period of the players of a restored game until it ends, see [`Game::load`].
+    restore_grace: Option<Duration>,
+    /// Time when the turn of the current player runs out, see [`Config::turn_timeout`].
+    turn_deadline: Option<Instant>,
+    /// Reconnection tokens of the players, see [`Game::reconnection_token`].
+    tokens: HashMap<usize, String>,
 }
 
 impl Game {
+    /// Constructs a new instance of [`Game`].
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    /// Constructs a new instance of [`Game`] for the given number of players,
+    /// e.g. 2 for a classic match. The rest of the configuration is the default one.
+    ///
+    /// Returns an error if the number of players is not between 2 and [`MAX_PLAYERS`].
+    /// Also see [`Config::players_per_game`].
+    pub fn with_capacity(player_count: usize) -> Result<Self> {
+        if !(2..=MAX_PLAYERS).contains(&player_count) {
+            return Err("[!] Invalid number of players per game.".into());
+        }
+        Ok(Self::new(Config {
+            players_per_game: player_count,
+            ..Config::default()
+        }))
+    }
+
+    /// Returns the token that the player at the given index has to enter for reconnecting.
+    ///
+    /// The tokens are given out once the game is ready, only the players that can reconnect
+    /// are told theirs (see [`Config::disconnect_grace`] and [`Config::save_path`]),
+    /// so that nobody else can take over their place by connecting with the same name.
+    pub fn reconnection_token(&self, index: usize) -> Option<&str> {
+        self.tokens.get(&index).map(String::as_str)
+    }
+
+    /// Returns the name of the winner after the game ends.
+    ///
+    /// Returns `None` while the game is in progress or if the game ended in a draw.
+    pub fn winner(&self) -> Option<&str> {
+        self.winner.as_ref().map(String::as_str)
+    }
+
     /// Checks if the players are ready to play.
+    ///
+    /// Also see [`Config::players_per_game`].
     pub fn is_ready(&self) -> bool {
-        self.players.len() == MAX_PLAYERS
+        self.players.len() >= self.config.players_per_game.min(MAX_PLAYERS)
     }
 
     /// Adds a new player to the game.
     ///
+    /// Players that are disconnected while waiting in the lobby are removed beforehand.
+    /// Once the game is ready, each player is told the names of all of their opponents.
+    /// The preferred game mode of the first player is applied to the game,
+    /// the players that prefer another mode are told about it.
+    ///
     /// Also see [`Game::is_ready`]
-    pub fn add_player(&mut self, player: Player) -> Result<()> {
-        if self.players.len() < MAX_PLAYERS {
-            self.players.push(player);
-            self.players[0].send("Waiting for opponent...\n")?;
+    pub fn add_player(&mut self, mut player: Box<dyn Participant>) -> Result<()> {
+        self.remove_idle_players();
+        if self.players.is_empty() {
+            self.waiting_since = Some(Instant::now());
+            self.set_mode(player.game_mode());
         } else {
-            self.players.push(player);
-            for i in 0..MAX_PLAYERS {
-                let message = format!(
-                    "Your opponent is {}\n",
-                    self.opponent(i).name
-                );
+            let mode = self.mode.unwrap_or_default();
+            if player.game_mode().map(|preferred| preferred != mode).unwrap_or(false) {
+                let host = self.players[0].name();
+                player.send(&format!(
+                    "This game is played in {} mode, as chosen by {}.\n",
+                    mode, host
+                ))?;
+            }
+        }
+        self.players.push(player);
+        if self.is_ready() {
+            let can_reconnect =
+                self.config.disconnect_grace.is_some() || self.config.save_path.is_some();
+            for i in 0..self.players.len() {
+                let token = self
+                    .tokens
+                    .entry(i)
+                    .or_insert_with(|| format!("{:016x}", fastrand::u64(..)))
+                    .clone();
+                if can_reconnect {
+                    let message = format!(
+                        "Your reconnection token is {}, enter it if you get disconnected.\n",
+                        token
+                    );
+                    self.players[i].send(&message)?;
+                }
+                let opponents = self
+                    .players
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, _)| *j != i)
+                    .map(|(_, opponent)| opponent.name())
+                    .collect::<Vec<&str>>();
+                let message = if opponents.len() == 1 {
+                    format!("Your opponent is {}\n", opponents[0])
+                } else {
+                    format!("Your opponents are: {}\n", opponents.join(", "))
+                };
                 self.players[i].send(&message)?;
             }
+        } else if let Some(player) = self.players.last_mut() {
+            player.send("Waiting for opponent...\n")?;
+        }
+        self.update_progress(false);
+        Ok(())
+    }
+
+    /// Applies the given game mode on top of the configuration of the game.
+    ///
+    /// The previous mode is reverted, `None` restores the configuration.
+    fn set_mode(&mut self, mode: Option<GameMode>) {
+        if let Some(config) = self.base_config.take() {
+            self.config = config;
+        }
+        if let Some(mode) = mode {
+            self.base_config = Some(self.config.clone());
+            mode.apply(&mut self.config);
+            println!("[#] Game mode: {}", mode);
+        }
+        self.mode = mode;
+    }
+
+    /// Fills the empty seats of the game with bots if the players waited for long enough.
+    ///
+    /// Returns `true` if the game is filled, see [`Config::bot_backfill_timeout`].
+    pub fn backfill_with_bots(&mut self) -> Result<bool> {
+        let is_expired = match (self.config.bot_backfill_timeout, self.waiting_since) {
+            (Some(timeout), Some(waiting_since)) => waiting_since.elapsed() >= timeout,
+            _ => false,
+        };
+        if !is_expired || self.players.is_empty() || self.is_ready() {
+            return Ok(false);
+        }
+        println!("[#] Filling the game with bots.");
+        for player in self.players.iter_mut() {
+            player.send("Filling the game with bots...\n")?;
+        }
+        let mut bot_count = 0;
+        while !self.is_ready() {
+            bot_count += 1;
+            self.add_player(Box::new(BotPlayer::new(&format!("Bot-{}", bot_count))))?;
+        }
+        Ok(true)
+    }
+
+    /// Adds a spectator that watches the game without being prompted to shoot.
+    ///
+    /// The current state of the grids is sent immediately,
+    /// the spectator receives the announcements of the game afterwards.
+    pub fn add_spectator(&mut self, spectator: Player) -> Result<()> {
+        let state = self.spectator_state(None)?;
+        let replay = self.config.spectator_replay;
+        match self.gallery.lock() {
+            Ok(mut gallery) => {
+                gallery.state = state;
+                gallery.add_spectator(spectator, replay)
+            }
+            Err(_) => Err("[!] Failed to retrieve the spectators.".into()),
+        }
+    }
+
+    /// Removes the players that are no longer connected.
+    fn remove_idle_players(&mut self) {
+        let mut i = 0;
+        while i < self.players.len() {
+            if self.players[i].is_alive() {
+                i += 1;
+            } else {
+                let player = self.players.remove(i);
+                println!("[#] {} left the lobby.", player.name());
+            }
+        }
+    }
+
+    /// Assigns random grids to the players.
+    ///
+    /// The grids are generated from the given seed so that the game can be reproduced.
+    /// The given size is overridden by [`Config::grid_sizes`], the fleet is the same for everyone.
+    pub fn assign_random_grids(&mut self, width: u8, height: u8, seed: u64) -> Result<()> {
+        self.seed = seed;
+        let rng = Rng::with_seed(seed);
+        let fleet = self.random_fleet(width, height);
+        let grids = (0..self.players.len())
+            .map(|i| {
+                let (width, height) = self.grid_size(i, width, height);
+                self.random_grid(width, height, fleet.as_ref().map(Vec::as_slice), &rng)
+            })
+            .collect::<Result<Vec<Grid>>>()?;
+        self.players.iter_mut().zip(grids).try_for_each(|(player, grid)| {
+            *player.grid_mut() = grid;
+            println!(
+                "[#] {}'s grid:{}",
+                player.name(),
+                player.grid().as_string(true)?
+            );
+            Ok(())
+        })
+    }
+
+    /// Returns a random grid with the given fleet, or random ships if the fleet is not given.
+    ///
+    /// A decoy is placed if [`Config::decoys`] is set.
+    /// Grids with touching ships are generated again if [`Config::no_touch`] is set,
+    /// so that the cells around the ships are guaranteed to be water.
+    fn random_grid(&self, width: u8, height: u8, fleet: Option<&[u8]>, rng: &Rng) -> Result<Grid> {
+        const MAX_ATTEMPTS: usize = 100;
+        let style = self.config.placement_style;
+        for _ in 0..MAX_ATTEMPTS {
+            let mut grid = match fleet {
+                Some(fleet) => Grid::new_random_with_style(width, height, fleet, style, rng)?,
+                None => Grid::new_random_with_rng(width, height, rng),
+            };
+            grid.origin = self.config.row_origin;
+            if self.config.decoys {
+                grid.place_random_decoy(rng);
+            }
+            if !self.config.no_touch || !grid.ships_touch() {
+                return Ok(grid);
+            }
+        }
+        Err(format!("[!] Failed to place the ships apart on a {}x{} grid.", width, height).into())
+    }
+
+    /// Returns the grid size (width, height) of the player at the given index.
+    ///
+    /// Falls back to the given default size if the player has no configured size.
+    pub fn grid_size(&self, i: usize, width: u8, height: u8) -> (u8, u8) {
+        self.config.grid_sizes.get(i).copied().unwrap_or((width, height))
+    }
+
+    /// Returns the fleet (ship sizes) of the players.
+    ///
+    /// Fleet is scaled with the board size if the fleet density is configured.
+    pub fn fleet(&self, width: u8, height: u8) -> Vec<u8> {
+        match self.config.fleet_density {
+            Some(density) => ship::fleet_for_board(width, height, density),
+            None => STANDARD_FLEET.to_vec(),
+        }
+    }
+
+    /// Returns the fleet of the random grids, or `None` if the grids get random ships.
+    fn random_fleet(&self, width: u8, height: u8) -> Option<Vec<u8>> {
+        let style = self.config.placement_style;
+        if self.config.fleet_density.is_some() || style != PlacementStyle::Uniform {
+            Some(self.fleet(width, height))
+        } else {
+            None
+        }
+    }
+
+    /// Checks that the ships fit on the configured grids before any grid is generated.
+    ///
+    /// Each size of [`Config::grid_sizes`] is checked as well as the default size.
+    /// Returns an error if the fleet cannot be placed on one of the grids.
+    pub fn check_grid_sizes(&self) -> Result<()> {
+        let default_size = (self.config.grid_width, self.config.grid_height);
+        let grid_sizes = self.config.grid_sizes.iter().copied().chain(iter::once(default_size));
+        for (width, height) in grid_sizes {
+            let fleet = if self.config.manual_placement {
+                Some(self.fleet(width, height))
+            } else {
+                self.random_fleet(width, height)
+            };
+            let fits = match &fleet {
+                Some(fleet) => ship::fleet_fits_board(width, height, fleet),
+                None => usize::from(width) * usize::from(height) >= MAX_RANDOM_SHIP_COUNT,
+            };
+            if !fits {
+                return Err(match fleet {
+                    Some(fleet) => format!(
+                        "[!] The fleet {:?} does not fit on a {}x{} grid.",
+                        fleet, width, height
+                    ),
+                    None => format!("[!] The ships do not fit on a {}x{} grid.", width, height),
+                }
+                .into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Lets the players place their ships one by one.
+    ///
+    /// Quick match players get a random grid instead.
+    pub fn place_ships_manually(&mut self, width: u8, height: u8) -> Result<()> {
+        let fleet = self.fleet(width, height);
+        for i in 0..self.players.len() {
+            let (width, height) = self.grid_size(i, width, height);
+            if self.players[i].quick_match() {
+                let grid = self.random_grid(width, height, Some(&fleet), &Rng::new())?;
+                *self.players[i].grid_mut() = grid;
+                continue;
+            }
+            let message =
+                format!("Waiting for {} to place their ships...\n", self.players[i].name());
+            for j in 0..self.players.len() {
+                if j != i {
+                    self.players[j].send(&message)?;
+                }
+            }
+            let player = &mut self.players[i];
+            player.send("Type \"random\" to place the remaining ships randomly.\n")?;
+            loop {
+                *player.grid_mut() = Grid::new(width, height);
+                player.grid_mut().origin = self.config.row_origin;
+                while let Some(size) = player.grid().remaining_to_place(&fleet).first().copied() {
+                    let grid_str = player.grid().as_string_with_glyphs(true, &self.config.glyphs)?;
+                    player.send_grid(&grid_str)?;
+                    player.send(&format!("Place your size-{} ship (e.g. C3 v): ", size))?;
+                    let placement = player.read()?;
+                    let is_placed = if placement.eq_ignore_ascii_case("random") {
+                        let no_touch = self.config.no_touch;
+                        player.grid_mut().place_remaining_randomly(&fleet, no_touch, &Rng::new())
+                    } else {
+                        Placement::try_from(placement)
+                            .ok()
+                            .and_then(|placement| {
+                                // The coordinates are entered from the row origin of the grid.
+                                let grid = player.grid();
+                                let relabel = |c: Coordinate| grid.parse_coordinate(&c.to_string());
+                                let placement = match placement.end {
+                                    Some(end) => Placement::from_range(
+                                        relabel(placement.coordinate)?,
+                                        relabel(end)?,
+                                    )?,
+                                    None => Placement {
+                                        coordinate: relabel(placement.coordinate)?,
+                                        ..placement
+                                    },
+                                };
+                                if !grid.contains(&placement.coordinate) {
+                                    return None;
+                                }
+                                placement.to_ship(size)
+                            })
+                            .map(|ship| player.grid_mut().place_ship(ship))
+                            .unwrap_or(false)
+                    };
+                    if !is_placed {
+                        player.send("Invalid placement, try again.\n")?;
+                    }
+                }
+                while self.config.decoys && !player.grid().ships.iter().any(|ship| ship.decoy) {
+                    player.send("Place your decoy (e.g. C3): ")?;
+                    let decoy = player.read()?;
+                    let is_placed = player
+                        .grid()
+                        .parse_coordinate(&decoy)
+                        .map(|coordinate| player.grid_mut().place_ship(Ship::new_decoy(coordinate)))
+                        .unwrap_or(false);
+                    if !is_placed {
+                        player.send("Invalid placement, try again.\n")?;
+                    }
+                }
+                match player.grid().validate_against_fleet(&fleet, self.config.no_touch) {
+                    Ok(()) => break,
+                    Err(e) => player.send(&format!("{} Place your ships again.\n", e))?,
+                }
+            }
+            println!(
+                "[#] {}'s grid:{}",
+                player.name(),
+                player.grid().as_string(true)?
+            );
+        }
+        Ok(())
+    }
+
+    /// Updates the shared progress of the game.
+    pub fn update_progress(&self, in_progress: bool) {
+        if let Ok(mut progress) = self.progress.lock() {
+            progress.in_progress = in_progress;
+            progress.players = self.players.iter().map(|p| p.name().to_string()).collect();
+            progress.ships_remaining = self
+                .players
+                .iter()
+                .map(|p| p.grid().ships.iter().filter(|ship| !ship.is_sunk()).count())
+                .sum();
+            progress.turn_count = self.turn_count;
+            if !in_progress {
+                progress.current_player = None;
+            }
+        }
+    }
+
+    /// Ends the game and removes the players and the spectators.
+    ///
+    /// The result is appended to the game log if it is configured
+    /// and the ratings of the players are updated.
+    /// The summary of the game is kept in the progress until the next game starts.
+    fn end(&mut self, winner: Option<usize>) {
+        if let Err(e) = self.log_result(winner) {
+            eprintln!("[!] Failed to write the game log: {}", e);
+        }
+        // The finished game is not restored on the next start.
+        if let Some(path) = &self.config.save_path {
+            match fs::remove_file(path) {
+                Err(e) if e.kind() != ErrorKind::NotFound => {
+                    eprintln!("[!] Failed to remove the saved game: {}", e);
+                }
+                _ => {}
+            }
+        }
+        self.update_ratings(winner);
+        let mut summary = Summary {
+            players: self.players.iter().map(|p| p.name().to_string()).collect(),
+            winner: winner.map(|winner| self.players[winner].name().to_string()),
+            turn_count: self.turn_count,
+            moves: Vec::new(),
+            labels: Vec::new(),
+            turn_times: (0..self.players.len())
+                .map(|i| self.turn_times.get(&i).copied().unwrap_or_default())
+                .collect(),
+        };
+        self.winner = summary.winner.clone();
+        self.spectator_count = 0;
+        if let Ok(mut gallery) = self.gallery.lock() {
+            summary.moves = gallery.moves.clone();
+            summary.labels = gallery
+                .moves
+                .iter()
+                .map(|m| self.players[m.target].grid().coordinate_label(m.coordinate))
+                .collect();
+            gallery.broadcast(&match winner {
+                Some(winner) => format!("{} won.\n", self.players[winner].name()),
+                None => String::from("Draw.\n"),
+            });
+            gallery.clear();
+        }
+        if let Ok(mut reconnections) = self.reconnections.lock() {
+            for i in self.disconnected_since.keys() {
+                reconnections.remove(self.players[*i].name());
+            }
+        }
+        self.disconnected_since.clear();
+        self.restore_grace = None;
+        self.tokens.clear();
+        self.players.clear();
+        self.last_shots.clear();
+        self.targets.clear();
+        self.revealed.clear();
+        self.eliminated_at.clear();
+        self.turn_times.clear();
+        self.autoplay.clear();
+        self.reprompts = 0;
+        self.waiting_since = None;
+        self.set_mode(None);
+        self.turn_count = 0;
+        self.update_progress(false);
+        if let Ok(mut progress) = self.progress.lock() {
+            progress.games_completed += 1;
+            progress.last_game = Some(summary);
+        }
+    }
+
+    /// Updates the ratings of the players with the result of the game.
+    fn update_ratings(&self, winner: Option<usize>) {
+        if let Ok(mut ratings) = self.ratings.lock() {
+            let names = self.players.iter().map(|p| p.name()).collect::<Vec<&str>>();
+            ratings.record_game(&names, winner.map(|winner| names[winner]));
+            for name in names {
+                println!("[#] {}'s rating: {}", name, ratings.get(name));
+            }
+        }
+    }
+
+    /// Returns the human-readable record of the game result.
+    ///
+    /// e.g. `[1650000000] Alice vs Bob: Alice won after 12 turns`
+    fn result_record(&self, winner: Option<usize>, timestamp: u64) -> String {
+        let players = self
+            .players
+            .iter()
+            .map(|player| player.name())
+            .collect::<Vec<&str>>()
+            .join(" vs ");
+        let result = match winner {
+            Some(winner) => format!("{} won", self.players[winner].name()),
+            None => String::from("draw"),
+        };
+        format!("[{}] {}: {} after {} turns\n", timestamp, players, result, self.turn_count)
+    }
+
+    /// Returns the machine-readable line of the game result.
+    ///
+    /// e.g. `RESULT winner=Alice turns=42 players=Alice,Bob`, the winner is `none` on a draw.
+    fn result_line(&self, winner: Option<usize>) -> String {
+        let players = self
+            .players
+            .iter()
+            .map(|player| player.name())
+            .collect::<Vec<&str>>()
+            .join(",");
+        let winner = winner.map_or("none", |winner| self.players[winner].name());
+        format!("RESULT winner={} turns={} players={}\n", winner, self.turn_count, players)
+    }
+
+    /// Returns the game as a string that can be shared and imported with [`Game::from_shareable`].
+    ///
+    /// It is the Base64 encoding of the seed, the grids of the players without the shots
+    /// and the moves so far (see [`Move::to_record`]), one per line.
+    pub fn to_shareable(&self) -> String {
+        let mut s = format!("seed\t{}\n", self.seed);
+        for player in &self.players {
+            let grid = player.grid();
+            let ships = grid
+                .ships
+                .iter()
+                .map(|ship| {
+                    let coords = ship.coords.iter().map(|c| c.to_string()).collect::<Vec<String>>();
+                    format!(
+                        "{}{}{}:{}",
+                        if ship.decoy { "~" } else { "" },
+                        ship.type_.size(),
+                        match ship.type_.orientation() {
+                            Some(Orientation::Horizontal) => 'h',
+                            _ => 'v',
+                        },
+                        coords.join("-")
+                    )
+                })
+                .collect::<Vec<String>>();
+            s += &format!(
+                "player\t{}\t{}x{}\t{}\n",
+                player.name(),
+                grid.width,
+                grid.height,
+                ships.join(" ")
+            );
+        }
+        if let Ok(gallery) = self.gallery.lock() {
+            for shot in &gallery.moves {
+                s += &format!("move\t{}\n", shot.to_record());
+            }
+        }
+        share::encode_base64(s.as_bytes())
+    }
+
+    /// Imports a game that is exported with [`Game::to_shareable`] with the given configuration.
+    ///
+    /// The players are bots with the shared grids, the moves can be replayed
+    /// with [`Game::replay_moves`].
+    pub fn from_shareable(s: &str, config: Config) -> Result<Self> {
+        let data = String::from_utf8(share::decode_base64(s)?)?;
+        let mut game = Game::new(config);
+        let mut moves = Vec::new();
+        for line in data.lines() {
+            match line.split('\t').collect::<Vec<&str>>().as_slice() {
+                ["seed", seed] => game.seed = seed.parse()?,
+                ["player", name, size, ships] => {
+                    let mut size = size.splitn(2, 'x').map(str::parse);
+                    let mut grid = match (size.next(), size.next()) {
+                        (Some(Ok(width)), Some(Ok(height))) => Grid::new(width, height),
+                        _ => return Err(format!("[!] Invalid grid size: {}", line).into()),
+                    };
+                    for ship in ships.split(' ').filter(|ship| !ship.is_empty()) {
+                        let ship = parse_shared_ship(ship)
+                            .ok_or_else(|| format!("[!] Invalid ship: {}", ship))?;
+                        grid.ships.push(ship);
+                    }
+                    grid.origin = game.config.row_origin;
+                    let mut player = BotPlayer::new(name);
+                    *player.grid_mut() = grid;
+                    game.players.push(Box::new(player));
+                }
+                ["move", record] => moves.push(Move::from_record(record)?),
+                _ => return Err(format!("[!] Invalid line in the shared game: {}", line).into()),
+            }
+        }
+        game.restore_moves(moves)?;
+        Ok(game)
+    }
+
+    /// Fires the shots of the recorded moves at the grids of the players.
+    ///
+    /// It is meant for replaying the games that are imported with [`Game::from_shareable`].
+    pub fn replay_moves(&mut self) -> Result<()> {
+        let moves = match self.gallery.lock() {
+            Ok(gallery) => gallery.moves.clone(),
+            Err(_) => return Err("[!] Failed to retrieve the moves.".into()),
+        };
+        for shot in moves {
+            match self.players.get_mut(shot.target) {
+                Some(target) => {
+                    target.grid_mut().fire_at(shot.coordinate);
+                    self.turn_count += 1;
+                }
+                None => return Err(format!("[!] Invalid move: {}", shot.to_record()).into()),
+            }
+        }
+        Ok(())
+    }
+
+    /// Restores the move history of a saved or shared game after its players are added.
+    ///
+    /// Returns an error if a move refers to a player that is not in the game.
+    fn restore_moves(&mut self, moves: Vec<Move>) -> Result<()> {
+        let players = self.players.len();
+        if let Some(shot) = moves.iter().find(|m| m.shooter >= players || m.target >= players) {
+            return Err(format!("[!] Invalid move: {}", shot.to_record()).into());
+        }
+        let history = moves.iter().map(|shot| self.describe_move(shot)).collect();
+        if let Ok(mut gallery) = self.gallery.lock() {
+            gallery.moves = moves;
+            gallery.history = history;
+        }
+        Ok(())
+    }
+
+    /// Saves the state of the game in progress to the given file.
+    ///
+    /// The names, grids, hints and reconnection tokens of the players, the move history
+    /// and the player whose turn it is are saved, the connections are not.
+    /// Also see [`Game::load`].
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let mut s = format!(
+            "turn\t{}\nturn_count\t{}\nseed\t{}\n",
+            self.current_player, self.turn_count, self.seed
+        );
+        for (i, player) in self.players.iter().enumerate() {
+            s += &format!(
+                "player\t{}\t{}\t{}\t{}\n",
+                player.name(),
+                player.hints(),
+                player.grid().to_snapshot(),
+                self.reconnection_token(i).unwrap_or_default()
+            );
+        }
+        if let Ok(gallery) = self.gallery.lock() {
+            for shot in &gallery.moves {
+                s += &format!("move\t{}\n", shot.to_record());
+            }
+        }
+        // The game is written to a temporary file first so that a crash while writing
+        // does not leave a truncated save behind.
+        let mut temp_path = path.as_ref().as_os_str().to_owned();
+        temp_path.push(".tmp");
+        fs::write(&temp_path, s)?;
+        fs::rename(&temp_path, path)?;
+        Ok(())
+    }
+
+    /// Loads a game that is saved with [`Game::save`] with the given configuration.
+    ///
+    /// The players are held as disconnected until they reconnect with the same names and tokens
+    /// (see [`Config::disconnect_grace`], [`DEFAULT_RESTORE_GRACE`] if it is not set),
+    /// the game goes on with [`Game::resume`] from [`Game::current_player`].
+    pub fn load<P: AsRef<Path>>(path: P, config: Config) -> Result<Self> {
+        let data = fs::read_to_string(path)?;
+        let mut game = Game::new(config);
+        game.restore_grace = game.config.disconnect_grace.or(Some(DEFAULT_RESTORE_GRACE));
+        let mut moves = Vec::new();
+        for line in data.lines() {
+            match line.split('\t').collect::<Vec<&str>>().as_slice() {
+                ["turn", index] => game.current_player = index.parse()?,
+                ["turn_count", turn_count] => game.turn_count = turn_count.parse()?,
+                ["seed", seed] => game.seed = seed.parse()?,
+                ["player", name, hints, grid, token] => {
+                    let mut grid = Grid::from_snapshot(grid)?;
+                    grid.origin = game.config.row_origin;
+                    let player = AbsentPlayer::restored(name, grid, hints.parse()?);
+                    game.disconnected_since.insert(game.players.len(), Instant::now());
+                    game.tokens.insert(game.players.len(), token.to_string());
+                    game.players.push(Box::new(player));
+                }
+                ["move", record] => moves.push(Move::from_record(record)?),
+                _ => return Err(format!("[!] Invalid line in the saved game: {}", line).into()),
+            }
+        }
+        if game.current_player >= game.players.len() {
+            return Err(format!("[!] Invalid player index: {}", game.current_player).into());
+        }
+        if let Ok(mut reconnections) = game.reconnections.lock() {
+            for (i, player) in game.players.iter().enumerate() {
+                let token = game.reconnection_token(i).unwrap_or_default().to_string();
+                reconnections.insert(player.name().to_string(), (token, None));
+            }
+        }
+        game.restore_moves(moves)?;
+        game.update_progress(true);
+        Ok(game)
+    }
+
+    /// Returns the indices of the players from the first place to the last.
+    ///
+    /// Players that are not defeated come first, then the players that were eliminated later.
+    /// Players that were eliminated on the same turn are ranked by the number of their hits,
+    /// then by their names, so that the ranking is deterministic.
+    pub fn ranking(&self) -> Vec<usize> {
+        let moves = match self.gallery.lock() {
+            Ok(gallery) => gallery.moves.clone(),
+            Err(_) => Vec::new(),
+        };
+        let hits = |i: usize| moves.iter().filter(|m| m.shooter == i && m.is_hit()).count();
+        let mut ranking = (0..self.players.len()).collect::<Vec<usize>>();
+        ranking.sort_by(|a, b| {
+            let eliminated_at = |i: usize| self.eliminated_at.get(&i).copied();
+            let alive = |i: usize| !self.players[i].grid().is_defeated();
+            alive(*b)
+                .cmp(&alive(*a))
+                .then_with(|| eliminated_at(*b).cmp(&eliminated_at(*a)))
+                .then_with(|| hits(*b).cmp(&hits(*a)))
+                .then_with(|| self.players[*a].name().cmp(self.players[*b].name()))
+        });
+        ranking
+    }
+
+    /// Sends the final ranking to the players and the spectators.
+    ///
+    /// It is only sent if there are more than two players, e.g. `Ranking: 1. Alice, 2. Bob, ...`.
+    fn send_ranking(&mut self) -> Result<()> {
+        if self.players.len() <= 2 {
+            return Ok(());
+        }
+        let places = self
+            .ranking()
+            .iter()
+            .enumerate()
+            .map(|(place, i)| format!("{}. {}", place + 1, self.players[*i].name()))
+            .collect::<Vec<String>>();
+        let message = format!("Ranking: {}\n", places.join(", "));
+        print!("[#] {}", message);
+        for player in self.players.iter_mut() {
+            player.send(&message)?;
+        }
+        self.broadcast_to_spectators(&message);
+        Ok(())
+    }
+
+    /// Sends the result line to the players and the spectators.
+    fn send_result_line(&mut self, winner: Option<usize>) -> Result<()> {
+        let line = self.result_line(winner);
+        print!("[#] {}", line);
+        for player in self.players.iter_mut() {
+            player.send(&line)?;
+        }
+        self.broadcast_to_spectators(&line);
+        Ok(())
+    }
+
+    /// Appends the result of the game to the game log file.
+    fn log_result(&self, winner: Option<usize>) -> Result<()> {
+        if let Some(path) = &self.config.game_log_path {
+            let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+            let record = self.result_record(winner, timestamp);
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            file.write_all(record.as_bytes())?;
         }
         Ok(())
     }
@@ -50,6 +1023,9 @@ impl Game {
     /// Shows countdown to players for starting the game.
     fn show_countdown(&mut self) -> Result<()> {
         println!("[#] Game is starting.");
+        let message = format!("Game seed: {}\n", self.seed);
+        print!("[#] {}", message);
+        self.players.iter_mut().try_for_each(|p| p.send(&message))?;
         for i in 1..4 {
             let message = format!("Game starts in {}...\n", 4 - i);
             self.players.iter_mut().try_for_each(|p| p.send(&message))?;
@@ -58,151 +1034,3064 @@ impl Game {
         Ok(())
     }
 
+    /// Warns the players about the grids that leave (almost) no water.
+    ///
+    /// The game is still played as usual since every shot is a hit or a sink,
+    /// see [`HIGH_COVERAGE_RATIO`].
+    fn warn_about_coverage(&mut self) -> Result<()> {
+        let names = self
+            .players
+            .iter()
+            .filter(|player| player.grid().coverage_ratio() >= HIGH_COVERAGE_RATIO)
+            .map(|player| player.name().to_string())
+            .collect::<Vec<String>>();
+        if names.is_empty() {
+            return Ok(());
+        }
+        let message = format!(
+            "Warning: ships cover almost the whole grid of {}, nearly every shot will hit.\n",
+            names.join(", ")
+        );
+        print!("[!] {}", message);
+        self.players.iter_mut().try_for_each(|player| player.send(&message))
+    }
+
+    /// Returns the description of the game that is sent before the first turn.
+    ///
+    /// It has the players, board size, fleet, rules in effect and the seed
+    /// so that the clients can render the empty board before the shots.
+    /// The fleet is omitted if [`Config::hide_ship_count`] is set.
+    pub fn info(&self) -> String {
+        let names = self.players.iter().map(|p| p.name()).collect::<Vec<&str>>();
+        let sizes = self
+            .players
+            .iter()
+            .map(|p| format!("{}x{}", p.grid().width, p.grid().height))
+            .collect::<Vec<String>>();
+        let board = if sizes.windows(2).all(|pair| pair[0] == pair[1]) {
+            sizes.first().cloned().unwrap_or_default()
+        } else {
+            names
+                .iter()
+                .zip(&sizes)
+                .map(|(name, size)| format!("{} {}", name, size))
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+        let mut message = format!("Players: {}\nBoard: {}\n", names.join(", "), board);
+        if !self.config.hide_ship_count {
+            let counts = self.players.first().map(|p| p.grid().ship_count_by_size());
+            let fleet = counts
+                .iter()
+                .flat_map(|counts| counts.iter().rev())
+                .flat_map(|(size, count)| iter::repeat(size.to_string()).take(*count))
+                .collect::<Vec<String>>();
+            message += &format!("Fleet: {}\n", fleet.join(", "));
+        }
+        let mut rules = [
+            (self.config.manual_placement, "manual placement"),
+            (self.config.no_touch, "no touching ships"),
+            (self.config.practice_mode, "practice mode"),
+            (self.config.proximity_hints, "proximity hints"),
+            (self.config.radar, "radar"),
+            (self.config.reveal_on_sink, "reveal on sink"),
+            (self.config.column_reveal, "column reveal"),
+            (self.config.decoys, "decoys"),
+            (self.config.invalid_input == InputPolicy::Strict, "strict input"),
+            (self.config.confirm_shots, "confirm shots"),
+            (self.config.display_policy == DisplayPolicy::AllVisible, "all visible"),
+        ]
+        .iter()
+        .filter(|(enabled, _)| *enabled)
+        .map(|(_, rule)| rule.to_string())
+        .collect::<Vec<String>>();
+        if self.config.shots_per_turn > 1 {
+            rules.push(format!("{} shots per turn", self.config.shots_per_turn));
+        }
+        let rules = if rules.is_empty() { String::from("standard") } else { rules.join(", ") };
+        message += &format!("Rules: {}\nSeed: {}\n", rules, self.seed);
+        message
+    }
+
     /// Shows the grid of the players.
     ///
     /// Hits/misses are shown on the upper grid.
     /// Lower grid is used for showing the player ships.
-    fn show_grid(&mut self, width: u8, height: u8) -> Result<()> {
-        for i in 0..MAX_PLAYERS {
+    /// The grids are shown side by side instead if [`Config::side_by_side`] is set.
+    /// The ships of the opponent are shown to everyone in teaching mode
+    /// and to the players that used the `reveal` command in practice mode.
+    fn show_grid(&mut self) -> Result<()> {
+        let glyphs = &self.config.glyphs;
+        for i in 0..self.players.len() {
+            let show_ships = self.config.display_policy == DisplayPolicy::AllVisible
+                || self.revealed.contains(&i);
+            let target = self.target_index(i);
+            let last_shot = target.and_then(|target| self.last_shots.get(&(i, target)).copied());
+            if let (Some(target), true) = (target, self.config.side_by_side) {
+                let enemy = self.players[target].grid();
+                let own = self.players[i].grid();
+                let message = side_by_side(
+                    ("Enemy waters", enemy, &enemy.tracking_rows(last_shot, show_ships, glyphs)),
+                    ("Your fleet", own, &own.rows_with_glyphs(true, glyphs)),
+                );
+                self.players[i].send_grid(&message)?;
+                continue;
+            }
+
             // Show upper grid (hits/misses).
-            let ships = self.opponent(i)
-                .grid
-                .hits
-                .iter()
-                .map(|coord| Ship {
-                    coords: vec![Coordinate {
-                        x: coord.x,
-                        y: coord.y,
-                        is_hit: self.opponent(i)
-                            .grid
-                            .ships
-                            .iter()
-                            .any(|ship| ship.coords.contains(coord)),
-                    }],
-                    ..Default::default()
-                })
-                .collect();
-            let grid_str = Grid {
-                width,
-                height,
-                ships,
-                hits: vec![]
+            if let Some(target) = target {
+                let grid = self.players[target].grid();
+                let grid_str = if show_ships {
+                    grid.as_revealed_tracking_string(last_shot, glyphs)?
+                } else {
+                    grid.as_tracking_string(last_shot, glyphs)?
+                };
+                self.players[i].send_grid(&grid_str)?;
             }
-            .as_string(false)?;
-            self.players[i].send(&grid_str)?;
 
             // Show lower grid (ships).
             self.players[i].send("\nYour grid:")?;
-            let grid_str = self.players[i].grid.as_string(true)?;
-            self.players[i].send(&grid_str)?;
+            let grid_str = self.players[i].grid().as_string_with_glyphs(true, glyphs)?;
+            self.players[i].send_grid(&grid_str)?;
         }
         Ok(())
     }
 
     /// Starts the game.
     ///
-    /// Number of players is determined by [`MAX_PLAYERS`] constant.
-    /// Game loop continues until one of the players hits all of the ships of the opponent.
-    /// Lower and upper grids are shown along with extra messages during the gameplay.
-    pub fn start(&mut self, grid_width: u8, grid_height: u8) -> Result<()> {
+    /// Number of players is determined by [`Config::players_per_game`].
+    /// Each player shoots at the next player in the ring unless they pick another target
+    /// with the `target` command, defeated players are skipped.
+    /// Game loop continues until only one of the players has ships left.
+    /// Each turn is played via [`Game::play_turn`], starting from the first player
+    /// (see [`Game::resume`]).
+    pub fn start(&mut self) -> Result<()> {
+        if let Ok(mut progress) = self.progress.lock() {
+            progress.last_game = None;
+        }
+        self.winner = None;
         self.show_countdown()?;
-        'game: loop {
-            let mut i = 0;
-            while i < MAX_PLAYERS {
-                // Check if the player has won.
-                if self.players[i].grid.ships.iter().all(|ship| ship.is_sunk()) {
-                    let message = format!("{} won.\n", self.opponent(i).name);
-                    self.players[i].send(&message)?;
-                    self.opponent_mut(i).send("You won!\n")?;
-                    self.players.clear();
-                    print!("[#] {}", message);
-                    break 'game;
-                }
+        let info = self.info();
+        self.players.iter_mut().try_for_each(|player| player.send(&info))?;
+        self.warn_about_coverage()?;
+        if self.config.practice_mode {
+            let hint_count = self.config.hint_count;
+            self.players.iter_mut().try_for_each(|player| {
+                player.set_hints(hint_count);
+                player.send(&format!(
+                    "Practice mode: type \"hint\" to reveal a ship ({} uses).\n",
+                    hint_count
+                ))
+            })?;
+        }
+        if self.config.display_policy == DisplayPolicy::AllVisible {
+            let message = "Teaching mode: all ships are visible to everyone.\n";
+            self.players.iter_mut().try_for_each(|player| player.send(message))?;
+        }
+        self.apply_handicaps()?;
+        if self.config.column_reveal {
+            self.reveal_columns()?;
+        }
+        let first = self.first_player_index();
+        if self.config.random_first_player {
+            let message = format!("{} goes first.\n", self.players[first].name());
+            print!("[#] {}", message);
+            self.players.iter_mut().try_for_each(|player| player.send(&message))?;
+        }
+        self.resume(first)
+    }
+
+    /// Returns the index of the player who shoots first.
+    ///
+    /// It is the first player to join unless [`Config::random_first_player`] is set,
+    /// then the player is picked with the game seed.
+    fn first_player_index(&self) -> usize {
+        if self.config.random_first_player && !self.players.is_empty() {
+            Rng::with_seed(self.seed).usize(..self.players.len())
+        } else {
+            0
+        }
+    }
+
+    /// Enters the turn loop at the turn of the given player.
+    ///
+    /// Restored games continue from the player whose turn it was,
+    /// the countdown and the other preparations of [`Game::start`] are skipped.
+    pub fn resume(&mut self, start_index: usize) -> Result<()> {
+        if start_index >= self.players.len() {
+            return Err(format!("[!] Invalid player index: {}", start_index).into());
+        }
+        let mut i = start_index;
+        while !self.check_game_over()? {
+            // Tell the players about the spectators that joined or left.
+            self.announce_spectators()?;
 
-                // Show the grid.
-                self.show_grid(grid_width, grid_height)?;
+            // Skip the defeated players.
+            if self.players[i].grid().is_defeated() {
+                i = self.opponent_index(i).unwrap_or(i);
+                continue;
+            }
 
-                // Handle the player turn.
-                {
-                    let msg = format!("Your turn to shoot {}: ", self.opponent(i).name);
-                    self.players[i].send(&msg)?;
+            // Skip the disconnected players until they reconnect or run out of time.
+            if let Some(since) = self.disconnected_since.get(&i).copied() {
+                if !self.reconnect(i)? {
+                    let grace = self.disconnect_grace().unwrap_or_default();
+                    if since.elapsed() >= grace {
+                        self.disconnected_since.remove(&i);
+                        if let Ok(mut reconnections) = self.reconnections.lock() {
+                            reconnections.remove(self.players[i].name());
+                        }
+                        if self.eliminate_player(i, "did not reconnect in time")? {
+                            break;
+                        }
+                    } else if (0..self.players.len()).all(|j| {
+                        self.disconnected_since.contains_key(&j)
+                            || self.players[j].grid().is_defeated()
+                    }) {
+                        thread::sleep(RECONNECT_POLL_INTERVAL);
+                    }
+                    i = self.opponent_index(i).unwrap_or(i);
+                    continue;
                 }
-                let message = format!("{}'s turn.\n", self.players[i].name);
-                print!("[#] {}", message);
-                for j in 0..self.players.len() {
+            }
+
+            // Handle the player turn and check the outcome right away
+            // so the game does not wait for the next turn to end.
+            match self.play_turn(i) {
+                Ok(outcome) if outcome.advances_turn() => i = self.opponent_index(i).unwrap_or(i),
+                Ok(_) => {}
+                Err(e) => {
+                    if self.hold_disconnected_players(e.as_ref())? {
+                        if self.disconnected_since.contains_key(&i) {
+                            i = self.opponent_index(i).unwrap_or(i);
+                        }
+                    } else if is_disconnect(e.as_ref()) {
+                        // The disconnected players forfeit and the survivors play on.
+                        let disconnected = (0..self.players.len())
+                            .filter(|j| {
+                                !self.players[*j].grid().is_defeated()
+                                    && !self.players[*j].is_alive()
+                            })
+                            .collect::<Vec<usize>>();
+                        if disconnected.is_empty() {
+                            return Err(e);
+                        }
+                        // Replace all of them first so the announcements are not sent to them.
+                        for j in disconnected.iter() {
+                            let absent = AbsentPlayer::new(self.players[*j].as_mut());
+                            self.players[*j] = Box::new(absent);
+                        }
+                        for j in disconnected {
+                            if self.remove_player(j)? {
+                                return Ok(());
+                            }
+                        }
+                        if self.players[i].grid().is_defeated() {
+                            i = self.opponent_index(i).unwrap_or(i);
+                        }
+                    } else {
+                        return Err(e);
+                    }
+                }
+            }
+            self.current_player = i;
+            if let Some(path) = &self.config.save_path {
+                if let Err(e) = self.save(path) {
+                    eprintln!("[!] Failed to save the game: {}", e);
+                }
+            }
+            if self.check_game_over()? {
+                break;
+            }
+            self.wait_between_turns();
+        }
+        Ok(())
+    }
+
+    /// Eliminates the player at the given index, e.g. when they surrender or are kicked.
+    ///
+    /// The ships of the player are sunk so that they are skipped in the turn ring,
+    /// the reason is announced to the other players and the spectators.
+    /// Returns `true` if the game is over, i.e. there is only one survivor left.
+    pub fn eliminate_player(&mut self, index: usize, reason: &str) -> Result<bool> {
+        match self.players.get(index) {
+            Some(player) if player.grid().is_defeated() => {
+                return Err(format!("[!] {} is already defeated.", player.name()).into());
+            }
+            Some(_) => {}
+            None => return Err(format!("[!] Invalid player index: {}", index).into()),
+        }
+        self.players[index].grid_mut().sink_all_ships();
+        self.eliminated_at.insert(index, self.turn_count);
+        self.update_progress(true);
+        self.announce_elimination(index, Some(reason))?;
+        self.check_game_over()
+    }
+
+    /// Removes the player at the given index from the game after a failed read or write.
+    ///
+    /// The player is replaced with an [`AbsentPlayer`] so that the indices of the others
+    /// stay the same, then they forfeit, see [`Game::eliminate_player`].
+    /// Returns `true` if the game is over.
+    pub fn remove_player(&mut self, index: usize) -> Result<bool> {
+        if index >= self.players.len() {
+            return Err(format!("[!] Invalid player index: {}", index).into());
+        }
+        let absent = AbsentPlayer::new(self.players[index].as_mut());
+        self.players[index] = Box::new(absent);
+        self.eliminate_player(index, "disconnected")
+    }
+
+    /// Announces to everyone that the player at the given index has been eliminated.
+    ///
+    /// The reason is omitted if the player was defeated by the shots.
+    fn announce_elimination(&mut self, index: usize, reason: Option<&str>) -> Result<()> {
+        let name = self.players[index].name();
+        let message = match reason {
+            Some(reason) => format!("{} has been eliminated: {}.\n", name, reason),
+            None => format!("{} has been eliminated.\n", name),
+        };
+        print!("[#] {}", message);
+        for player in self.players.iter_mut() {
+            player.send(&message)?;
+        }
+        self.broadcast_to_spectators(&message);
+        Ok(())
+    }
+
+    /// Returns the time that the disconnected players have to reconnect.
+    ///
+    /// It is [`Config::disconnect_grace`] unless the game is restored, see [`Game::load`].
+    fn disconnect_grace(&self) -> Option<Duration> {
+        self.restore_grace.or(self.config.disconnect_grace)
+    }
+
+    /// Holds the places of the players that are disconnected for the configured grace period.
+    ///
+    /// The players are replaced with an [`AbsentPlayer`] and the others are told to wait.
+    /// Returns `false` if the error is not caused by a disconnect or the grace is not set,
+    /// see [`Config::disconnect_grace`].
+    fn hold_disconnected_players(&mut self, error: &(dyn Error + 'static)) -> Result<bool> {
+        if !is_disconnect(error) || self.disconnect_grace().is_none() {
+            return Ok(false);
+        }
+        let mut is_held = false;
+        for i in 0..self.players.len() {
+            if self.disconnected_since.contains_key(&i)
+                || self.players[i].grid().is_defeated()
+                || self.players[i].is_alive()
+            {
+                continue;
+            }
+            let absent = AbsentPlayer::new(self.players[i].as_mut());
+            self.players[i] = Box::new(absent);
+            self.disconnected_since.insert(i, Instant::now());
+            let name = self.players[i].name().to_string();
+            let token = self.reconnection_token(i).unwrap_or_default().to_string();
+            if let Ok(mut reconnections) = self.reconnections.lock() {
+                reconnections.insert(name.clone(), (token, None));
+            }
+            let message = format!("Waiting for {} to reconnect...\n", name);
+            print!("[#] {}", message);
+            for player in self.players.iter_mut() {
+                player.send(&message)?;
+            }
+            self.broadcast_to_spectators(&message);
+            is_held = true;
+        }
+        Ok(is_held)
+    }
+
+    /// Gives the place of the disconnected player back to their new connection if there is one.
+    ///
+    /// Returns `true` if the player is reconnected.
+    fn reconnect(&mut self, i: usize) -> Result<bool> {
+        let name = self.players[i].name().to_string();
+        let player = match self.reconnections.lock() {
+            Ok(mut reconnections) => match reconnections.get_mut(&name).and_then(|r| r.1.take()) {
+                Some(player) => {
+                    reconnections.remove(&name);
+                    player
+                }
+                None => return Ok(false),
+            },
+            Err(_) => return Ok(false),
+        };
+        let mut player = player;
+        *player.grid_mut() = mem::replace(self.players[i].grid_mut(), Grid::default());
+        player.set_hints(self.players[i].hints());
+        self.players[i] = player;
+        self.disconnected_since.remove(&i);
+        let message = format!("{} reconnected.\n", name);
+        print!("[#] {}", message);
+        for (j, player) in self.players.iter_mut().enumerate() {
+            if j != i {
+                player.send(&message)?;
+            }
+        }
+        self.broadcast_to_spectators(&message);
+        self.players[i].send("Welcome back! The game goes on.\n")?;
+        Ok(true)
+    }
+
+    /// Ends the game if there is a winner or all of the players are defeated.
+    ///
+    /// The ranking is sent before the result, see [`Game::ranking`].
+    ///
+    /// Returns `true` if the game is over.
+    fn check_game_over(&mut self) -> Result<bool> {
+        if let Some(winner) = self.winner_index() {
+            self.send_ranking()?;
+            let message = format!("{} won.\n", self.players[winner].name());
+            for j in 0..self.players.len() {
+                if j != winner {
+                    self.players[j].send(&message)?;
+                }
+            }
+            self.players[winner].send("You won!\n")?;
+            print!("[#] {}", message);
+            self.send_result_line(Some(winner))?;
+            self.end(Some(winner));
+            Ok(true)
+        } else if self.players.iter().all(|player| player.grid().is_defeated()) {
+            self.send_ranking()?;
+            for player in self.players.iter_mut() {
+                player.send("Draw.\n")?;
+            }
+            println!("[#] Draw.");
+            self.send_result_line(None)?;
+            self.end(None);
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// Waits for the configured delay between the turns.
+    ///
+    /// Returns `false` without sleeping if the delay is zero.
+    fn wait_between_turns(&self) -> bool {
+        if self.config.turn_delay == Duration::from_secs(0) {
+            return false;
+        }
+        thread::sleep(self.config.turn_delay);
+        true
+    }
+
+    /// Lets each player reveal whether one of their columns has a ship.
+    ///
+    /// The answer is broadcast to the other players, an empty input skips the reveal.
+    fn reveal_columns(&mut self) -> Result<()> {
+        for i in 0..self.players.len() {
+            let player = &mut self.players[i];
+            let column = loop {
+                player.send(
+                    "Reveal one of your columns to the opponents (e.g. C) or press enter to skip: ",
+                )?;
+                let input = player.read()?.to_lowercase();
+                if input.is_empty() {
+                    break None;
+                }
+                let column = ALPHABET
+                    .chars()
+                    .position(|c| input == c.to_string())
+                    .map(|i| i as u8 + 1)
+                    .filter(|column| *column <= player.grid().width);
+                match column {
+                    Some(column) => break Some((column, input.to_uppercase())),
+                    None => player.send("Invalid column, try again.\n")?,
+                }
+            };
+            if let Some((column, column_name)) = column {
+                let message = format!(
+                    "{} revealed that column {} {}.\n",
+                    self.players[i].name(),
+                    column_name,
+                    if self.players[i].grid().scan_col(column) {
+                        "has a ship"
+                    } else {
+                        "has no ships"
+                    }
+                );
+                print!("[#] {}", message);
+                for j in 0..self.players.len() {
                     if j != i {
                         self.players[j].send(&message)?;
                     }
                 }
-                
-                // Parse the grid coordinate.
-                let coordinate_str = self.players[i].read()?;
-                let coordinate =
-                    if let Ok(coordinate) = Coordinate::try_from(coordinate_str.to_string()) {
-                        println!(
-                            "[#] {} is firing a shot: {} ({:?})",
-                            self.players[i].name, coordinate_str, coordinate
-                        );
-                        coordinate
-                    } else {
-                        self.players[i].send("Your missile went to space!\n")?;
-                        continue;
-                    };
+            }
+        }
+        Ok(())
+    }
+
+    /// Gives the bonus hints of the configured handicaps to the players.
+    ///
+    /// Handicaps are given per turn order, e.g. `[0, 1]` gives one hint to the second player.
+    fn apply_handicaps(&mut self) -> Result<()> {
+        for (player, bonus) in self.players.iter_mut().zip(self.config.handicaps.iter()) {
+            if *bonus > 0 {
+                player.set_hints(player.hints() + bonus);
+                player.send(&format!(
+                    "Handicap: you have {} bonus hints, type \"hint\" to reveal a ship.\n",
+                    bonus
+                ))?;
+                println!("[#] {} has {} bonus hints.", player.name(), bonus);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the status line of the player at the given index.
+    ///
+    /// It is e.g. `Turn 5 | Your ships: 2 | Bob: 1 | Accuracy: 50% (1/2)`,
+    /// the ships of the opponents are hidden if [`Config::hide_ship_count`] is set.
+    pub fn status_line(&self, i: usize) -> String {
+        let ships_remaining = |grid: &Grid| {
+            grid.ships.iter().filter(|ship| !ship.decoy && !ship.is_sunk()).count()
+        };
+        let mut s = format!(
+            "Turn {} | Your ships: {}",
+            self.turn_count + 1,
+            ships_remaining(self.players[i].grid())
+        );
+        for (j, player) in self.players.iter().enumerate() {
+            if j == i {
+                continue;
+            }
+            if self.config.hide_ship_count && !player.grid().is_defeated() {
+                s += &format!(" | {}: ?", player.name());
+            } else {
+                s += &format!(" | {}: {}", player.name(), ships_remaining(player.grid()));
+            }
+        }
+        let (shots, hits) = match self.gallery.lock() {
+            Ok(gallery) => {
+                let shots = gallery.moves.iter().filter(|m| m.shooter == i).collect::<Vec<_>>();
+                (shots.len(), shots.iter().filter(|m| m.is_hit()).count())
+            }
+            Err(_) => (0, 0),
+        };
+        match (hits * 100).checked_div(shots) {
+            Some(accuracy) => s += &format!(" | Accuracy: {}% ({}/{})", accuracy, hits, shots),
+            None => s += " | Accuracy: -",
+        }
+        s
+    }
+
+    /// Plays a single turn of the given player.
+    ///
+    /// Grids are shown to the players and the shot of the player is resolved.
+    /// With more than one [`Config::shots_per_turn`], the shots are resolved one by one
+    /// and a [`TurnOutcome::Volley`] is returned once all of them are fired.
+    /// Returns an error if the player has no opponents left.
+    /// Also see [`TurnOutcome::advances_turn`].
+    pub fn play_turn(&mut self, shooter: usize) -> Result<TurnOutcome> {
+        let shots_per_turn = self.config.shots_per_turn;
+        if shots_per_turn <= 1 {
+            return self.play_shot(shooter);
+        }
+        let (mut shots, mut hits) = (0, 0);
+        let mut announced = None;
+        while shots < shots_per_turn {
+            if announced != Some(shots) {
+                let message = format!("Shot {} of {}.\n", shots + 1, shots_per_turn);
+                self.players[shooter].send(&message)?;
+                announced = Some(shots);
+            }
+            match self.play_shot(shooter)? {
+                TurnOutcome::Hit | TurnOutcome::Sunk => {
+                    shots += 1;
+                    hits += 1;
+                }
+                TurnOutcome::Missed => shots += 1,
+                // Lost turns end the volley early.
+                outcome if outcome.advances_turn() => return Ok(outcome),
+                _ => {}
+            }
+            if self.winner_index().is_some() {
+                break;
+            }
+        }
+        Ok(TurnOutcome::Volley { hits })
+    }
+
+    /// Plays a single shot of the given player.
+    ///
+    /// The turn is forfeited as a miss once the player reaches the [`Config::reprompt_limit`]
+    /// with invalid inputs, the commands that do not end the turn are not counted.
+    fn play_shot(&mut self, shooter: usize) -> Result<TurnOutcome> {
+        let outcome = self.take_shot(shooter).map_err(|e| {
+            self.turn_deadline = None;
+            e
+        })?;
+        match outcome {
+            TurnOutcome::Invalid { advances: false } => self.reprompts += 1,
+            TurnOutcome::Command { advances: false } => return Ok(outcome),
+            _ => {
+                self.reprompts = 0;
+                self.turn_deadline = None;
+                return Ok(outcome);
+            }
+        }
+        match self.config.reprompt_limit {
+            Some(limit) if self.reprompts >= limit => {
+                self.reprompts = 0;
+                self.turn_deadline = None;
+                println!("[#] {} reached the re-prompt limit.", self.players[shooter].name());
+                self.players[shooter].send("Too many attempts! Your turn counts as a miss.\n")?;
+                Ok(TurnOutcome::Missed)
+            }
+            _ => Ok(outcome),
+        }
+    }
+
+    /// Reads the input of the player in their turn, see [`Config::turn_timeout`].
+    ///
+    /// The deadline is set by the first read of the turn, so the commands
+    /// and the confirmation of the shot do not extend the turn.
+    fn read_turn_input(&mut self, shooter: usize) -> Result<String> {
+        let timeout = match self.config.turn_timeout {
+            Some(timeout) => timeout,
+            None => return self.players[shooter].read(),
+        };
+        let now = Instant::now();
+        let deadline = *self.turn_deadline.get_or_insert(now + timeout);
+        if now >= deadline {
+            return Err(IoError::new(ErrorKind::TimedOut, "no input in time").into());
+        }
+        self.players[shooter].read_with_timeout(deadline - now)
+    }
+
+    /// Forfeits the shot of the player that ran out of time.
+    ///
+    /// Running out of time is not a disconnect, the turn passes on as a miss.
+    fn forfeit_shot(&mut self, shooter: usize) -> Result<TurnOutcome> {
+        println!("[#] {} ran out of time.", self.players[shooter].name());
+        self.players[shooter].send("\nTime's up, you forfeited your shot.\n")?;
+        Ok(TurnOutcome::Missed)
+    }
+
+    /// Lets the player take a shot or run a command, see [`Game::play_shot`].
+    fn take_shot(&mut self, shooter: usize) -> Result<TurnOutcome> {
+        let target = match self.target_index(shooter) {
+            Some(target) => target,
+            None => {
+                let name = self.players[shooter].name();
+                return Err(format!("[!] {} has no opponents left.", name).into());
+            }
+        };
+
+        // Show the grid.
+        self.update_progress(true);
+        if let Ok(mut progress) = self.progress.lock() {
+            progress.current_player = Some(self.players[shooter].name().to_string());
+        }
+        if self.config.status_line {
+            let message = format!("{}\n", self.status_line(shooter));
+            self.players[shooter].send(&message)?;
+        }
+        self.show_grid()?;
+
+        // Handle the player turn.
+        let target_name = self.players[target].name().to_string();
+        {
+            let grid = self.players[target].grid();
+            let (width, height, origin) = (grid.width, grid.height, grid.origin);
+            self.players[shooter].set_target(&target_name, width, height, origin);
+            let msg = format!("Your turn to shoot {}: ", target_name);
+            self.players[shooter].send(&msg)?;
+        }
+        let mut message = format!("{}'s turn.\n", self.players[shooter].name());
+        print!("[#] {}", message);
+        if self.players.iter().filter(|player| !player.grid().is_defeated()).count() > 2 {
+            message += &format!("Turn order: {}\n", self.turn_order(shooter));
+        }
+        let waiting =
+            format!("Waiting for {} to take their shot...\n", self.players[shooter].name());
+        for j in 0..self.players.len() {
+            if j != shooter {
+                self.players[j].send(&format!("{}{}", message, waiting))?;
+            }
+        }
+        self.broadcast_to_spectators(&message);
+        
+        // Parse the grid coordinate, the players on autoplay fire without being asked.
+        let mut coordinate_str = if self.autoplay.contains(&shooter) {
+            String::from("auto")
+        } else {
+            let prompted_at = Instant::now();
+            let input = self.read_turn_input(shooter);
+            self.turn_times.entry(shooter).or_default().record(prompted_at.elapsed());
+            match input {
+                Err(e) if is_timeout(e.as_ref()) => return self.forfeit_shot(shooter),
+                input => input?,
+            }
+        };
+        if coordinate_str.eq_ignore_ascii_case("autofinish") {
+            self.autoplay.insert(shooter);
+            println!("[#] {} switched to autoplay.", self.players[shooter].name());
+            let message = "Your shots are fired at random cells for the rest of the game.\n";
+            self.players[shooter].send(message)?;
+            coordinate_str = String::from("auto");
+        }
+        let invalid_inputs = self.players[shooter].invalid_inputs();
+        if coordinate_str.eq_ignore_ascii_case("help") {
+            let help = self.help(shooter);
+            self.players[shooter].send(&help)?;
+            return Ok(TurnOutcome::Command { advances: false });
+        }
+        if coordinate_str.eq_ignore_ascii_case("fleet") {
+            let status = self.fleet_status(shooter);
+            self.players[shooter].send(&status)?;
+            return Ok(TurnOutcome::Command { advances: false });
+        }
+        if coordinate_str.eq_ignore_ascii_case("hint") {
+            let advances = self.use_hint(shooter)?;
+            return Ok(TurnOutcome::Command { advances });
+        }
+        if coordinate_str.eq_ignore_ascii_case("reveal") {
+            self.toggle_reveal(shooter, true)?;
+            return Ok(TurnOutcome::Command { advances: false });
+        }
+        if coordinate_str.eq_ignore_ascii_case("hide") {
+            self.toggle_reveal(shooter, false)?;
+            return Ok(TurnOutcome::Command { advances: false });
+        }
+        let mut words = coordinate_str.splitn(2, ' ');
+        let command = words.next().unwrap_or_default();
+        if command.eq_ignore_ascii_case("target") {
+            self.pick_target(shooter, words.next().unwrap_or_default().trim())?;
+            return Ok(TurnOutcome::Command { advances: false });
+        }
+        if command.eq_ignore_ascii_case("say") {
+            self.say(shooter, words.next().unwrap_or_default())?;
+            return Ok(TurnOutcome::Command { advances: false });
+        }
+        let coordinate = if coordinate_str.eq_ignore_ascii_case("auto") {
+            match self.players[target].grid().random_unfired_coordinate() {
+                Some(coordinate) => {
+                    let label = self.players[target].grid().coordinate_label(coordinate);
+                    self.players[shooter].send(&format!("Firing at {}.\n", label))?;
+                    Some(coordinate)
+                }
+                None => {
+                    self.players[shooter].send("There are no cells left to fire at.\n")?;
+                    return Ok(TurnOutcome::Command { advances: false });
+                }
+            }
+        } else {
+            self.players[target].grid().parse_coordinate(&coordinate_str)
+        };
+        // Shots are range checked against the target grid since the grid sizes can differ.
+        let coordinate = coordinate.filter(|c| self.players[target].grid().contains(c));
+        // Out-of-range shots follow the same policy as the invalid input.
+        let coordinate = if let Some(coordinate) = coordinate {
+            println!(
+                "[#] {} is firing a shot: {} ({:?})",
+                self.players[shooter].name(), coordinate_str, coordinate
+            );
+            coordinate
+        } else {
+            let advances = self.config.invalid_input == InputPolicy::Strict;
+            let message = if advances {
+                "Your missile went to space! You lost your turn.\n"
+            } else {
+                "Your missile went to space! Try again.\n"
+            };
+            self.players[shooter].send(message)?;
+            return self.penalize_invalid_input(shooter, invalid_inputs + 1, advances);
+        };
+
+        // Repeated shots would waste the turn or count a hit twice.
+        // They still count as invalid inputs so that they do not reset the penalty.
+        if self.players[target].grid().is_shot_at(&coordinate) {
+            self.players[shooter].send("You already fired there, try again.\n")?;
+            return self.penalize_invalid_input(shooter, invalid_inputs + 1, false);
+        }
+
+        // Confirm the shot, the players on autoplay are not asked.
+        let label = self.players[target].grid().coordinate_label(coordinate);
+        if self.config.confirm_shots && !self.autoplay.contains(&shooter) {
+            self.players[shooter].send(&format!("Fire at {}? (y/n) ", label))?;
+            let answer = match self.read_turn_input(shooter) {
+                Err(e) if is_timeout(e.as_ref()) => return self.forfeit_shot(shooter),
+                answer => answer?.to_lowercase(),
+            };
+            if answer != "y" && answer != "yes" {
+                self.players[shooter].send("Shot cancelled.\n")?;
+                return Ok(TurnOutcome::Command { advances: false });
+            }
+        }
+
+        // Only an accepted shot resets the invalid inputs, the commands do not.
+        self.players[shooter].set_invalid_inputs(0);
+
+        // Handle hit/miss.
+        let outcome = self.players[target].grid_mut().fire_at(coordinate);
+        self.players[shooter].on_shot(&target_name, coordinate, &outcome);
+        self.turn_count += 1;
+        self.last_shots.insert((shooter, target), coordinate);
+        let sunk_kind = match &outcome {
+            ShotOutcome::Sunk { kind, .. } => Some(*kind),
+            _ => None,
+        };
+        let is_sunk = sunk_kind.is_some();
+        let is_hit = if is_sunk || outcome == ShotOutcome::Hit {
+            self.players[shooter].send("Hit!\n")?;
+            true
+        } else {
+            let grid = self.players[target].grid();
+            let mut message = String::from("Missed.");
+            if self.config.proximity_hints {
+                message += &format!(" Your shot was {}.", grid.proximity_hint(&coordinate));
+            }
+            if self.config.radar {
+                let count = grid.adjacent_ship_count(&coordinate);
+                message += &format!(" Radar: {} ship cells nearby.", count);
+            }
+            message.push('\n');
+            self.players[shooter].send(&message)?;
+            false
+        };
+
+        // Name the ship that is sunk by this shot, the later hits do not sink it again.
+        if let Some(kind) = sunk_kind {
+            let ship = kind.name();
+            let message = if self.config.sink_shot_count {
+                // The current shot is not recorded yet.
+                let shots = self.shot_count(shooter, target) + 1;
+                format!(
+                    "You sank {}'s {} after {} total shots at them.\n",
+                    self.players[target].name(),
+                    ship,
+                    shots
+                )
+            } else {
+                format!("You sank {}'s {}!\n", self.players[target].name(), ship)
+            };
+            self.players[shooter].send(&message)?;
+        }
+
+        // The surrounding cells are only guaranteed to be water if the ships cannot touch.
+        if is_sunk && self.config.reveal_on_sink && self.config.no_touch {
+            self.players[target].grid_mut().reveal_around_ship(&coordinate);
+        }
+
+        // Inform about the game stats.
+        if let Some(message) = self.ships_remaining_message(shooter, is_sunk) {
+            self.players[shooter].send(&message)?;
+        }
+        let mut message = format!("{} is firing at {}\n", self.players[shooter].name(), label);
+        if let Some(kind) = sunk_kind {
+            message += &format!("{} sank your {}!\n", self.players[shooter].name(), kind.name());
+        }
+        self.players[target].send(&message)?;
+
+        // Announce the elimination if the game continues between the survivors.
+        if is_sunk && self.players[target].grid().is_defeated() {
+            self.eliminated_at.insert(target, self.turn_count);
+            if self.winner_index().is_none() {
+                self.announce_elimination(target, None)?;
+            }
+        }
+
+        let outcome = if is_sunk {
+            TurnOutcome::Sunk
+        } else if is_hit {
+            TurnOutcome::Hit
+        } else {
+            TurnOutcome::Missed
+        };
+        self.record_move(shooter, target, coordinate, outcome)?;
+        Ok(outcome)
+    }
+
+    /// Returns the number of shots that the shooter fired at the target so far.
+    ///
+    /// Shots are counted from the move history, see [`Gallery::moves`].
+    ///
+    /// [`Gallery::moves`]: crate::spectator::Gallery::moves
+    fn shot_count(&self, shooter: usize, target: usize) -> usize {
+        match self.gallery.lock() {
+            Ok(gallery) => {
+                gallery.moves.iter().filter(|m| m.shooter == shooter && m.target == target).count()
+            }
+            Err(_) => 0,
+        }
+    }
+
+    /// Records the shot in the move history and broadcasts it to the spectators.
+    fn record_move(
+        &mut self,
+        shooter: usize,
+        target: usize,
+        coordinate: Coordinate,
+        outcome: TurnOutcome,
+    ) -> Result<()> {
+        let shot = Move {
+            turn: self.turn_count,
+            shooter,
+            target,
+            coordinate,
+            outcome,
+        };
+        let line = self.describe_move(&shot);
+        let state = self.spectator_state(Some((target, coordinate)))?;
+        if let Ok(mut gallery) = self.gallery.lock() {
+            gallery.record(shot, line, state);
+        }
+        Ok(())
+    }
+
+    /// Returns the status line of the move with the names of the players, see [`Move::describe`].
+    fn describe_move(&self, shot: &Move) -> String {
+        let (shooter, target) = (&self.players[shot.shooter], &self.players[shot.target]);
+        shot.describe(shooter.name(), target.name(), target.grid())
+    }
+
+    /// Returns the grids of the players as seen by the spectators.
+    ///
+    /// The last shot is marked on the grid of its target if it is given
+    /// and each grid is followed by its checksum, see [`Grid::state_hash`].
+    fn spectator_state(&self, last_shot: Option<(usize, Coordinate)>) -> Result<String> {
+        let mut state = String::new();
+        for (i, player) in self.players.iter().enumerate() {
+            let last_shot = last_shot.filter(|(target, _)| *target == i).map(|(_, c)| c);
+            state += &format!("\n{}'s grid:", player.name());
+            state += &player.grid().as_tracking_string(last_shot, &self.config.glyphs)?;
+            state += &format!("Checksum: {:016x}\n", player.grid().state_hash());
+        }
+        Ok(state)
+    }
+
+    /// Sends the message to the spectators of the game.
+    fn broadcast_to_spectators(&self, message: &str) {
+        if let Ok(mut gallery) = self.gallery.lock() {
+            gallery.broadcast(message);
+        }
+    }
 
-                // Handle hit/miss.
-                self.opponent_mut(i).grid.hits.push(coordinate);
-                let is_hit = if let Some(coordinate) = self.opponent_mut(i)
-                    .grid
+    /// Tells the players how many spectators are watching if it changed since the last time.
+    ///
+    /// Nothing is sent unless [`Config::announce_spectators`] is set.
+    fn announce_spectators(&mut self) -> Result<()> {
+        if !self.config.announce_spectators {
+            return Ok(());
+        }
+        let count = match self.gallery.lock() {
+            Ok(gallery) => gallery.spectator_count(),
+            Err(_) => return Ok(()),
+        };
+        if count == self.spectator_count {
+            return Ok(());
+        }
+        self.spectator_count = count;
+        let message = match count {
+            0 => String::from("Nobody is watching anymore.\n"),
+            1 => String::from("1 person is now watching.\n"),
+            _ => format!("{} people are now watching.\n", count),
+        };
+        self.players.iter_mut().try_for_each(|player| player.send(&message))
+    }
+
+    /// Returns the message about the remaining ships of the opponent.
+    ///
+    /// If the ship count is hidden, only the sinks are announced.
+    fn ships_remaining_message(&self, i: usize, is_sunk: bool) -> Option<String> {
+        let opponent = self.opponent(i)?;
+        if self.config.hide_ship_count {
+            if is_sunk {
+                Some(format!("{} lost a ship.\n", opponent.name()))
+            } else {
+                None
+            }
+        } else {
+            Some(format!(
+                "{} has {} ships remaining.\n",
+                opponent.name(),
+                opponent
+                    .grid()
                     .ships
-                    .iter_mut()
-                    .find(|ship| ship.coords.contains(&coordinate))
-                    .and_then(|ship| ship.coords.iter_mut().find(|c| *c == &coordinate))
-                {
-                    coordinate.is_hit = true;
-                    self.players[i].send("Hit!\n")?;
-                    true
-                } else {
-                    self.players[i].send("Missed.\n")?;
-                    false
-                };
+                    .iter()
+                    .filter(|ship| !ship.is_sunk())
+                    .count()
+            ))
+        }
+    }
 
-                // Inform about the game stats.
-                let message = {
-                    let opponent = self.opponent(i);
-                    format!(
-                        "{} has {} ships remaining.\n",
-                        opponent.name,
-                        opponent
-                            .grid
-                            .ships
-                            .iter()
-                            .filter(|ship| !ship.is_sunk())
-                            .count()
-                    )
-                };
-                self.players[i].send(&message)?;
-                let message = format!("{} is firing at {}\n", self.players[i].name, coordinate);
-                self.opponent_mut(i).send(&message)?;
+    /// Returns the commands that are available to the player during their turn.
+    ///
+    /// Each command is given with its syntax and description,
+    /// commands of the disabled features are left out.
+    fn available_commands(&self, i: usize) -> Vec<(&'static str, &'static str)> {
+        let mut commands = vec![
+            ("<coordinate>", "fire a shot, e.g. A1"),
+            ("auto", "fire at a random cell"),
+            ("autofinish", "fire at random cells for the rest of the game"),
+            ("fleet", "show the status of your fleet"),
+        ];
+        if self.config.practice_mode || self.players[i].hints() > 0 {
+            commands.push(("hint", "reveal a ship of the opponent"));
+        }
+        if self.config.practice_mode {
+            commands.push(("reveal/hide", "show or hide the ships of the opponent"));
+        }
+        if self.living_opponent_count(i) > 1 {
+            commands.push(("target <name>", "choose the opponent to shoot at"));
+        }
+        commands.push(("say <message>", "send a message to the other players"));
+        commands.push(("help", "show the available commands"));
+        commands
+    }
+
+    /// Returns the list of the available commands for the player.
+    fn help(&self, i: usize) -> String {
+        let mut help = String::from("Available commands:\n");
+        for (syntax, description) in self.available_commands(i) {
+            help += &format!("- {}: {}\n", syntax, description);
+        }
+        help
+    }
 
-                if !is_hit {
-                    i += 1;
+    /// Returns the status of the player's own ships.
+    fn fleet_status(&self, i: usize) -> String {
+        let mut status = String::from("Your fleet:\n");
+        for ship in &self.players[i].grid().ships {
+            status += &format!(
+                "- {}: {}/{} intact\n",
+                if ship.decoy { "Decoy" } else { ship.type_.name() },
+                ship.cells_remaining(),
+                ship.size()
+            );
+        }
+        status
+    }
+
+    /// Applies the [`Config::invalid_input_penalty`] after too many consecutive invalid inputs.
+    ///
+    /// The player is warned one input before reaching the [`Config::invalid_input_limit`].
+    /// Disconnecting the player is reported as a [`ConnectionAborted`] error.
+    ///
+    /// [`ConnectionAborted`]: ErrorKind::ConnectionAborted
+    fn penalize_invalid_input(
+        &mut self,
+        i: usize,
+        invalid_inputs: usize,
+        advances: bool,
+    ) -> Result<TurnOutcome> {
+        self.players[i].set_invalid_inputs(invalid_inputs);
+        let limit = match self.config.invalid_input_limit {
+            Some(limit) => limit,
+            None => return Ok(TurnOutcome::Invalid { advances }),
+        };
+        let penalty = self.config.invalid_input_penalty;
+        if invalid_inputs >= limit {
+            self.players[i].set_invalid_inputs(0);
+            println!("[#] {} sent too many invalid inputs.", self.players[i].name());
+            match penalty {
+                InvalidInputPenalty::Forfeit => {
+                    self.players[i].send("Too many invalid inputs! You lost your turn.\n")?;
+                    Ok(TurnOutcome::Invalid { advances: true })
+                }
+                InvalidInputPenalty::Disconnect => {
+                    self.players[i].send("Too many invalid inputs! Disconnecting.\n")?;
+                    self.players[i].exit()?;
+                    let error = IoError::new(ErrorKind::ConnectionAborted, "too many invalid inputs");
+                    Err(error.into())
                 }
             }
+        } else {
+            if invalid_inputs + 1 == limit {
+                self.players[i].send(match penalty {
+                    InvalidInputPenalty::Forfeit => {
+                        "Warning: one more invalid input and you lose your turn.\n"
+                    }
+                    InvalidInputPenalty::Disconnect => {
+                        "Warning: one more invalid input and you will be disconnected.\n"
+                    }
+                })?;
+            }
+            Ok(TurnOutcome::Invalid { advances })
+        }
+    }
+
+    /// Relays the message of the player to the other players.
+    ///
+    /// Control characters are removed and the message is truncated to [`MAX_SAY_LENGTH`].
+    fn say(&mut self, i: usize, message: &str) -> Result<()> {
+        let message = message
+            .chars()
+            .filter(|c| !c.is_control())
+            .take(MAX_SAY_LENGTH)
+            .collect::<String>();
+        let message = message.trim();
+        if message.is_empty() {
+            return self.players[i].send("Usage: say <message>\n");
+        }
+        let message = format!("{} says: {}\n", self.players[i].name(), message);
+        print!("[#] {}", message);
+        for j in 0..self.players.len() {
+            if j != i {
+                self.players[j].send(&message)?;
+            }
         }
+        self.broadcast_to_spectators(&message);
         Ok(())
     }
 
-    fn opponent_index(&self, i: usize) -> usize {
-        (i + 1) % MAX_PLAYERS
+    /// Reveals an intact ship coordinate of the opponent.
+    ///
+    /// Hints are available in practice mode or as a handicap bonus.
+    /// Returns `true` if the hint is used, which consumes the turn.
+    fn use_hint(&mut self, i: usize) -> Result<bool> {
+        if !self.config.practice_mode && self.players[i].hints() == 0 {
+            self.players[i].send("Hints are only available in practice mode.\n")?;
+            return Ok(false);
+        }
+        if self.players[i].hints() == 0 {
+            self.players[i].send("You have no hints left.\n")?;
+            return Ok(false);
+        }
+        match self.opponent(i).and_then(|opponent| {
+            let grid = opponent.grid();
+            grid.random_intact_coordinate().map(|c| grid.coordinate_label(c))
+        }) {
+            Some(label) => {
+                let hints = self.players[i].hints();
+                self.players[i].set_hints(hints - 1);
+                let message = format!(
+                    "Hint: there is a ship at {} ({} hints left)\n",
+                    label, self.players[i].hints()
+                );
+                self.players[i].send(&message)?;
+                println!("[#] {} used a hint: {}", self.players[i].name(), label);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Shows or hides the ships of the opponent on the tracking grid of the player.
+    ///
+    /// It is only available in practice mode for learning where the shots missed,
+    /// it does not consume the turn.
+    fn toggle_reveal(&mut self, i: usize, reveal: bool) -> Result<()> {
+        if !self.config.practice_mode {
+            let message = "Revealing the ships is only available in practice mode.\n";
+            return self.players[i].send(message);
+        }
+        if reveal {
+            self.revealed.insert(i);
+            self.players[i].send("The ships of your opponent are now visible.\n")
+        } else {
+            self.revealed.remove(&i);
+            self.players[i].send("The ships of your opponent are now hidden.\n")
+        }
+    }
+
+    /// Returns the index of the next player in the ring that is not defeated yet.
+    ///
+    /// Players attack the next player in the ring and the turn passes to them on a miss.
+    /// Returns `None` if there are no other players left.
+    fn opponent_index(&self, i: usize) -> Option<usize> {
+        let player_count = self.players.len();
+        (1..player_count)
+            .map(|j| (i + j) % player_count)
+            .find(|j| !self.players[*j].grid().is_defeated())
+    }
+
+    /// Returns the index of the opponent that the player shoots at.
+    ///
+    /// It is the target that the player picked if it is not defeated yet,
+    /// otherwise the next player in the ring.
+    fn target_index(&self, i: usize) -> Option<usize> {
+        match self.targets.get(&i) {
+            Some(target) if *target != i && !self.players[*target].grid().is_defeated() => {
+                Some(*target)
+            }
+            _ => self.opponent_index(i),
+        }
+    }
+
+    /// Returns the number of opponents of the player that are not defeated yet.
+    fn living_opponent_count(&self, i: usize) -> usize {
+        (0..self.players.len())
+            .filter(|j| *j != i && !self.players[*j].grid().is_defeated())
+            .count()
     }
 
-    fn opponent(&self, i: usize) -> &Player {
-        let player_index = self.opponent_index(i);
-        &self.players[player_index]
+    /// Lets the player shoot at the opponent with the given name.
+    fn pick_target(&mut self, i: usize, name: &str) -> Result<()> {
+        let target = (0..self.players.len()).find(|j| {
+            *j != i
+                && self.players[*j].name().eq_ignore_ascii_case(name)
+                && !self.players[*j].grid().is_defeated()
+        });
+        match target {
+            Some(target) => {
+                self.targets.insert(i, target);
+                let message = format!("You are now targeting {}.\n", self.players[target].name());
+                self.players[i].send(&message)
+            }
+            None => {
+                let message = format!("There is no opponent named {} left.\n", name);
+                self.players[i].send(&message)
+            }
+        }
+    }
+
+    /// Returns the order of the turns starting from the given player, e.g. `Alice → Bob → Carol`.
+    ///
+    /// Defeated players are left out.
+    fn turn_order(&self, i: usize) -> String {
+        let player_count = self.players.len();
+        (0..player_count)
+            .map(|j| (i + j) % player_count)
+            .filter(|j| *j == i || !self.players[*j].grid().is_defeated())
+            .map(|j| self.players[j].name())
+            .collect::<Vec<&str>>()
+            .join(" → ")
+    }
+
+    /// Returns the index of the winner if there is only one player left.
+    fn winner_index(&self) -> Option<usize> {
+        let mut alive = (0..self.players.len()).filter(|i| !self.players[*i].grid().is_defeated());
+        match (alive.next(), alive.next()) {
+            (Some(i), None) => Some(i),
+            _ => None,
+        }
+    }
+
+    fn opponent(&self, i: usize) -> Option<&dyn Participant> {
+        self.target_index(i).map(|player_index| self.players[player_index].as_ref())
+    }
+}
+
+/// Parses a ship of a shared game, e.g. `2h:A1-B1`, decoys start with `~`.
+///
+/// Also see [`Game::to_shareable`].
+fn parse_shared_ship(s: &str) -> Option<Ship> {
+    let decoy = s.starts_with('~');
+    let mut parts = s.trim_start_matches('~').splitn(2, ':');
+    let kind = parts.next()?;
+    let orientation = match kind.chars().last()? {
+        'h' => Orientation::Horizontal,
+        'v' => Orientation::Vertical,
+        _ => return None,
+    };
+    let size = kind[..kind.len() - 1].parse().ok()?;
+    let coords = parts
+        .next()?
+        .split('-')
+        .map(|c| Coordinate::try_from(c.to_string()).ok())
+        .collect::<Option<Vec<Coordinate>>>()?;
+    let mut ship = Ship::new(ShipType::from_size(size, orientation)?, coords);
+    ship.decoy = decoy;
+    Some(ship)
+}
+
+/// Joins two grids side by side under their titles, e.g. the tracking grid and the own grid.
+///
+/// Each side is given as its title, grid and rows (see [`Grid::rows`]).
+/// Lines of the shorter grid are padded so that the right grid stays aligned.
+fn side_by_side(left: (&str, &Grid, &[String]), right: (&str, &Grid, &[String])) -> String {
+    let lines = |(title, grid, rows): (&str, &Grid, &[String])| {
+        let mut lines = vec![
+            (title.to_string(), title.chars().count()),
+            (grid.header(), grid.display_width()),
+        ];
+        lines.extend(rows.iter().zip(1..).map(|(row, y)| {
+            (format!("{}{}", grid.row_label(y), row), grid.display_width())
+        }));
+        lines
+    };
+    let (left_lines, right_lines) = (lines(left), lines(right));
+    let width = left.1.display_width().max(left.0.chars().count());
+    let mut s = String::from("\n");
+    for i in 0..left_lines.len().max(right_lines.len()) {
+        let (line, len) = left_lines.get(i).cloned().unwrap_or_default();
+        let right_line = right_lines.get(i).map(|(line, _)| line.as_str()).unwrap_or_default();
+        s += &format!("{}{} | {}\n", line, " ".repeat(width - len), right_line);
+    }
+    s
+}
+
+/// Checks if the error is caused by a closed connection.
+fn is_disconnect(error: &(dyn Error + 'static)) -> bool {
+    error.downcast_ref::<IoError>().map_or(false, |error| {
+        [
+            ErrorKind::BrokenPipe,
+            ErrorKind::ConnectionReset,
+            ErrorKind::ConnectionAborted,
+            ErrorKind::UnexpectedEof,
+        ]
+        .contains(&error.kind())
+    })
+}
+
+/// Checks if the error is caused by a read that timed out.
+fn is_timeout(error: &(dyn Error + 'static)) -> bool {
+    error
+        .downcast_ref::<IoError>()
+        .map_or(false, |error| error.kind() == ErrorKind::TimedOut)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::RowOrigin;
+    use crate::player::{connect_player, ScriptedPlayer, WRITE_TIMEOUT};
+    use crate::rating::INITIAL_RATING;
+    use crate::ship::{Orientation, ShipType};
+    use std::fs;
+    use std::io::Read;
+    use std::net::TcpStream;
+    use std::time::Instant;
+
+    /// Returns a game with connected players.
+    ///
+    /// Each player has a 5x5 grid with boats at A1 and C3.
+    fn create_game(names: &[&str]) -> Result<(Game, Vec<TcpStream>)> {
+        let mut game = Game::default();
+        let mut clients = Vec::new();
+        for name in names {
+            let (mut player, client) = connect_player(name)?;
+            player.grid = Grid::new(5, 5);
+            player.grid.place_ship(Ship::new(ShipType::Boat, vec![Coordinate::from((1, 1))]));
+            player.grid.place_ship(Ship::new(ShipType::Boat, vec![Coordinate::from((3, 3))]));
+            game.players.push(Box::new(player));
+            clients.push(client);
+        }
+        Ok((game, clients))
+    }
+
+    /// Removes the ANSI escape sequences from the given string.
+    fn strip_ansi(s: &str) -> String {
+        let mut output = String::new();
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            if c == '\u{1b}' {
+                chars.by_ref().find(|c| *c == 'm');
+            } else {
+                output.push(c);
+            }
+        }
+        output
+    }
+
+    /// Sinks all the ships of the player.
+    fn defeat(game: &mut Game, i: usize) {
+        game.players[i]
+            .grid_mut()
+            .ships
+            .iter_mut()
+            .for_each(|ship| ship.coords.iter_mut().for_each(|c| c.is_hit = true));
+    }
+
+    #[test]
+    fn test_game_mode() -> Result<()> {
+        let mut game = Game::default();
+        let mut clients = Vec::new();
+        let modes = [Some(GameMode::Practice), Some(GameMode::Radar), None];
+        for (name, mode) in ["Alice", "Bob", "Carol"].iter().zip(modes.iter()) {
+            let (mut player, client) = connect_player(name)?;
+            player.game_mode = *mode;
+            game.add_player(Box::new(player))?;
+            clients.push(client);
+        }
+        assert_eq!(Some(GameMode::Practice), game.mode);
+        assert!(game.config.practice_mode);
+        assert!(!game.config.radar);
+
+        // The configuration is restored once the game ends.
+        game.end(None);
+        assert_eq!(None, game.mode);
+        assert!(!game.config.practice_mode);
+        for (i, client) in clients.iter_mut().enumerate() {
+            let mut output = String::new();
+            client.read_to_string(&mut output)?;
+            let message = "This game is played in practice mode, as chosen by Alice.\n";
+            assert_eq!(i == 1, output.contains(message));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_with_capacity() -> Result<()> {
+        for player_count in [0, 1, MAX_PLAYERS + 1].iter() {
+            assert!(Game::with_capacity(*player_count).is_err());
+        }
+        let mut game = Game::with_capacity(2)?;
+        let mut clients = Vec::new();
+        for name in &["Alice", "Bob"] {
+            assert!(!game.is_ready());
+            let (mut player, client) = connect_player(name)?;
+            player.grid = Grid::new(5, 5);
+            player.grid.place_ship(Ship::new(ShipType::Boat, vec![Coordinate::new(1, 1)]));
+            game.add_player(Box::new(player))?;
+            clients.push(client);
+        }
+        assert!(game.is_ready());
+        assert_eq!(Some(1), game.opponent_index(0));
+        assert_eq!(Some(0), game.opponent_index(1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_player() -> Result<()> {
+        let mut game = Game::default();
+        let mut clients = Vec::new();
+        for name in &["Alice", "Bob", "Carol"] {
+            let (player, client) = connect_player(name)?;
+            game.add_player(Box::new(player))?;
+            clients.push(client);
+        }
+        assert!(game.is_ready());
+        game.players.clear();
+        let expected = [
+            "Waiting for opponent...\nYour opponents are: Bob, Carol\n",
+            "Waiting for opponent...\nYour opponents are: Alice, Carol\n",
+            "Your opponents are: Alice, Bob\n",
+        ];
+        for (client, expected) in clients.iter_mut().zip(expected.iter()) {
+            let mut output = String::new();
+            client.read_to_string(&mut output)?;
+            assert_eq!(*expected, output);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_fleet_status() -> Result<()> {
+        let (mut player, _client) = connect_player("Alice")?;
+        player.grid = Grid::new(5, 5);
+        let ship_type = ShipType::Submarine(Orientation::Vertical);
+        player.grid.place_ship(Ship::new(ship_type, ship_type.get_hitbox(Coordinate::from((1, 1)))));
+        player.grid.place_ship(Ship::new(ShipType::Boat, vec![Coordinate::from((3, 3))]));
+        player.grid.ships[0].coords[1].is_hit = true;
+        let mut game = Game::default();
+        game.players.push(Box::new(player));
+        assert_eq!(
+            "Your fleet:\n- Submarine: 2/3 intact\n- Boat: 1/1 intact\n",
+            game.fleet_status(0)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_opponent_ring() -> Result<()> {
+        let (mut game, _clients) = create_game(&["Alice", "Bob", "Carol"])?;
+        assert_eq!(
+            vec![Some(1), Some(2), Some(0)],
+            (0..3).map(|i| game.opponent_index(i)).collect::<Vec<_>>()
+        );
+        assert_eq!(None, game.winner_index());
+
+        // Bob is defeated, Alice attacks Carol and Bob is skipped.
+        defeat(&mut game, 1);
+        assert_eq!(Some(2), game.opponent_index(0));
+        assert_eq!(Some(0), game.opponent_index(2));
+        assert_eq!(Some(2), game.opponent_index(1));
+        assert_eq!(None, game.winner_index());
+
+        // Carol is defeated, Alice is the last player left.
+        defeat(&mut game, 2);
+        assert_eq!(None, game.opponent_index(0));
+        assert_eq!(Some(0), game.winner_index());
+        Ok(())
+    }
+
+    #[test]
+    fn test_opponent_count() -> Result<()> {
+        let (game, _clients) = create_game(&["Alice"])?;
+        assert_eq!(None, game.opponent_index(0));
+        assert!(game.opponent(0).is_none());
+
+        let (game, _clients) = create_game(&["Alice", "Bob"])?;
+        assert_eq!(Some("Bob"), game.opponent(0).map(|p| p.name()));
+        assert_eq!(Some("Alice"), game.opponent(1).map(|p| p.name()));
+
+        let (game, _clients) = create_game(&["Alice", "Bob", "Carol"])?;
+        assert_eq!(Some("Bob"), game.opponent(0).map(|p| p.name()));
+        assert_eq!(Some("Carol"), game.opponent(1).map(|p| p.name()));
+        assert_eq!(Some("Alice"), game.opponent(2).map(|p| p.name()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_play_turn_without_opponents() -> Result<()> {
+        let (mut game, _clients) = create_game(&["Alice"])?;
+        assert!(game.play_turn(0).is_err());
+        assert!(game.ships_remaining_message(0, false).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_ships_remaining_message() -> Result<()> {
+        let (mut game, _clients) = create_game(&["Alice", "Bob"])?;
+        assert_eq!(
+            Some(String::from("Bob has 2 ships remaining.\n")),
+            game.ships_remaining_message(0, false)
+        );
+        game.config.hide_ship_count = true;
+        assert_eq!(None, game.ships_remaining_message(0, false));
+        game.players[1].grid_mut().ships[0].coords[0].is_hit = true;
+        assert_eq!(
+            Some(String::from("Bob lost a ship.\n")),
+            game.ships_remaining_message(0, true)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_play_turn() -> Result<()> {
+        let (mut game, mut clients) = create_game(&["Alice", "Bob", "Carol"])?;
+        clients[0].write_all(b"fleet\nB2\nA1\nC3\nfire\n")?;
+        assert_eq!(TurnOutcome::Command { advances: false }, game.play_turn(0)?);
+        assert_eq!(TurnOutcome::Missed, game.play_turn(0)?);
+        assert_eq!(TurnOutcome::Sunk, game.play_turn(0)?);
+        assert_eq!(TurnOutcome::Sunk, game.play_turn(0)?);
+        assert_eq!(TurnOutcome::Invalid { advances: false }, game.play_turn(0)?);
+        assert_eq!(
+            vec![
+                Coordinate::from((2, 2)),
+                Coordinate::from((1, 1)),
+                Coordinate::from((3, 3))
+            ],
+            game.players[1].grid().hits
+        );
+        assert!(game.players[1].grid().is_defeated());
+        assert!(game.players[2].grid().hits.is_empty());
+        assert!(TurnOutcome::Missed.advances_turn());
+        assert!(!TurnOutcome::Hit.advances_turn());
+        assert!(!TurnOutcome::Invalid { advances: false }.advances_turn());
+        assert!(TurnOutcome::Invalid { advances: true }.advances_turn());
+        assert!(!TurnOutcome::Command { advances: false }.advances_turn());
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_input_policy() -> Result<()> {
+        for (policy, advances) in [(InputPolicy::Lenient, false), (InputPolicy::Strict, true)] {
+            let (mut game, mut clients) = create_game(&["Alice", "Bob"])?;
+            game.config.invalid_input = policy;
+            clients[0].write_all(b"fire\nA1\n")?;
+            assert_eq!(TurnOutcome::Invalid { advances }, game.play_turn(0)?);
+            assert!(game.players[1].grid().hits.is_empty());
+            assert_eq!(TurnOutcome::Sunk, game.play_turn(0)?);
+            assert_eq!(vec![Coordinate::from((1, 1))], game.players[1].grid().hits);
+        }
+        assert_eq!(Ok(InputPolicy::Strict), "Strict".parse());
+        assert!("harsh".parse::<InputPolicy>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_repeated_shot() -> Result<()> {
+        let (mut game, mut clients) = create_game(&["Alice", "Bob"])?;
+        clients[0].write_all(b"B2\nB2\nA1\na1\n")?;
+        assert_eq!(TurnOutcome::Missed, game.play_turn(0)?);
+        assert_eq!(TurnOutcome::Invalid { advances: false }, game.play_turn(0)?);
+        assert_eq!(TurnOutcome::Sunk, game.play_turn(0)?);
+        assert_eq!(TurnOutcome::Invalid { advances: false }, game.play_turn(0)?);
+        assert_eq!(
+            vec![Coordinate::from((2, 2)), Coordinate::from((1, 1))],
+            game.players[1].grid().hits
+        );
+        game.players.clear();
+        let mut output = String::new();
+        clients[0].read_to_string(&mut output)?;
+        assert_eq!(2, output.matches("You already fired there, try again.\n").count());
+
+        // Repeated shots count towards the invalid input limit.
+        let (mut game, mut clients) = create_game(&["Alice", "Bob"])?;
+        game.config.invalid_input_limit = Some(2);
+        clients[0].write_all(b"B2\nZ\nB2\n")?;
+        assert_eq!(TurnOutcome::Missed, game.play_turn(0)?);
+        assert_eq!(TurnOutcome::Invalid { advances: false }, game.play_turn(0)?);
+        assert_eq!(TurnOutcome::Invalid { advances: true }, game.play_turn(0)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_turn_timeout() -> Result<()> {
+        let (mut game, mut clients) = create_game(&["Alice", "Bob"])?;
+        game.config.turn_timeout = Some(Duration::from_millis(50));
+        assert_eq!(TurnOutcome::Missed, game.play_turn(0)?);
+        assert!(game.players[1].grid().hits.is_empty());
+        clients[0].write_all(b"A1\n")?;
+        assert_eq!(TurnOutcome::Sunk, game.play_turn(0)?);
+        game.players.clear();
+        let mut output = String::new();
+        clients[0].read_to_string(&mut output)?;
+        assert_eq!(1, output.matches("Time's up, you forfeited your shot.\n").count());
+
+        // The commands do not extend the turn and the confirmation is read before the deadline.
+        let (mut game, mut clients) = create_game(&["Alice", "Bob"])?;
+        game.config.turn_timeout = Some(Duration::from_millis(50));
+        clients[0].write_all(b"help\n")?;
+        assert_eq!(TurnOutcome::Command { advances: false }, game.play_turn(0)?);
+        thread::sleep(Duration::from_millis(60));
+        clients[0].write_all(b"A1\n")?;
+        assert_eq!(TurnOutcome::Missed, game.play_turn(0)?);
+        game.config.confirm_shots = true;
+        assert_eq!(TurnOutcome::Missed, game.play_turn(0)?);
+        assert!(game.players[1].grid().hits.is_empty());
+
+        // A line that is partially received in time is completed in the next turn.
+        clients[0].write_all(b"C")?;
+        game.config.confirm_shots = false;
+        assert_eq!(TurnOutcome::Missed, game.play_turn(0)?);
+        clients[0].write_all(b"3\n")?;
+        assert_eq!(TurnOutcome::Sunk, game.play_turn(0)?);
+
+        // Leaving is still a disconnect.
+        let (mut game, mut clients) = create_game(&["Alice", "Bob"])?;
+        game.config.turn_timeout = Some(Duration::from_millis(50));
+        drop(clients.remove(0));
+        assert!(is_disconnect(game.play_turn(0).expect_err("Alice left").as_ref()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_out_of_range_policy() -> Result<()> {
+        for (policy, advances) in [(InputPolicy::Lenient, false), (InputPolicy::Strict, true)] {
+            let (mut game, mut clients) = create_game(&["Alice", "Bob"])?;
+            game.config.invalid_input = policy;
+            clients[0].write_all(b"F1\nA1\n")?;
+            assert_eq!(TurnOutcome::Invalid { advances }, game.play_turn(0)?);
+            assert!(game.players[1].grid().hits.is_empty());
+            assert_eq!(TurnOutcome::Sunk, game.play_turn(0)?);
+            assert_eq!(vec![Coordinate::from((1, 1))], game.players[1].grid().hits);
+            game.players.clear();
+            let mut output = String::new();
+            clients[0].read_to_string(&mut output)?;
+            let message = if advances { "You lost your turn." } else { "Try again." };
+            assert!(output.contains(&format!("Your missile went to space! {}\n", message)));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_reprompt_limit() -> Result<()> {
+        let (mut game, mut clients) = create_game(&["Alice", "Bob"])?;
+        game.config.reprompt_limit = Some(3);
+        clients[0].write_all(b"X\nfleet\nX\nX\nB2\n")?;
+        assert_eq!(TurnOutcome::Invalid { advances: false }, game.play_turn(0)?);
+        assert_eq!(TurnOutcome::Command { advances: false }, game.play_turn(0)?);
+        assert_eq!(TurnOutcome::Invalid { advances: false }, game.play_turn(0)?);
+        assert_eq!(TurnOutcome::Missed, game.play_turn(0)?);
+        assert_eq!(0, game.turn_count);
+        assert_eq!(TurnOutcome::Missed, game.play_turn(0)?);
+        assert_eq!(0, game.reprompts);
+
+        // The game goes on with the next player.
+        clients[0].write_all(b"X\nX\nX\n")?;
+        clients[1].write_all(b"A1\nC3\n")?;
+        game.resume(0)?;
+        let summary = game.progress.lock().map_err(|e| e.to_string())?.last_game.clone();
+        let winner = summary.and_then(|summary| summary.winner);
+        assert_eq!(Some("Bob"), winner.as_ref().map(String::as_str));
+        let mut output = String::new();
+        clients[0].read_to_string(&mut output)?;
+        assert_eq!(2, output.matches("Too many attempts! Your turn counts as a miss.\n").count());
+        Ok(())
+    }
+
+    #[test]
+    fn test_reprompt_limit_ignores_commands() -> Result<()> {
+        let (mut game, mut clients) = create_game(&["Alice", "Bob"])?;
+        game.config.reprompt_limit = Some(3);
+        clients[0].write_all(b"help\nhelp\nhelp\nhelp\nA1\n")?;
+        for _ in 0..4 {
+            assert_eq!(TurnOutcome::Command { advances: false }, game.play_turn(0)?);
+        }
+        assert_eq!(0, game.reprompts);
+        assert_eq!(TurnOutcome::Sunk, game.play_turn(0)?);
+        assert_eq!(vec![Coordinate::from((1, 1))], game.players[1].grid().hits);
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_input_limit() -> Result<()> {
+        let (mut game, mut clients) = create_game(&["Alice", "Bob"])?;
+        game.config.invalid_input_limit = Some(3);
+        clients[0].write_all(b"x\nx\nfleet\nx\nx\nB2\nx\n")?;
+        assert_eq!(TurnOutcome::Invalid { advances: false }, game.play_turn(0)?);
+        assert_eq!(TurnOutcome::Invalid { advances: false }, game.play_turn(0)?);
+        // The commands do not reset the count.
+        assert_eq!(TurnOutcome::Command { advances: false }, game.play_turn(0)?);
+        assert_eq!(TurnOutcome::Invalid { advances: true }, game.play_turn(0)?);
+        assert_eq!(0, game.players[0].invalid_inputs());
+        // An accepted shot resets the count.
+        assert_eq!(TurnOutcome::Invalid { advances: false }, game.play_turn(0)?);
+        assert_eq!(TurnOutcome::Missed, game.play_turn(0)?);
+        assert_eq!(0, game.players[0].invalid_inputs());
+        assert_eq!(TurnOutcome::Invalid { advances: false }, game.play_turn(0)?);
+        assert_eq!(1, game.players[0].invalid_inputs());
+
+        game.config.invalid_input_penalty = InvalidInputPenalty::Disconnect;
+        clients[0].write_all(b"x\nx\n")?;
+        assert_eq!(TurnOutcome::Invalid { advances: false }, game.play_turn(0)?);
+        let error = game.play_turn(0).expect_err("player is not disconnected");
+        assert_eq!(
+            Some(ErrorKind::ConnectionAborted),
+            error.downcast_ref::<IoError>().map(IoError::kind)
+        );
+        game.players.clear();
+        let mut output = String::new();
+        clients[0].read_to_string(&mut output)?;
+        assert_eq!(1, output.matches("one more invalid input and you lose your turn").count());
+        assert!(output.contains("Too many invalid inputs! You lost your turn.\n"));
+        assert!(output.contains("one more invalid input and you will be disconnected.\n"));
+        assert!(output.ends_with("Too many invalid inputs! Disconnecting.\n"));
+        assert_eq!(Ok(InvalidInputPenalty::Disconnect), "Disconnect".parse());
+        Ok(())
+    }
+
+    #[test]
+    fn test_spectator_replay() -> Result<()> {
+        let (mut game, mut clients) = create_game(&["Alice", "Bob"])?;
+        clients[0].write_all(b"B2\n")?;
+        clients[1].write_all(b"A1\n")?;
+        assert_eq!(TurnOutcome::Missed, game.play_turn(0)?);
+        assert_eq!(TurnOutcome::Sunk, game.play_turn(1)?);
+
+        let (spectator, mut spectator_client) = connect_player("Carol")?;
+        game.gallery
+            .lock()
+            .expect("failed to retrieve gallery")
+            .add_spectator(spectator, true)?;
+        clients[0].write_all(b"D4\n")?;
+        assert_eq!(TurnOutcome::Missed, game.play_turn(0)?);
+        game.gallery.lock().expect("failed to retrieve gallery").clear();
+
+        let mut output = String::new();
+        spectator_client.read_to_string(&mut output)?;
+        let replay = "Replaying 2 moves:\n1 Alice>Bob B2 miss\n2 Bob>Alice A1 sunk\n";
+        assert!(output.starts_with(replay));
+        let live = output.find("Watching live.\n").expect("spectator is not watching live");
+        let state = &output[replay.len()..live];
+        assert!(state.contains("Alice's grid:") && state.contains("Bob's grid:"));
+        let checksum = format!("Checksum: {:016x}\n", game.players[1].grid().state_hash());
+        assert!(output[live..].contains(&checksum));
+        assert!(output[live..].contains("3 Alice>Bob D4 miss\n"));
+        assert!(!output[live..].contains("sunk"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_turn_delay() {
+        let mut game = Game::default();
+        let now = Instant::now();
+        assert!(!game.wait_between_turns());
+        assert!(now.elapsed() < Duration::from_millis(10));
+
+        game.config.turn_delay = Duration::from_millis(20);
+        let now = Instant::now();
+        assert!(game.wait_between_turns());
+        assert!(now.elapsed() >= Duration::from_millis(20));
+    }
+
+    #[test]
+    fn test_display_policy() -> Result<()> {
+        for (policy, ship_count) in [(DisplayPolicy::Fog, 0), (DisplayPolicy::AllVisible, 2)] {
+            let (mut game, mut clients) = create_game(&["Alice", "Bob"])?;
+            game.config.display_policy = policy;
+            game.show_grid()?;
+            game.players.clear();
+            let mut output = String::new();
+            clients[0].read_to_string(&mut output)?;
+            let tracking_view = output.split("Your grid:").next().unwrap_or_default();
+            assert_eq!(ship_count, tracking_view.matches(&ShipType::Boat.to_string()).count());
+        }
+        assert_eq!(Ok(DisplayPolicy::AllVisible), "all-visible".parse());
+        Ok(())
+    }
+
+    #[test]
+    fn test_side_by_side() -> Result<()> {
+        let (mut game, mut clients) = create_game(&["Alice", "Bob"])?;
+        game.config.side_by_side = true;
+        *game.players[0].grid_mut() = Grid::new(2, 2);
+        game.players[0]
+            .grid_mut()
+            .place_ship(Ship::new(ShipType::Boat, vec![Coordinate::from((1, 1))]));
+        *game.players[1].grid_mut() = Grid::new(3, 3);
+        game.players[1]
+            .grid_mut()
+            .place_ship(Ship::new(ShipType::Boat, vec![Coordinate::from((3, 3))]));
+        game.players[1].grid_mut().fire_at(Coordinate::from((2, 1)));
+        game.last_shots.insert((0, 1), Coordinate::from((2, 1)));
+        game.show_grid()?;
+        game.players.clear();
+        let mut output = String::new();
+        clients[0].read_to_string(&mut output)?;
+        let expected = [
+            "",
+            "Enemy waters | Your fleet",
+            "  |A |B |C   |   |A |B ",
+            " 1|  |💦|    |  1|⛵|  ",
+            " 2|  |  |    |  2|  |  ",
+            " 3|  |  |    | ",
+            "",
+        ];
+        assert_eq!(expected.join("\n"), strip_ansi(&output));
+        Ok(())
+    }
+
+    #[test]
+    fn test_auto_shot() -> Result<()> {
+        let (mut game, mut clients) = create_game(&["Alice", "Bob"])?;
+        let unfired = [Coordinate::from((1, 1)), Coordinate::from((5, 5))];
+        for y in 1..=5 {
+            for x in 1..=5 {
+                if !unfired.contains(&Coordinate::from((x, y))) {
+                    game.players[1].grid_mut().hits.push(Coordinate::from((x, y)));
+                }
+            }
+        }
+
+        clients[0].write_all(b"auto\nAUTO\nauto\n")?;
+        let mut outcomes = vec![game.play_turn(0)?, game.play_turn(0)?];
+        outcomes.sort_by_key(|outcome| *outcome == TurnOutcome::Sunk);
+        assert_eq!(vec![TurnOutcome::Missed, TurnOutcome::Sunk], outcomes);
+        let hits = &game.players[1].grid().hits;
+        assert_eq!(25, hits.len());
+        assert!(unfired.iter().all(|c| hits.contains(c)));
+        assert_eq!(TurnOutcome::Command { advances: false }, game.play_turn(0)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_spectator() -> Result<()> {
+        let (mut game, mut clients) = create_game(&["Alice", "Bob"])?;
+        let (spectator, mut spectator_client) = connect_player("Carol")?;
+        game.add_spectator(spectator)?;
+        assert_eq!(2, game.players.len());
+        clients[0].write_all(b"B2\n")?;
+        clients[1].write_all(b"say hi\nA1\n")?;
+        assert_eq!(TurnOutcome::Missed, game.play_turn(0)?);
+        assert_eq!(TurnOutcome::Command { advances: false }, game.play_turn(1)?);
+        assert_eq!(TurnOutcome::Sunk, game.play_turn(1)?);
+        game.gallery.lock().expect("failed to retrieve gallery").clear();
+
+        let mut output = String::new();
+        spectator_client.read_to_string(&mut output)?;
+        assert!(output.starts_with("\nAlice's grid:"));
+        assert!(output.contains("Alice's turn.\n1 Alice>Bob B2 miss\n"));
+        assert!(output.contains("Bob's turn.\nBob says: hi\n"));
+        assert!(output.contains("2 Bob>Alice A1 sunk\n"));
+        assert!(!output.contains("Your turn"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_autofinish() -> Result<()> {
+        let mut game = Game::new(Config {
+            players_per_game: 2,
+            ..Config::default()
+        });
+        let scripts: [(&str, &[&str]); 2] = [("Alice", &["autofinish"]), ("Bob", &["AUTOFINISH"])];
+        let mut outputs = Vec::new();
+        for (name, inputs) in scripts.iter() {
+            let mut player = ScriptedPlayer::new(name, inputs);
+            *player.grid_mut() = Grid::new(5, 5);
+            for (x, y) in [(1, 1), (3, 3)].iter() {
+                let boat = Ship::new(ShipType::Boat, vec![Coordinate::new(*x, *y)]);
+                player.grid_mut().place_ship(boat);
+            }
+            outputs.push(player.sent());
+            game.add_player(Box::new(player))?;
+        }
+        // The scripts run out after enabling autoplay, so the rest of the turns do not read.
+        game.resume(0)?;
+        assert!(game.winner().is_some());
+        assert!(game.autoplay.is_empty());
+        let message = "Your shots are fired at random cells for the rest of the game.\n";
+        let alice = outputs[0].lock().map_err(|e| e.to_string())?.concat();
+        assert_eq!(1, alice.matches(message).count());
+        let shots = alice.matches("Firing at ").count();
+        assert!(shots >= 2);
+        assert_eq!(alice.matches("Your turn to shoot Bob: ").count(), shots);
+        // Alice can sink both boats before Bob gets a turn.
+        let bob = outputs[1].lock().map_err(|e| e.to_string())?.concat();
+        assert!(bob.matches(message).count() <= 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_autofinish_skips_confirmation() -> Result<()> {
+        let mut game = Game::new(Config {
+            players_per_game: 2,
+            confirm_shots: true,
+            ..Config::default()
+        });
+        let scripts: [(&str, &[&str]); 2] = [("Alice", &["autofinish"]), ("Bob", &["autofinish"])];
+        let mut outputs = Vec::new();
+        for (name, inputs) in scripts.iter() {
+            let mut player = ScriptedPlayer::new(name, inputs);
+            *player.grid_mut() = Grid::new(5, 5);
+            for (x, y) in [(1, 1), (3, 3)].iter() {
+                let boat = Ship::new(ShipType::Boat, vec![Coordinate::new(*x, *y)]);
+                player.grid_mut().place_ship(boat);
+            }
+            outputs.push(player.sent());
+            game.add_player(Box::new(player))?;
+        }
+        // The scripts have no answers, so asking for a confirmation would disconnect the players.
+        game.resume(0)?;
+        assert!(game.winner().is_some());
+        for output in outputs.iter() {
+            let output = output.lock().map_err(|e| e.to_string())?.concat();
+            assert!(!output.contains("(y/n)"));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_help() -> Result<()> {
+        let (mut game, mut clients) = create_game(&["Alice", "Bob"])?;
+        let commands = |game: &Game| {
+            game.available_commands(0)
+                .iter()
+                .map(|(syntax, _)| *syntax)
+                .collect::<Vec<&str>>()
+        };
+        assert_eq!(
+            vec!["<coordinate>", "auto", "autofinish", "fleet", "say <message>", "help"],
+            commands(&game)
+        );
+        game.config.practice_mode = true;
+        assert_eq!(
+            vec![
+                "<coordinate>",
+                "auto",
+                "autofinish",
+                "fleet",
+                "hint",
+                "reveal/hide",
+                "say <message>",
+                "help"
+            ],
+            commands(&game)
+        );
+
+        clients[0].write_all(b"HELP\n")?;
+        assert_eq!(TurnOutcome::Command { advances: false }, game.play_turn(0)?);
+        game.players.clear();
+        let mut output = String::new();
+        clients[0].read_to_string(&mut output)?;
+        assert!(output.ends_with(
+            "Available commands:\n\
+             - <coordinate>: fire a shot, e.g. A1\n\
+             - auto: fire at a random cell\n\
+             - autofinish: fire at random cells for the rest of the game\n\
+             - fleet: show the status of your fleet\n\
+             - hint: reveal a ship of the opponent\n\
+             - reveal/hide: show or hide the ships of the opponent\n\
+             - say <message>: send a message to the other players\n\
+             - help: show the available commands\n"
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_reveal() -> Result<()> {
+        let test_cases = [
+            (false, &["reveal"][..], "only available in practice mode.\n", 0),
+            (true, &["reveal"][..], "now visible.\n", 2),
+            (true, &["reveal", "hide"][..], "now hidden.\n", 0),
+        ];
+        for (practice_mode, commands, message, ship_count) in test_cases.iter() {
+            let (mut game, mut clients) = create_game(&["Alice", "Bob"])?;
+            game.config.practice_mode = *practice_mode;
+            for command in commands.iter() {
+                clients[0].write_all(format!("{}\n", command).as_bytes())?;
+                assert_eq!(TurnOutcome::Command { advances: false }, game.play_turn(0)?);
+            }
+            game.show_grid()?;
+            game.players.clear();
+            let mut output = String::new();
+            clients[0].read_to_string(&mut output)?;
+            let after = &output[output.rfind(message).ok_or("no message")?..];
+            let tracking_view = after.split("Your grid:").next().unwrap_or_default();
+            assert_eq!(*ship_count, tracking_view.matches(&ShipType::Boat.to_string()).count());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_turn_order() -> Result<()> {
+        let (mut game, mut clients) = create_game(&["Alice", "Bob", "Carol"])?;
+        assert_eq!("Alice → Bob → Carol", game.turn_order(0));
+        assert_eq!("Bob → Carol → Alice", game.turn_order(1));
+        clients[0].write_all(b"fleet\n")?;
+        game.play_turn(0)?;
+
+        defeat(&mut game, 2);
+        assert_eq!("Bob → Alice", game.turn_order(1));
+        assert_eq!("Alice → Bob", game.turn_order(0));
+        clients[0].write_all(b"fleet\n")?;
+        game.play_turn(0)?;
+
+        game.players.clear();
+        let mut output = String::new();
+        clients[1].read_to_string(&mut output)?;
+        assert_eq!(1, output.matches("Alice's turn.\nTurn order: Alice → Bob → Carol\n").count());
+        assert_eq!(2, output.matches("Alice's turn.\n").count());
+        assert_eq!(1, output.matches("Turn order").count());
+        Ok(())
+    }
+
+    #[test]
+    fn test_elimination() -> Result<()> {
+        let (mut game, mut clients) = create_game(&["Alice", "Bob", "Carol"])?;
+        game.players[2].grid_mut().ships[0].coords[0].is_hit = true;
+        clients[1].write_all(b"C3\nB2\n")?;
+        assert_eq!(TurnOutcome::Sunk, game.play_turn(1)?);
+        assert!(game.players[2].grid().is_defeated());
+
+        // The game continues between the survivors.
+        assert_eq!(None, game.winner_index());
+        assert_eq!(Some(0), game.opponent_index(1));
+        assert_eq!(Some(1), game.opponent_index(0));
+        assert_eq!(TurnOutcome::Missed, game.play_turn(1)?);
+        assert_eq!(vec![Coordinate::from((2, 2))], game.players[0].grid().hits);
+
+        game.players.clear();
+        for client in clients.iter_mut() {
+            let mut output = String::new();
+            client.read_to_string(&mut output)?;
+            assert_eq!(1, output.matches("Carol has been eliminated.\n").count());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_slow_client() -> Result<()> {
+        let (mut slow_game, _slow_clients) = create_game(&["Alice", "Bob"])?;
+        let (mut game, mut clients) = create_game(&["Carol", "Dan"])?;
+        let slow_game = thread::spawn(move || {
+            // Nobody reads the grids, the players are disconnected once their queue is full.
+            let now = Instant::now();
+            while slow_game.show_grid().is_ok() {}
+            now.elapsed()
+        });
+        clients[1].write_all(b"A1\nC3\n")?;
+        game.resume(1)?;
+        let mut output = String::new();
+        clients[1].read_to_string(&mut output)?;
+        assert!(output.ends_with("RESULT winner=Dan turns=2 players=Carol,Dan\n"));
+        let elapsed = slow_game.join().map_err(|_| "slow game panicked")?;
+        assert!(elapsed < WRITE_TIMEOUT);
+        Ok(())
+    }
+
+    #[test]
+    fn test_resume() -> Result<()> {
+        let (mut game, mut clients) = create_game(&["Alice", "Bob"])?;
+        assert!(game.resume(2).is_err());
+        clients[1].write_all(b"A1\nC3\n")?;
+        game.resume(1)?;
+        assert!(game.players.is_empty());
+
+        let mut output = String::new();
+        clients[0].read_to_string(&mut output)?;
+        assert!(!output.contains("Your turn to shoot"));
+        assert!(output.ends_with("Bob won.\nRESULT winner=Bob turns=2 players=Alice,Bob\n"));
+        output.clear();
+        clients[1].read_to_string(&mut output)?;
+        assert_eq!(2, output.matches("Your turn to shoot Alice").count());
+        Ok(())
+    }
+
+    #[test]
+    fn test_scripted_game() -> Result<()> {
+        let mut game = Game::new(Config {
+            players_per_game: 2,
+            ..Config::default()
+        });
+        let scripts: [(&str, &[&str]); 2] =
+            [("Alice", &["B2", "A1", "C3"]), ("Bob", &["fleet", "A1", "B2"])];
+        let mut outputs = Vec::new();
+        for (name, inputs) in scripts.iter() {
+            let mut player = ScriptedPlayer::new(name, inputs);
+            *player.grid_mut() = Grid::new(5, 5);
+            player.grid_mut().place_ship(Ship::new(ShipType::Boat, vec![Coordinate::new(1, 1)]));
+            player.grid_mut().place_ship(Ship::new(ShipType::Boat, vec![Coordinate::new(3, 3)]));
+            outputs.push(player.sent());
+            game.add_player(Box::new(player))?;
+        }
+        assert_eq!(None, game.winner());
+        game.resume(0)?;
+        assert_eq!(Some("Alice"), game.winner());
+        let output = |i: usize| -> Result<String> {
+            Ok(outputs[i].lock().map_err(|e| e.to_string())?.concat())
+        };
+        let (alice, bob) = (output(0)?, output(1)?);
+        assert!(alice.starts_with("Waiting for opponent...\nYour opponent is Bob\n"));
+        assert_eq!(3, alice.matches("Your turn to shoot Bob: ").count());
+        assert!(alice.ends_with("You won!\nRESULT winner=Alice turns=5 players=Alice,Bob\n"));
+        assert!(bob.contains("Your turn to shoot Alice: Your fleet:"));
+        assert!(bob.contains("Hit!\n"));
+        assert!(bob.ends_with("Alice won.\nRESULT winner=Alice turns=5 players=Alice,Bob\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_disconnect_grace() -> Result<()> {
+        let scripted_game = |grace: Duration, scripts: [(&str, &[&str]); 2]| -> Result<_> {
+            let mut game = Game::new(Config {
+                players_per_game: 2,
+                disconnect_grace: Some(grace),
+                ..Config::default()
+            });
+            let mut outputs = Vec::new();
+            for (name, inputs) in scripts.iter() {
+                let mut player = ScriptedPlayer::new(name, inputs);
+                *player.grid_mut() = Grid::new(5, 5);
+                for (x, y) in [(1, 1), (3, 3)].iter() {
+                    let boat = Ship::new(ShipType::Boat, vec![Coordinate::new(*x, *y)]);
+                    player.grid_mut().place_ship(boat);
+                }
+                outputs.push(player.sent());
+                game.add_player(Box::new(player))?;
+            }
+            Ok((game, outputs))
+        };
+        let concat = |output: &Arc<Mutex<Vec<String>>>| -> Result<String> {
+            Ok(output.lock().map_err(|e| e.to_string())?.concat())
+        };
+
+        // The player takes their place back after reconnecting.
+        let (mut game, outputs) =
+            scripted_game(Duration::from_secs(60), [("Alice", &["B2"]), ("Bob", &["E5", "E4"])])?;
+        game.play_turn(0)?;
+        game.play_turn(1)?;
+        let error = game.play_turn(0).expect_err("Alice should be disconnected");
+        assert!(game.hold_disconnected_players(error.as_ref())?);
+        assert!(!game.hold_disconnected_players(error.as_ref())?);
+        let alice = ScriptedPlayer::new("Alice", &["A1", "C3"]);
+        let reconnected = alice.sent();
+        let token = game.reconnection_token(0).ok_or("no token")?.to_string();
+        assert!(concat(&outputs[0])?.contains(&format!("Your reconnection token is {}", token)));
+        let mut reconnections = game.reconnections.lock().map_err(|e| e.to_string())?;
+        assert_eq!(Some(&token), reconnections.get("Alice").map(|r| &r.0));
+        reconnections.insert(String::from("Alice"), (token, Some(Box::new(alice))));
+        drop(reconnections);
+        game.resume(1)?;
+        let (alice, bob) = (concat(&reconnected)?, concat(&outputs[1])?);
+        assert!(alice.starts_with("Welcome back! The game goes on.\n"));
+        assert!(alice.contains("You won!\n"));
+        assert!(bob.contains("Waiting for Alice to reconnect...\n"));
+        assert!(bob.contains("Alice reconnected.\n"));
+        assert!(game.reconnections.lock().map_err(|e| e.to_string())?.is_empty());
+
+        // The player is eliminated if they do not reconnect in time.
+        let (mut game, outputs) =
+            scripted_game(Duration::from_secs(0), [("Alice", &["B2"]), ("Bob", &["E5", "E4"])])?;
+        game.resume(0)?;
+        let bob = concat(&outputs[1])?;
+        assert!(bob.contains("Alice has been eliminated: did not reconnect in time.\n"));
+        assert!(bob.contains("You won!\n"));
+        assert!(game.reconnections.lock().map_err(|e| e.to_string())?.is_empty());
+
+        // Other errors are not held.
+        let error = IoError::new(ErrorKind::InvalidData, "invalid");
+        assert!(!game.hold_disconnected_players(&error)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_remove_player() -> Result<()> {
+        let mut game = Game::new(Config {
+            players_per_game: 3,
+            ..Config::default()
+        });
+        let mut outputs = Vec::new();
+        let scripts: [(&str, &[&str]); 3] =
+            [("Alice", &[]), ("Bob", &["A1", "C3"]), ("Carol", &["E5"])];
+        for (name, inputs) in scripts.iter() {
+            let mut player = ScriptedPlayer::new(name, inputs);
+            *player.grid_mut() = Grid::new(5, 5);
+            for (x, y) in [(1, 1), (3, 3)].iter() {
+                let boat = Ship::new(ShipType::Boat, vec![Coordinate::new(*x, *y)]);
+                player.grid_mut().place_ship(boat);
+            }
+            outputs.push(player.sent());
+            game.add_player(Box::new(player))?;
+        }
+
+        // Alice leaves on her first turn, Bob and Carol play on.
+        game.resume(0)?;
+        let bob = outputs[1].lock().map_err(|e| e.to_string())?.concat();
+        assert!(bob.contains("Alice has been eliminated: disconnected.\n"));
+        assert!(bob.contains("Your turn to shoot Carol"));
+        assert!(bob.contains("You won!\n"));
+        assert_eq!(Some("Bob"), game.winner());
+
+        assert!(game.remove_player(3).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_disconnect_ends_game() -> Result<()> {
+        let mut game = Game::default();
+        let mut outputs = Vec::new();
+        for name in ["Alice", "Bob"].iter() {
+            let mut player = ScriptedPlayer::new(name, &[]);
+            *player.grid_mut() = Grid::new(5, 5);
+            player.grid_mut().place_ship(Ship::new(ShipType::Boat, vec![Coordinate::new(1, 1)]));
+            outputs.push(player.sent());
+            game.add_player(Box::new(player))?;
+        }
+
+        // Alice leaves on her first turn and the only survivor wins.
+        game.resume(0)?;
+        let bob = outputs[1].lock().map_err(|e| e.to_string())?.concat();
+        assert!(bob.contains("Alice has been eliminated: disconnected.\n"));
+        assert!(bob.contains("You won!\n"));
+        assert_eq!(Some("Bob"), game.winner());
+        Ok(())
+    }
+
+    #[test]
+    fn test_shots_per_turn() -> Result<()> {
+        let mut game = Game::new(Config {
+            players_per_game: 2,
+            shots_per_turn: 2,
+            ..Config::default()
+        });
+        let scripts: [(&str, &[&str]); 2] =
+            [("Alice", &["A1", "fleet", "B2", "C3"]), ("Bob", &["B2", "E5"])];
+        let mut outputs = Vec::new();
+        for (name, inputs) in scripts.iter() {
+            let mut player = ScriptedPlayer::new(name, inputs);
+            *player.grid_mut() = Grid::new(5, 5);
+            player.grid_mut().place_ship(Ship::new(ShipType::Boat, vec![Coordinate::new(1, 1)]));
+            player.grid_mut().place_ship(Ship::new(ShipType::Boat, vec![Coordinate::new(3, 3)]));
+            outputs.push(player.sent());
+            game.add_player(Box::new(player))?;
+        }
+
+        // The hit does not grant an extra shot and the command does not count as a shot.
+        let outcome = game.play_turn(0)?;
+        assert!(game.info().contains("Rules: 2 shots per turn\n"));
+        assert_eq!(TurnOutcome::Volley { hits: 1 }, outcome);
+        assert!(outcome.advances_turn());
+        assert_eq!(2, game.turn_count);
+
+        // The volley stops once the game is won.
+        game.resume(1)?;
+        let output = outputs[0].lock().map_err(|e| e.to_string())?.concat();
+        assert_eq!(2, output.matches("Shot 1 of 2.\n").count());
+        assert_eq!(1, output.matches("Shot 2 of 2.\n").count());
+        assert!(output.ends_with("You won!\nRESULT winner=Alice turns=5 players=Alice,Bob\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_full_coverage() -> Result<()> {
+        let mut game = Game::new(Config {
+            players_per_game: 2,
+            ..Config::default()
+        });
+        let mut outputs = Vec::new();
+        for (name, inputs) in [("Alice", ["auto"; 5]), ("Bob", ["auto"; 5])].iter() {
+            let mut player = ScriptedPlayer::new(name, inputs);
+            *player.grid_mut() = Grid::new(2, 2);
+            for y in 1..=2 {
+                let coords = vec![Coordinate::new(1, y), Coordinate::new(2, y)];
+                let ship = Ship::new(ShipType::Destroyer(Orientation::Horizontal), coords);
+                assert!(player.grid_mut().place_ship(ship));
+            }
+            outputs.push(player.sent());
+            game.add_player(Box::new(player))?;
+        }
+        game.warn_about_coverage()?;
+
+        // Every shot hits, so the first player sinks the whole fleet in a row.
+        game.resume(0)?;
+        let output = outputs[0].lock().map_err(|e| e.to_string())?.concat();
+        assert!(output.contains("Warning: ships cover almost the whole grid of Alice, Bob,"));
+        assert_eq!(4, output.matches("Hit!\n").count());
+        assert!(!output.contains("Missed."));
+        assert!(output.ends_with("You won!\nRESULT winner=Alice turns=4 players=Alice,Bob\n"));
+        assert!(outputs[1].lock().map_err(|e| e.to_string())?.concat().contains("Alice won.\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_summary() -> Result<()> {
+        let (mut game, mut clients) = create_game(&["Alice", "Bob"])?;
+        clients[0].write_all(b"B2\nA1\nC3\n")?;
+        clients[1].write_all(b"A1\nB2\n")?;
+        game.resume(0)?;
+        let summary = game.progress.lock().map_err(|e| e.to_string())?.last_game.clone();
+        let mut summary = summary.ok_or("summary is not kept")?;
+        assert_eq!(Some("Alice"), summary.winner.as_ref().map(String::as_str));
+        let counts = summary.turn_times.iter().map(|times| times.count).collect::<Vec<u32>>();
+        assert_eq!(vec![3, 2], counts);
+        assert!(summary.turn_times.iter().all(|times| times.longest <= times.total));
+
+        // The timings are replaced for a deterministic description.
+        summary.turn_times[0].total = Duration::from_millis(4500);
+        summary.turn_times[0].longest = Duration::from_millis(2500);
+        summary.turn_times[1] = TurnTimes::default();
+        summary.turn_times[1].record(Duration::from_millis(1000));
+        assert_eq!(
+            [
+                "Last game: Alice won after 5 turns.",
+                "Alice: 3 shots, 2 hits, 2 ships sunk.",
+                "Alice: 1.5s per turn on average, 2.5s at most.",
+                "Bob: 2 shots, 1 hits, 1 ships sunk.",
+                "Bob: 1.0s per turn on average, 1.0s at most.",
+                "Turn 2: Bob sank Alice's ship at A1.",
+                "Turn 4: Alice sank Bob's ship at A1.",
+                "Turn 5: Alice sank Bob's ship at C3.",
+                "",
+            ]
+            .join("\n"),
+            summary.describe()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_move_history_names() -> Result<()> {
+        // The moves refer to the players by their indices, so the names can contain anything.
+        let (mut game, mut clients) = create_game(&["Alice Smith", "Bob>Carol"])?;
+        clients[0].write_all(b"B2\n")?;
+        clients[1].write_all(b"A1\nB2\n")?;
+        assert_eq!(TurnOutcome::Missed, game.play_turn(0)?);
+        assert_eq!(TurnOutcome::Sunk, game.play_turn(1)?);
+        assert_eq!(TurnOutcome::Missed, game.play_turn(1)?);
+        assert_eq!((1, 2), (game.shot_count(0, 1), game.shot_count(1, 0)));
+        assert_eq!(vec![1, 0], game.ranking());
+        assert!(game.status_line(1).ends_with(" | Accuracy: 50% (1/2)"));
+
+        let mut imported = Game::from_shareable(&game.to_shareable(), Config::default())?;
+        imported.replay_moves()?;
+        for (player, imported_player) in game.players.iter().zip(&imported.players) {
+            assert_eq!(player.grid().hits, imported_player.grid().hits);
+        }
+
+        game.end(None);
+        let summary = game.progress.lock().map_err(|e| e.to_string())?.last_game.clone();
+        let description = summary.ok_or("summary is not kept")?.describe();
+        assert!(description.contains("Bob>Carol: 2 shots, 1 hits, 1 ships sunk.\n"));
+        assert!(description.contains("Turn 2: Bob>Carol sank Alice Smith's ship at A1.\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_move_history_row_origin() -> Result<()> {
+        let (mut game, mut clients) = create_game(&["Alice", "Bob"])?;
+        game.config.row_origin = RowOrigin::Bottom;
+        for player in game.players.iter_mut() {
+            player.grid_mut().origin = RowOrigin::Bottom;
+        }
+        clients[0].write_all(b"A5\nB1\n")?;
+        assert_eq!(TurnOutcome::Sunk, game.play_turn(0)?);
+        assert_eq!(TurnOutcome::Missed, game.play_turn(0)?);
+        let history = game.gallery.lock().map_err(|e| e.to_string())?.history.clone();
+        assert_eq!(vec!["1 Alice>Bob A5 sunk", "2 Alice>Bob B1 miss"], history);
+
+        // The cells are on the rows with the same labels in the rendered grid.
+        let grid = game.players[1].grid().as_tracking_string(None, &game.config.glyphs)?;
+        let grid = strip_ansi(&grid);
+        let row = |label: &str| {
+            grid.lines().find(|line| line.starts_with(label)).unwrap_or_default()
+        };
+        assert!(row(" 5|").starts_with(&format!(" 5|{}", game.config.glyphs.sunk)));
+        assert!(row(" 1|").starts_with(&format!(" 1|  |{}", game.config.glyphs.miss)));
+
+        game.end(None);
+        let summary = game.progress.lock().map_err(|e| e.to_string())?.last_game.clone();
+        let description = summary.ok_or("summary is not kept")?.describe();
+        assert!(description.contains("Turn 1: Alice sank Bob's ship at A5.\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_eliminate_player() -> Result<()> {
+        let (mut game, mut clients) = create_game(&["Alice", "Bob", "Carol"])?;
+        assert!(game.eliminate_player(3, "kicked").is_err());
+        assert!(!game.eliminate_player(2, "kicked by the admin")?);
+        assert!(game.players[2].grid().is_defeated());
+        assert_eq!("Alice → Bob", game.turn_order(0));
+        assert!(game.eliminate_player(2, "kicked by the admin").is_err());
+
+        // The last survivor wins.
+        assert!(game.eliminate_player(0, "surrendered")?);
+        assert!(game.players.is_empty());
+        let summary = game.progress.lock().map_err(|e| e.to_string())?.last_game.clone();
+        let winner = summary.and_then(|summary| summary.winner);
+        assert_eq!(Some("Bob"), winner.as_ref().map(String::as_str));
+        let mut output = String::new();
+        clients[1].read_to_string(&mut output)?;
+        assert_eq!(
+            "Carol has been eliminated: kicked by the admin.\n\
+             Alice has been eliminated: surrendered.\n",
+            output.lines().take(2).map(|line| format!("{}\n", line)).collect::<String>()
+        );
+        assert!(output.contains("You won!\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_ranking() -> Result<()> {
+        let (mut game, mut clients) = create_game(&["Alice", "Bob", "Carol", "Dan"])?;
+        clients[3].write_all(b"A1\n")?;
+        clients[0].write_all(b"B2\nB3\n")?;
+        assert_eq!(TurnOutcome::Sunk, game.play_turn(3)?);
+        assert_eq!(TurnOutcome::Missed, game.play_turn(0)?);
+
+        // Carol and Dan are eliminated on the same turn, Dan has more hits.
+        game.eliminate_player(2, "left the game")?;
+        game.eliminate_player(3, "left the game")?;
+        assert_eq!(vec![0, 1, 3, 2], game.ranking());
+        assert_eq!(TurnOutcome::Missed, game.play_turn(0)?);
+        game.eliminate_player(1, "surrendered")?;
+        let mut output = String::new();
+        clients[0].read_to_string(&mut output)?;
+        assert!(output.contains("Ranking: 1. Alice, 2. Bob, 3. Dan, 4. Carol\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_status_line() -> Result<()> {
+        let (mut game, mut clients) = create_game(&["Alice", "Bob"])?;
+        assert_eq!("Turn 1 | Your ships: 2 | Bob: 2 | Accuracy: -", game.status_line(0));
+        clients[0].write_all(b"B2\nA1\n")?;
+        clients[1].write_all(b"A1\nB2\n")?;
+        for i in &[0, 1, 1] {
+            game.play_turn(*i)?;
+        }
+        game.config.status_line = true;
+        game.play_turn(0)?;
+        assert_eq!("Turn 5 | Your ships: 1 | Bob: 1 | Accuracy: 50% (1/2)", game.status_line(0));
+        assert_eq!("Turn 5 | Your ships: 1 | Alice: 1 | Accuracy: 50% (1/2)", game.status_line(1));
+        game.config.hide_ship_count = true;
+        assert_eq!("Turn 5 | Your ships: 1 | Bob: ? | Accuracy: 50% (1/2)", game.status_line(0));
+
+        // The status line is only sent once it is enabled.
+        game.players.clear();
+        let mut output = String::new();
+        clients[0].read_to_string(&mut output)?;
+        assert_eq!(1, output.matches("Turn ").count());
+        assert!(output.contains("Turn 4 | Your ships: 1 | Bob: 2 | Accuracy: 0% (0/1)\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_game_over() -> Result<()> {
+        let (mut game, mut clients) = create_game(&["Alice", "Bob", "Carol"])?;
+        assert!(!game.check_game_over()?);
+        defeat(&mut game, 2);
+        game.players[1].grid_mut().ships[0].coords[0].is_hit = true;
+        game.config.turn_delay = Duration::from_secs(5);
+
+        // The killing blow ends the game without waiting for the next turn.
+        clients[0].write_all(b"C3\n")?;
+        let now = Instant::now();
+        game.resume(0)?;
+        assert!(now.elapsed() < game.config.turn_delay);
+        assert!(game.players.is_empty());
+
+        let mut output = String::new();
+        clients[1].read_to_string(&mut output)?;
+        let result = "RESULT winner=Alice turns=1 players=Alice,Bob,Carol\n";
+        assert!(output.ends_with(&format!("Alice won.\n{}", result)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_ratings() -> Result<()> {
+        let (mut game, mut clients) = create_game(&["Alice", "Bob"])?;
+        clients[1].write_all(b"A1\nC3\n")?;
+        game.resume(1)?;
+        let ratings = game.ratings.lock().expect("failed to retrieve ratings");
+        assert!(ratings.get("Bob") > INITIAL_RATING);
+        assert!(ratings.get("Alice") < INITIAL_RATING);
+        Ok(())
+    }
+
+    #[test]
+    fn test_result_line() -> Result<()> {
+        let (mut game, mut clients) = create_game(&["Alice", "Bob"])?;
+        game.turn_count = 42;
+        assert_eq!("RESULT winner=Alice turns=42 players=Alice,Bob\n", game.result_line(Some(0)));
+        assert_eq!("RESULT winner=none turns=42 players=Alice,Bob\n", game.result_line(None));
+
+        defeat(&mut game, 0);
+        defeat(&mut game, 1);
+        assert!(game.check_game_over()?);
+        assert_eq!(None, game.winner());
+        for client in clients.iter_mut() {
+            let mut output = String::new();
+            client.read_to_string(&mut output)?;
+            assert_eq!("Draw.\nRESULT winner=none turns=42 players=Alice,Bob\n", output);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_grid_sizes() -> Result<()> {
+        let (mut game, mut clients) = create_game(&["Alice", "Bob"])?;
+        game.config.grid_sizes = vec![(5, 5), (7, 6)];
+        game.config.fleet_density = Some(0.08);
+        game.assign_random_grids(5, 5, 42)?;
+        assert_eq!((5, 5), (game.players[0].grid().width, game.players[0].grid().height));
+        assert_eq!((7, 6), (game.players[1].grid().width, game.players[1].grid().height));
+
+        // Shots are range checked against the grid of the target.
+        clients[0].write_all(b"G6\n")?;
+        clients[1].write_all(b"G6\nE5\n")?;
+        assert_ne!(TurnOutcome::Invalid { advances: false }, game.play_turn(0)?);
+        assert_eq!(vec![Coordinate::from((7, 6))], game.players[1].grid().hits);
+        assert_eq!(TurnOutcome::Invalid { advances: false }, game.play_turn(1)?);
+        assert!(game.players[0].grid().hits.is_empty());
+        assert_ne!(TurnOutcome::Invalid { advances: false }, game.play_turn(1)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bot_game() -> Result<()> {
+        let mut game = Game::new(Config {
+            confirm_shots: true,
+            ..Config::default()
+        });
+        for name in &["Bot-1", "Bot-2"] {
+            let mut bot = BotPlayer::new(name);
+            *bot.grid_mut() = Grid::new(5, 5);
+            bot.grid_mut().place_ship(Ship::new(ShipType::Boat, vec![Coordinate::from((2, 4))]));
+            game.players.push(Box::new(bot));
+        }
+        game.resume(0)?;
+        assert!(game.players.is_empty());
+        let ratings = game.ratings.lock().expect("failed to retrieve ratings");
+        assert_ne!(INITIAL_RATING, ratings.get("Bot-1"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_confirm_shots() -> Result<()> {
+        let (mut game, mut clients) = create_game(&["Alice", "Bob"])?;
+        game.config.confirm_shots = true;
+        clients[0].write_all(b"C5\nn\nA1\nY\n")?;
+        assert_eq!(TurnOutcome::Command { advances: false }, game.play_turn(0)?);
+        assert!(game.players[1].grid().hits.is_empty());
+        assert_eq!(0, game.turn_count);
+        assert_eq!(TurnOutcome::Sunk, game.play_turn(0)?);
+        assert_eq!(vec![Coordinate::from((1, 1))], game.players[1].grid().hits);
+
+        game.players.clear();
+        let mut output = String::new();
+        clients[0].read_to_string(&mut output)?;
+        assert!(output.contains("Fire at C5? (y/n) Shot cancelled.\n"));
+        assert_eq!(2, output.matches("Your turn to shoot Bob").count());
+        assert!(output.contains("Fire at A1? (y/n) Hit!\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_reveal_on_sink() -> Result<()> {
+        let combinations = [(false, false), (true, false), (false, true), (true, true)];
+        for (no_touch, reveal_on_sink) in combinations {
+            let (mut game, mut clients) = create_game(&["Alice", "Bob"])?;
+            game.config.no_touch = no_touch;
+            game.config.reveal_on_sink = reveal_on_sink;
+            clients[0].write_all(b"A1\n")?;
+            assert_eq!(TurnOutcome::Sunk, game.play_turn(0)?);
+            let mut hits = vec![(1, 1)];
+            if no_touch && reveal_on_sink {
+                hits.extend(&[(2, 1), (1, 2), (2, 2)]);
+            }
+            let hits = hits.into_iter().map(Coordinate::from).collect::<Vec<Coordinate>>();
+            assert_eq!(hits, game.players[1].grid().hits);
+
+            // Random grids never have touching ships if they are not allowed.
+            game.assign_random_grids(10, 10, 42)?;
+            if no_touch {
+                assert!(game.players.iter().all(|player| !player.grid().ships_touch()));
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_and_load() -> Result<()> {
+        let path = std::env::temp_dir().join(format!("battleship-{}.save", fastrand::u64(..)));
+        let (mut game, mut clients) = create_game(&["Alice", "Bob"])?;
+        game.seed = 42;
+        clients[0].write_all(b"B2\n")?;
+        clients[1].write_all(b"A1\nD4\n")?;
+        assert_eq!(TurnOutcome::Missed, game.play_turn(0)?);
+        assert_eq!(TurnOutcome::Sunk, game.play_turn(1)?);
+        assert_eq!(TurnOutcome::Missed, game.play_turn(1)?);
+        game.players[1].set_hints(2);
+        game.tokens.insert(1, String::from("0123456789abcdef"));
+        game.current_player = 0;
+        game.save(&path)?;
+        let mut temp_path = path.clone().into_os_string();
+        temp_path.push(".tmp");
+        assert!(!Path::new(&temp_path).exists());
+        let config = Config {
+            row_origin: RowOrigin::Bottom,
+            ..Config::default()
+        };
+        let mut restored = Game::load(&path, config)?;
+        fs::remove_file(&path)?;
+        assert_eq!(0, restored.current_player);
+        assert_eq!(3, restored.turn_count);
+        assert_eq!(42, restored.seed);
+        assert_eq!(2, restored.players[1].hints());
+        assert_eq!(Some("0123456789abcdef"), restored.reconnection_token(1));
+        for (player, restored_player) in game.players.iter().zip(restored.players.iter()) {
+            assert_eq!(player.name(), restored_player.name());
+            assert_eq!(player.grid(), restored_player.grid());
+            assert_eq!(player.grid().hits, restored_player.grid().hits);
+        }
+        assert!(restored.players[0].grid().ships[0].coords[0].is_hit);
+        assert!(restored.players.iter().all(|p| p.grid().origin == RowOrigin::Bottom));
+        assert_eq!(Some(DEFAULT_RESTORE_GRACE), restored.disconnect_grace());
+        assert_eq!(None, restored.config.disconnect_grace);
+
+        // The players take their places back by reconnecting with the same names.
+        // The row numbers are from the bottom, so A5 is the top left cell.
+        for name in ["Alice", "Bob"].iter() {
+            let player = ScriptedPlayer::new(name, &["C3", "A5"]);
+            let mut reconnections = restored.reconnections.lock().map_err(|e| e.to_string())?;
+            let reconnection = reconnections.get_mut(*name).ok_or("not waiting")?;
+            reconnection.1 = Some(Box::new(player));
+        }
+        restored.resume(0)?;
+        assert_eq!(Some("Alice"), restored.winner());
+        assert_eq!(None, restored.disconnect_grace());
+        assert!(Game::load(&path, Config::default()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_game_log() -> Result<()> {
+        let path = std::env::temp_dir().join(format!("battleship-{}.log", fastrand::u64(..)));
+        let (mut game, mut clients) = create_game(&["Alice", "Bob"])?;
+        game.config.game_log_path = Some(path.to_string_lossy().to_string());
+        clients[0].write_all(b"B2\n")?;
+        clients[1].write_all(b"A1\nC3\n")?;
+        assert_eq!(TurnOutcome::Missed, game.play_turn(0)?);
+        assert_eq!(TurnOutcome::Sunk, game.play_turn(1)?);
+        assert_eq!(TurnOutcome::Sunk, game.play_turn(1)?);
+        game.end(game.winner_index());
+        let log = fs::read_to_string(&path)?;
+        fs::remove_file(&path)?;
+        let records = log.lines().collect::<Vec<&str>>();
+        assert_eq!(1, records.len());
+        assert!(records[0].starts_with('['));
+        assert!(records[0].ends_with("] Alice vs Bob: Bob won after 3 turns"));
+        assert!(game.players.is_empty());
+        assert_eq!(0, game.turn_count);
+        Ok(())
+    }
+
+    #[test]
+    fn test_say() -> Result<()> {
+        let (mut game, mut clients) = create_game(&["Alice", "Bob", "Carol"])?;
+        clients[0].write_all(b"say Good \x07luck!\nsay\n")?;
+        assert_eq!(TurnOutcome::Command { advances: false }, game.play_turn(0)?);
+        assert_eq!(TurnOutcome::Command { advances: false }, game.play_turn(0)?);
+        assert!(game.players[1].grid().hits.is_empty());
+        game.players.clear();
+        for client in &mut clients[1..] {
+            let mut received = String::new();
+            client.read_to_string(&mut received)?;
+            assert_eq!(1, received.matches("Alice says: Good luck!\n").count());
+        }
+        let mut received = String::new();
+        clients[0].read_to_string(&mut received)?;
+        assert!(received.contains("Usage: say <message>\n"));
+        assert!(!received.contains("Alice says"));
+
+        let (mut game, mut clients) = create_game(&["Alice", "Bob"])?;
+        game.say(0, &"x".repeat(MAX_SAY_LENGTH * 2))?;
+        game.players.clear();
+        let mut received = String::new();
+        clients[1].read_to_string(&mut received)?;
+        assert_eq!(format!("Alice says: {}\n", "x".repeat(MAX_SAY_LENGTH)), received);
+        Ok(())
+    }
+
+    #[test]
+    fn test_handicaps() -> Result<()> {
+        let (mut game, mut clients) = create_game(&["Alice", "Bob", "Carol"])?;
+        game.config.handicaps = vec![0, 2];
+        game.apply_handicaps()?;
+        assert_eq!(
+            vec![0, 2, 0],
+            game.players.iter().map(|p| p.hints()).collect::<Vec<_>>()
+        );
+        clients[1].write_all(b"hint\nhint\nhint\n")?;
+        for _ in 0..2 {
+            assert_eq!(TurnOutcome::Command { advances: true }, game.play_turn(1)?);
+        }
+        assert_eq!(TurnOutcome::Command { advances: false }, game.play_turn(1)?);
+        assert_eq!(0, game.players[1].hints());
+        Ok(())
+    }
+
+    #[test]
+    fn test_reveal_columns() -> Result<()> {
+        let (mut game, mut clients) = create_game(&["Alice", "Bob", "Carol"])?;
+        clients[0].write_all(b"a\n")?;
+        clients[1].write_all(b"Z\nB\n")?;
+        clients[2].write_all(b"\n")?;
+        game.reveal_columns()?;
+        game.players.clear();
+        let received = clients
+            .iter_mut()
+            .map(|client| {
+                let mut received = String::new();
+                client.read_to_string(&mut received).map(|_| received)
+            })
+            .collect::<std::io::Result<Vec<String>>>()?;
+        assert!(!received[0].contains("Alice revealed"));
+        assert!(received[0].contains("Bob revealed that column B has no ships.\n"));
+        assert!(received[1].contains("Alice revealed that column A has a ship.\n"));
+        assert!(received[1].contains("Invalid column, try again.\n"));
+        assert!(received[2].contains("Alice revealed that column A has a ship.\n"));
+        assert!(!received.iter().any(|r| r.contains("Carol revealed")));
+        Ok(())
+    }
+
+    #[test]
+    fn test_quick_match_placement() -> Result<()> {
+        let mut game = Game::default();
+        let mut clients = Vec::new();
+        for name in &["Player-1234", "Player-5678"] {
+            let (mut player, client) = connect_player(name)?;
+            player.quick_match = true;
+            game.players.push(Box::new(player));
+            clients.push(client);
+        }
+        game.place_ships_manually(5, 5)?;
+        let fleet = game.fleet(5, 5);
+        for player in &game.players {
+            assert_eq!(5, player.grid().width);
+            assert!(player.grid().placement_complete(&fleet));
+            player.grid().validate_against_fleet(&fleet, false)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_random_placement_command() -> Result<()> {
+        let (player, mut client) = connect_player("Alice")?;
+        let mut game = Game::default();
+        game.players.push(Box::new(player));
+        client.write_all(b"A1 h\nrandom\n")?;
+        game.place_ships_manually(10, 10)?;
+        let grid = game.players[0].grid();
+        let fleet = game.fleet(10, 10);
+        assert!(grid.placement_complete(&fleet));
+        grid.validate_against_fleet(&fleet, false)?;
+        assert_eq!(ShipType::Carrier(Orientation::Horizontal), grid.ships[0].type_);
+        Ok(())
+    }
+
+    #[test]
+    fn test_announce_spectators() -> Result<()> {
+        for announce_spectators in [false, true].iter() {
+            let (mut game, mut clients) = create_game(&["Alice", "Bob"])?;
+            game.config.announce_spectators = *announce_spectators;
+            game.announce_spectators()?;
+            for name in ["Carol", "Dan"].iter() {
+                let (spectator, client) = connect_player(name)?;
+                clients.push(client);
+                game.gallery.lock().map_err(|e| e.to_string())?.add_spectator(spectator, false)?;
+            }
+            game.announce_spectators()?;
+            game.announce_spectators()?;
+            game.gallery.lock().map_err(|e| e.to_string())?.clear();
+            game.announce_spectators()?;
+            game.players.clear();
+            for client in clients.iter_mut().take(2) {
+                let mut output = String::new();
+                client.read_to_string(&mut output)?;
+                if *announce_spectators {
+                    assert_eq!("2 people are now watching.\nNobody is watching anymore.\n", output);
+                } else {
+                    assert_eq!("", output);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_range_placement() -> Result<()> {
+        let player = ScriptedPlayer::new("Alice", &["A1:A4", "A1:B2", "A254 v", "A5:A1", "random"]);
+        let sent = player.sent();
+        let mut game = Game::default();
+        game.players.push(Box::new(player));
+        game.place_ships_manually(10, 10)?;
+        let grid = game.players[0].grid();
+        assert_eq!(ShipType::Carrier(Orientation::Vertical), grid.ships[0].type_);
+        assert_eq!(Coordinate::from((1, 1)), grid.ships[0].coords[0]);
+        grid.validate_against_fleet(&game.fleet(10, 10), false)?;
+        let sent = sent.lock().map_err(|e| e.to_string())?.concat();
+        assert_eq!(3, sent.matches("Invalid placement, try again.\n").count());
+        Ok(())
+    }
+
+    #[test]
+    fn test_random_first_player() -> Result<()> {
+        let (mut game, _clients) = create_game(&["Alice", "Bob", "Carol"])?;
+        game.seed = 1;
+        assert_eq!(0, game.first_player_index());
+
+        game.config.random_first_player = true;
+        let first_players = (0..10)
+            .map(|seed| {
+                game.seed = seed;
+                game.first_player_index()
+            })
+            .collect::<Vec<usize>>();
+        assert!(first_players.iter().any(|i| *i != 0));
+        game.seed = 3;
+        assert_eq!(first_players[3], game.first_player_index());
+        Ok(())
+    }
+
+    #[test]
+    fn test_waiting_notification() -> Result<()> {
+        let (mut game, mut clients) = create_game(&["Alice", "Bob", "Carol"])?;
+        clients[0].write_all(b"B2\n")?;
+        clients[1].write_all(b"D4\n")?;
+        game.play_turn(0)?;
+        game.play_turn(1)?;
+        game.players.clear();
+        let mut outputs = Vec::new();
+        for client in clients.iter_mut() {
+            let mut output = String::new();
+            client.read_to_string(&mut output)?;
+            outputs.push(output);
+        }
+        let waiting_for = |name: &str, output: &str| {
+            output.matches(&format!("Waiting for {} to take their shot...\n", name)).count()
+        };
+        assert_eq!(0, waiting_for("Alice", &outputs[0]));
+        assert_eq!(1, waiting_for("Bob", &outputs[0]));
+        assert_eq!(1, waiting_for("Alice", &outputs[1]));
+        assert_eq!(1, waiting_for("Alice", &outputs[2]));
+        assert_eq!(1, waiting_for("Bob", &outputs[2]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_four_players() -> Result<()> {
+        let (mut game, mut clients) = create_game(&["Alice", "Bob", "Carol", "Dan"])?;
+        game.config.players_per_game = 4;
+        assert!(game.is_ready());
+
+        // Alice sinks Dan first, then the rest of the ring without giving the turn away.
+        clients[0].write_all(b"target dan\nA1\nC3\nA1\nC3\nA1\nC3\n")?;
+        game.resume(0)?;
+        assert!(game.players.is_empty());
+
+        let mut outputs = Vec::new();
+        for client in clients.iter_mut() {
+            let mut output = String::new();
+            client.read_to_string(&mut output)?;
+            outputs.push(output);
+        }
+        assert!(outputs[0].contains("You are now targeting Dan.\n"));
+        assert!(outputs[0].contains("Your turn to shoot Bob"));
+        let dan = outputs[1].find("Dan has been eliminated.\n").expect("Dan is not eliminated");
+        let bob = outputs[1].find("Bob has been eliminated.\n").expect("Bob is not eliminated");
+        assert!(dan < bob);
+        assert!(!outputs[1].contains("Carol has been eliminated.\n"));
+        let result = "RESULT winner=Alice turns=6 players=Alice,Bob,Carol,Dan\n";
+        assert!(outputs.iter().all(|output| output.ends_with(result)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_backfill_with_bots() -> Result<()> {
+        let mut game = Game::new(Config {
+            bot_backfill_timeout: Some(Duration::from_millis(20)),
+            ..Config::default()
+        });
+        let (player, mut client) = connect_player("Alice")?;
+        game.add_player(Box::new(player))?;
+        assert!(!game.backfill_with_bots()?);
+        thread::sleep(Duration::from_millis(20));
+        assert!(game.backfill_with_bots()?);
+        assert!(game.is_ready());
+        let names = game.players.iter().map(|p| p.name()).collect::<Vec<&str>>();
+        assert_eq!(vec!["Alice", "Bot-1", "Bot-2"], names);
+        assert!(game.players[1..].iter().all(|player| player.quick_match()));
+        assert!(!game.backfill_with_bots()?);
+
+        game.players.clear();
+        let mut output = String::new();
+        client.read_to_string(&mut output)?;
+        let expected = "Filling the game with bots...\nYour opponents are: Bot-1, Bot-2\n";
+        assert!(output.contains(expected));
+        Ok(())
+    }
+
+    #[test]
+    fn test_info() -> Result<()> {
+        let (mut game, _clients) = create_game(&["Alice", "Bob"])?;
+        game.seed = 42;
+        game.config.radar = true;
+        game.config.invalid_input = InputPolicy::Strict;
+        game.players[0].grid_mut().ships[0].coords.push(Coordinate::from((1, 2)));
+        assert_eq!(
+            "Players: Alice, Bob\nBoard: 5x5\nFleet: 2, 1\nRules: radar, strict input\nSeed: 42\n",
+            game.info()
+        );
+
+        *game.players[1].grid_mut() = Grid::new(8, 6);
+        game.config = Config {
+            hide_ship_count: true,
+            ..Config::default()
+        };
+        assert_eq!(
+            "Players: Alice, Bob\nBoard: Alice 5x5, Bob 8x6\nRules: standard\nSeed: 42\n",
+            game.info()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_shareable() -> Result<()> {
+        let (mut game, mut clients) = create_game(&["Alice", "Bob"])?;
+        game.seed = 42;
+        game.players[1].grid_mut().ships[1] = Ship::new(
+            ShipType::Destroyer(Orientation::Horizontal),
+            vec![Coordinate::from((3, 3)), Coordinate::from((4, 3))],
+        );
+        game.players[1].grid_mut().ships[1].decoy = true;
+        clients[0].write_all(b"B2\n")?;
+        clients[1].write_all(b"A1\nB2\n")?;
+        assert_eq!(TurnOutcome::Missed, game.play_turn(0)?);
+        assert_eq!(TurnOutcome::Sunk, game.play_turn(1)?);
+        assert_eq!(TurnOutcome::Missed, game.play_turn(1)?);
+
+        let shared = game.to_shareable();
+        let config = Config {
+            row_origin: RowOrigin::Bottom,
+            ..Config::default()
+        };
+        let mut imported = Game::from_shareable(&shared, config)?;
+        assert_eq!(42, imported.seed);
+        assert_eq!(shared, imported.to_shareable());
+        assert!(imported.players.iter().all(|player| player.grid().hits.is_empty()));
+        imported.replay_moves()?;
+        assert_eq!(game.turn_count, imported.turn_count);
+        for (player, imported_player) in game.players.iter().zip(&imported.players) {
+            assert_eq!(player.name(), imported_player.name());
+            assert_eq!(player.grid(), imported_player.grid());
+            assert_eq!(player.grid().hits, imported_player.grid().hits);
+        }
+        assert!(imported.players[0].grid().ships[0].is_sunk());
+        assert!(imported.players[1].grid().ships[1].decoy);
+        assert!(imported.players.iter().all(|p| p.grid().origin == RowOrigin::Bottom));
+        assert!(Game::from_shareable("bm90IGEgZ2FtZQ==", Config::default()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_sink_announcement() -> Result<()> {
+        let (mut game, mut clients) = create_game(&["Alice", "Bob"])?;
+        let destroyer = ShipType::Destroyer(Orientation::Horizontal);
+        let coords = vec![Coordinate::from((1, 1)), Coordinate::from((2, 1))];
+        game.players[1].grid_mut().ships[0] = Ship::new(destroyer, coords);
+        clients[0].write_all(b"A1\nB1\n")?;
+        assert_eq!(TurnOutcome::Hit, game.play_turn(0)?);
+        assert_eq!(TurnOutcome::Sunk, game.play_turn(0)?);
+        game.players.clear();
+        let outputs = clients
+            .iter_mut()
+            .map(|client| {
+                let mut output = String::new();
+                client.read_to_string(&mut output).map(|_| output)
+            })
+            .collect::<std::io::Result<Vec<String>>>()?;
+        assert_eq!(1, outputs[0].matches("You sank").count());
+        assert!(outputs[0].contains("Hit!\nYou sank Bob's Destroyer!\n"));
+        assert_eq!(1, outputs[1].matches("sank your").count());
+        assert!(outputs[1].contains("Alice is firing at B1\nAlice sank your Destroyer!\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_sink_shot_count() -> Result<()> {
+        let (mut game, mut clients) = create_game(&["Alice", "Bob"])?;
+        game.config.sink_shot_count = true;
+        clients[0].write_all(b"B2\nE5\nA1\nD4\nC3\n")?;
+        clients[1].write_all(b"B2\nE5\n")?;
+        for i in &[0, 1, 0, 1, 0, 0, 0] {
+            game.play_turn(*i)?;
+        }
+        game.players.clear();
+        let mut output = String::new();
+        clients[0].read_to_string(&mut output)?;
+        let boat = ShipType::Boat.name();
+        let expected = [
+            format!("You sank Bob's {} after 3 total shots at them.\n", boat),
+            format!("You sank Bob's {} after 5 total shots at them.\n", boat),
+        ];
+        assert!(output.contains(&expected[0]));
+        assert!(output.contains(&expected[1]));
+        assert_eq!(2, output.matches(" total shots at them.").count());
+        Ok(())
+    }
+
+    #[test]
+    fn test_radar() -> Result<()> {
+        let (mut game, mut clients) = create_game(&["Alice", "Bob"])?;
+        game.config.radar = true;
+        clients[0].write_all(b"B2\n")?;
+        assert_eq!(TurnOutcome::Missed, game.play_turn(0)?);
+        game.players.clear();
+        let mut output = String::new();
+        clients[0].read_to_string(&mut output)?;
+        assert!(output.contains("Missed. Radar: 2 ship cells nearby.\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_decoy() -> Result<()> {
+        let (mut game, _clients) = create_game(&["Alice", "Bob"])?;
+        game.players[1]
+            .grid_mut()
+            .place_ship(Ship::new_decoy(Coordinate::from((5, 5))));
+        defeat(&mut game, 1);
+        game.players[1].grid_mut().ships[2].coords[0].is_hit = false;
+        assert_eq!(Some(0), game.winner_index());
+        Ok(())
     }
 
-    fn opponent_mut(&mut self, i: usize) -> &mut Player {
-        let player_index = self.opponent_index(i);
-        &mut self.players[player_index]
+    #[test]
+    fn test_lobby_full_message() {
+        let mut progress = Progress::default();
+        assert_eq!("Lobby is full. Please wait.\n", progress.lobby_full_message());
+        progress = Progress {
+            in_progress: true,
+            players: vec![String::from("Alice"), String::from("Bob")],
+            ships_remaining: 7,
+            games_completed: 0,
+            turn_count: 0,
+            current_player: None,
+            last_game: None,
+        };
+        assert_eq!(
+            "Lobby is full. Game in progress between Alice, Bob, ~7 ships remaining. Please try again later.\n",
+            progress.lobby_full_message()
+        );
     }
 }
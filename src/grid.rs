@@ -2,22 +2,170 @@
 
 use ansi_term::Style;
 
-use crate::ship::{Ship, ShipType};
+use crate::ship::{self, Orientation, Ship, ShipType};
+use fastrand::Rng;
 use crate::Result;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
 use std::convert::TryFrom;
 use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::io::{Result as IoResult, Write};
 use std::result::Result as StdResult;
-use std::str;
+use std::str::{self, FromStr};
 
 /// Available alphabet characters for column names.
 pub const ALPHABET: &str = "abcdefghijklmnopqrstuvwxyz";
+/// Maximum number of ships on a grid with random ships, see [`Grid::new_random`].
+pub const MAX_RANDOM_SHIP_COUNT: usize = 7;
+/// The character that marks the hit cells of the ships in the snapshots, see [`Grid::to_snapshot`].
+pub const HIT_MARKER: char = '*';
 /// The character that represents a hit.
 const HIT_POINT: &str = "🔥";
 /// The character that represents a miss.
 const MISSED_POINT: &str = "🌀";
+/// The character that represents the last shot if it was a hit.
+const LAST_HIT_POINT: &str = "💥";
+/// The character that represents the last shot if it was a miss.
+const LAST_MISSED_POINT: &str = "💦";
 /// The character to display a default coordinate.
 const DEFAULT_POINT: &str = "  ";
+/// The character that marks a ship cell in ASCII layouts.
+const ASCII_SHIP: char = '#';
+/// The character that marks a water cell in ASCII layouts.
+const ASCII_WATER: char = '.';
+/// The character for drawing the horizontal edges of the border.
+const BORDER_HORIZONTAL: &str = "─";
+/// The character for drawing the vertical edges of the border.
+const BORDER_VERTICAL: &str = "│";
+/// The character for drawing the top left corner of the border.
+const BORDER_TOP_LEFT: &str = "┌";
+/// The character for drawing the top right corner of the border.
+const BORDER_TOP_RIGHT: &str = "┐";
+/// The character for drawing the bottom left corner of the border.
+const BORDER_BOTTOM_LEFT: &str = "└";
+/// The character for drawing the bottom right corner of the border.
+const BORDER_BOTTOM_RIGHT: &str = "┘";
+
+/// Set of glyphs that are used for displaying the grid.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GlyphSet {
+    /// Glyph of the empty cells.
+    pub water: String,
+    /// Glyph of the ship cells.
+    ///
+    /// Each ship type has its own glyph if it is not set.
+    pub ship: Option<String>,
+    /// Glyph of the hits.
+    pub hit: String,
+    /// Glyph of the misses.
+    pub miss: String,
+    /// Glyph of the sunk ship cells.
+    pub sunk: String,
+    /// Glyph of the last shot if it was a hit.
+    pub last_hit: String,
+    /// Glyph of the last shot if it was a miss.
+    pub last_miss: String,
+}
+
+impl Default for GlyphSet {
+    fn default() -> Self {
+        Self {
+            water: DEFAULT_POINT.to_string(),
+            ship: None,
+            hit: HIT_POINT.to_string(),
+            miss: MISSED_POINT.to_string(),
+            sunk: HIT_POINT.to_string(),
+            last_hit: LAST_HIT_POINT.to_string(),
+            last_miss: LAST_MISSED_POINT.to_string(),
+        }
+    }
+}
+
+/// Parses the glyph set from a comma-separated list
+/// in the order of water, ship, hit, miss, sunk, last hit and last miss.
+///
+/// Defaults are kept for the omitted or empty glyphs, e.g. `~~,,XX,OO`.
+impl FromStr for GlyphSet {
+    type Err = String;
+    fn from_str(s: &str) -> StdResult<Self, Self::Err> {
+        let mut glyphs = Self::default();
+        let values = s.split(',').collect::<Vec<&str>>();
+        if values.len() > 7 {
+            return Err(format!("too many glyphs: {}", s));
+        }
+        for (i, value) in values.into_iter().enumerate() {
+            if value.is_empty() {
+                continue;
+            }
+            let value = value.to_string();
+            match i {
+                0 => glyphs.water = value,
+                1 => glyphs.ship = Some(value),
+                2 => glyphs.hit = value,
+                3 => glyphs.miss = value,
+                4 => glyphs.sunk = value,
+                5 => glyphs.last_hit = value,
+                _ => glyphs.last_miss = value,
+            }
+        }
+        Ok(glyphs)
+    }
+}
+
+/// Distribution of the randomly placed ships.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PlacementStyle {
+    /// Every cell is equally likely.
+    Uniform,
+    /// Cells near the center are more likely than the edges and the corners.
+    CenterBiased,
+}
+
+impl Default for PlacementStyle {
+    fn default() -> Self {
+        Self::Uniform
+    }
+}
+
+/// Parses the style from its name, e.g. `center-biased`.
+impl FromStr for PlacementStyle {
+    type Err = String;
+    fn from_str(s: &str) -> StdResult<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "uniform" => Ok(Self::Uniform),
+            "center-biased" => Ok(Self::CenterBiased),
+            _ => Err(format!("invalid placement style: {}", s)),
+        }
+    }
+}
+
+/// Origin of the row numbers on the grid.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RowOrigin {
+    /// Row 1 is at the top.
+    Top,
+    /// Row 1 is at the bottom, like on a chart.
+    Bottom,
+}
+
+impl Default for RowOrigin {
+    fn default() -> Self {
+        Self::Top
+    }
+}
+
+/// Parses the origin from its name, e.g. `bottom`.
+impl FromStr for RowOrigin {
+    type Err = String;
+    fn from_str(s: &str) -> StdResult<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "top" => Ok(Self::Top),
+            "bottom" => Ok(Self::Bottom),
+            _ => Err(format!("invalid row origin: {}", s)),
+        }
+    }
+}
 
 /// Representation of coordinates on a 2-dimensional plane.
 #[derive(Clone, Copy, Default)]
@@ -30,6 +178,24 @@ pub struct Coordinate {
     pub is_hit: bool,
 }
 
+impl Coordinate {
+    /// Constructs a new instance of [`Coordinate`] that is not hit.
+    pub fn new(x: u8, y: u8) -> Self {
+        Self { x, y, is_hit: false }
+    }
+
+    /// Constructs a new instance of [`Coordinate`] if it is inside a grid with the given size.
+    ///
+    /// Returns `None` if the coordinate is out of range.
+    pub fn in_grid(x: u8, y: u8, width: u8, height: u8) -> Option<Self> {
+        if x >= 1 && y >= 1 && x <= width && y <= height {
+            Some(Self::new(x, y))
+        } else {
+            None
+        }
+    }
+}
+
 /// Dismiss `is_hit` field during comparisons.
 impl PartialEq for Coordinate {
     fn eq(&self, other: &Self) -> bool {
@@ -51,16 +217,18 @@ impl fmt::Debug for Coordinate {
 impl fmt::Display for Coordinate {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let alphabet_chars = ALPHABET.chars().collect::<Vec<char>>();
-        let index = self.x.checked_sub(1).unwrap_or_default() as usize;
+        let index = self.x.saturating_sub(1) as usize;
         write!(f, "{}{}", alphabet_chars[index].to_uppercase(), self.y)
     }
 }
 
 /// For converting between e.g. "J10" to (10, 10)
+///
+/// Surrounding whitespace and line endings (e.g. `\r\n` from Telnet clients) are ignored.
 impl TryFrom<String> for Coordinate {
     type Error = ();
     fn try_from(mut value: String) -> StdResult<Self, Self::Error> {
-        value = value.to_lowercase();
+        value = value.trim().to_lowercase();
         let mut coordinate = Coordinate::default();
         for (i, c) in ALPHABET.chars().enumerate() {
             if value.starts_with(&c.to_string()) {
@@ -82,11 +250,53 @@ impl TryFrom<String> for Coordinate {
 /// Support constructing a [`Coordinate`] from a tuple.
 impl From<(u8, u8)> for Coordinate {
     fn from(v: (u8, u8)) -> Self {
-        Self {
-            x: v.0,
-            y: v.1,
-            ..Self::default()
-        }
+        Self::new(v.0, v.1)
+    }
+}
+
+/// Outcome of a shot at a grid.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ShotOutcome {
+    /// Shot hit a ship.
+    Hit,
+    /// Shot hit and sunk a ship.
+    Sunk {
+        /// Type of the ship that is sunk.
+        kind: ShipType,
+        /// Coordinates of the ship, so that the whole ship can be revealed at once.
+        cells: Vec<Coordinate>,
+    },
+    /// Shot landed on water.
+    Miss,
+    /// Shot was outside the grid and is not recorded.
+    OutOfRange,
+    /// Shot was already fired in the same batch and is skipped.
+    Duplicate,
+}
+
+/// Distance of a shot to the nearest ship.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Proximity {
+    /// Next to a ship.
+    Hot,
+    /// Two cells away from a ship.
+    Warm,
+    /// Far away from the ships.
+    Cold,
+}
+
+/// Display the proximity as a string.
+impl fmt::Display for Proximity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Proximity::Hot => "hot",
+                Proximity::Warm => "warm",
+                Proximity::Cold => "cold",
+            }
+        )
     }
 }
 
@@ -101,6 +311,29 @@ pub struct Grid {
     pub ships: Vec<Ship>,
     /// Hits.
     pub hits: Vec<Coordinate>,
+    /// Origin of the row numbers that are shown to and entered by the players.
+    ///
+    /// Coordinates are always stored from the top, see [`Grid::parse_coordinate`].
+    pub origin: RowOrigin,
+}
+
+/// Compare the grids regardless of the order of the ships and hits.
+///
+/// The row origin is only for displaying, so it is ignored.
+/// Ships are compared by their cells regardless of the order of the coordinates.
+impl PartialEq for Grid {
+    fn eq(&self, other: &Self) -> bool {
+        let same_cells = |a: &[Coordinate], b: &[Coordinate]| {
+            a.iter().all(|c| b.contains(c)) && b.iter().all(|c| a.contains(c))
+        };
+        let same_ship = |a: &Ship, b: &Ship| a.decoy == b.decoy && same_cells(&a.coords, &b.coords);
+        self.width == other.width
+            && self.height == other.height
+            && self.ships.len() == other.ships.len()
+            && self.ships.iter().all(|a| other.ships.iter().any(|b| same_ship(a, b)))
+            && other.ships.iter().all(|b| self.ships.iter().any(|a| same_ship(a, b)))
+            && same_cells(&self.hits, &other.hits)
+    }
 }
 
 impl Grid {
@@ -111,6 +344,7 @@ impl Grid {
             height,
             ships: Vec::new(),
             hits: Vec::new(),
+            origin: RowOrigin::default(),
         }
     }
 
@@ -121,11 +355,20 @@ impl Grid {
     ///
     /// [`Battleship`]: ShipType::Battleship
     pub fn new_random(width: u8, height: u8) -> Self {
+        Self::new_random_with_rng(width, height, &Rng::new())
+    }
+
+    /// Constructs a new instance of [`Grid`] with random ships and placements
+    /// using the given random number generator.
+    ///
+    /// The same sequence of grids is generated for the same seed.
+    /// Also see [`Grid::new_random`].
+    pub fn new_random_with_rng(width: u8, height: u8, rng: &Rng) -> Self {
         let mut grid = Grid::new(width, height);
-        let ship_count = fastrand::usize(4..=7);
+        let ship_count = rng.usize(4..=MAX_RANDOM_SHIP_COUNT);
         let mut battleship = None;
         while grid.ships.len() != ship_count {
-            let ship = Ship::new_random(grid.width, grid.height);
+            let ship = Ship::new_random_with_rng(grid.width, grid.height, rng);
             if let ShipType::Battleship(_) = ship.type_ {
                 battleship = Some(ship);
             } else {
@@ -138,20 +381,216 @@ impl Grid {
         grid
     }
 
+    /// Constructs a new instance of [`Grid`] with the ships of the given fleet
+    /// placed randomly using the given random number generator.
+    ///
+    /// Fleet is given as a list of ship sizes, see [`ShipType::from_size`].
+    /// Returns an error if the fleet does not fit on the grid.
+    pub fn new_random_with_fleet(width: u8, height: u8, fleet: &[u8], rng: &Rng) -> Result<Self> {
+        const MAX_ATTEMPTS: usize = 100;
+        for _ in 0..MAX_ATTEMPTS {
+            let mut grid = Grid::new(width, height);
+            let is_placed = fleet.iter().all(|size| {
+                (0..MAX_ATTEMPTS).any(|_| {
+                    let orientation = if rng.bool() {
+                        Orientation::Vertical
+                    } else {
+                        Orientation::Horizontal
+                    };
+                    match ShipType::from_size(*size, orientation) {
+                        Some(ship_type) => {
+                            let coordinate =
+                                Coordinate::new(rng.u8(1..=width), rng.u8(1..=height));
+                            grid.place_ship(Ship::new(ship_type, ship_type.get_hitbox(coordinate)))
+                        }
+                        None => false,
+                    }
+                })
+            });
+            if is_placed {
+                return Ok(grid);
+            }
+        }
+        Err(format!("[!] Failed to place the fleet {:?} on a {}x{} grid.", fleet, width, height).into())
+    }
+
+    /// Constructs a new instance of [`Grid`] with the ships of the given fleet
+    /// placed randomly from the given seed.
+    ///
+    /// The same grid is generated for the same seed, so that the boards can be reproduced.
+    /// Returns an error without placing any ship if the fleet cannot fit on the grid,
+    /// see [`ship::fleet_fits_board`]. Also see [`Grid::new_random_with_fleet`].
+    pub fn from_seed(width: u8, height: u8, fleet: &[u8], seed: u64) -> Result<Self> {
+        if !ship::fleet_fits_board(width, height, fleet) {
+            return Err(
+                format!("[!] The fleet {:?} does not fit on a {}x{} grid.", fleet, width, height)
+                    .into(),
+            );
+        }
+        Self::new_random_with_fleet(width, height, fleet, &Rng::with_seed(seed))
+    }
+
+    /// Constructs a new instance of [`Grid`] with the ships of the given fleet
+    /// placed randomly in the given style.
+    ///
+    /// With [`PlacementStyle::CenterBiased`], each legal placement is weighted
+    /// by the distance of its cells to the edges of the grid.
+    /// Also see [`Grid::new_random_with_fleet`].
+    pub fn new_random_with_style(
+        width: u8,
+        height: u8,
+        fleet: &[u8],
+        style: PlacementStyle,
+        rng: &Rng,
+    ) -> Result<Self> {
+        const MAX_ATTEMPTS: usize = 100;
+        if style == PlacementStyle::Uniform {
+            return Self::new_random_with_fleet(width, height, fleet, rng);
+        }
+        for _ in 0..MAX_ATTEMPTS {
+            let mut grid = Grid::new(width, height);
+            let is_placed = fleet.iter().all(|size| {
+                let placements = grid.legal_placements(*size, false);
+                let weights = placements
+                    .iter()
+                    .map(|ship| ship.coords.iter().map(|c| grid.center_weight(c)).sum())
+                    .collect::<Vec<usize>>();
+                let total = weights.iter().sum::<usize>();
+                if total == 0 {
+                    return false;
+                }
+                let mut pick = rng.usize(..total);
+                let index = weights.iter().position(|weight| {
+                    if pick < *weight {
+                        true
+                    } else {
+                        pick -= weight;
+                        false
+                    }
+                });
+                match index.and_then(|i| placements.into_iter().nth(i)) {
+                    Some(ship) => grid.place_ship(ship),
+                    None => false,
+                }
+            });
+            if is_placed {
+                return Ok(grid);
+            }
+        }
+        Err(format!("[!] Failed to place the fleet {:?} on a {}x{} grid.", fleet, width, height).into())
+    }
+
+    /// Returns the weight of the given cell for the center-biased placement.
+    ///
+    /// The weight grows with the distance to the nearest edge, starting from 1 at the edges.
+    fn center_weight(&self, coordinate: &Coordinate) -> usize {
+        let distance = (coordinate.x - 1)
+            .min(self.width - coordinate.x)
+            .min(coordinate.y - 1)
+            .min(self.height - coordinate.y);
+        1 + usize::from(distance)
+    }
+
+    /// Constructs a new instance of [`Grid`] from an ASCII layout.
+    ///
+    /// Each line is a row of the grid where `#` marks a ship cell and `.` marks water.
+    /// Contiguous ship cells form a single ship which must have the shape of one of the ship types,
+    /// e.g. a straight line.
+    /// Returns an error if the layout is malformed.
+    pub fn from_ascii(s: &str) -> Result<Self> {
+        let rows = s
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| line.chars().collect::<Vec<char>>())
+            .collect::<Vec<Vec<char>>>();
+        let width = rows.first().map(Vec::len).unwrap_or(0);
+        if width == 0 || width > ALPHABET.len() || rows.len() > usize::from(std::u8::MAX) {
+            return Err(format!("[!] Invalid layout size: {}x{}", width, rows.len()).into());
+        }
+        let mut cells = Vec::new();
+        for (y, row) in rows.iter().enumerate() {
+            if row.len() != width {
+                return Err(format!("[!] Row {} has a different width.", y + 1).into());
+            }
+            for (x, c) in row.iter().enumerate() {
+                match *c {
+                    ASCII_SHIP => cells.push(Coordinate::new(x as u8 + 1, y as u8 + 1)),
+                    ASCII_WATER => {}
+                    c => return Err(format!("[!] Invalid character in layout: {}", c).into()),
+                }
+            }
+        }
+
+        let mut grid = Grid::new(width as u8, rows.len() as u8);
+        while let Some(start) = cells.pop() {
+            // Collect the connected cells.
+            let mut coords = vec![start];
+            let mut i = 0;
+            while i < coords.len() {
+                let current = coords[i];
+                let mut j = 0;
+                while j < cells.len() {
+                    let c = cells[j];
+                    let distance = (i16::from(c.x) - i16::from(current.x)).abs()
+                        + (i16::from(c.y) - i16::from(current.y)).abs();
+                    if distance == 1 {
+                        coords.push(cells.remove(j));
+                    } else {
+                        j += 1;
+                    }
+                }
+                i += 1;
+            }
+
+            let top_left = Coordinate::new(
+                coords.iter().map(|c| c.x).min().unwrap_or(start.x),
+                coords.iter().map(|c| c.y).min().unwrap_or(start.y),
+            );
+            let ship_type = [Orientation::Horizontal, Orientation::Vertical]
+                .iter()
+                .filter_map(|orientation| ShipType::from_size(coords.len() as u8, *orientation))
+                .find(|ship_type| {
+                    let hitbox = ship_type.get_hitbox(top_left);
+                    hitbox.len() == coords.len() && hitbox.iter().all(|c| coords.contains(c))
+                });
+            match ship_type {
+                Some(ship_type) => {
+                    grid.ships.push(Ship::new(ship_type, ship_type.get_hitbox(top_left)));
+                }
+                None => return Err(format!("[!] Ship at {} has an invalid shape.", top_left).into()),
+            }
+        }
+        grid.ships.reverse();
+        Ok(grid)
+    }
+
+    /// Checks if the coordinate is inside the grid.
+    ///
+    /// Also see [`Coordinate::in_grid`].
+    pub fn contains(&self, coordinate: &Coordinate) -> bool {
+        Coordinate::in_grid(coordinate.x, coordinate.y, self.width, self.height).is_some()
+    }
+
     /// Places a ship on the grid.
     ///
-    /// Returns `false` if the ship is overlapping with other ships
-    /// or placed outside the grid.
+    /// Returns `false` if the ship is overlapping with other ships,
+    /// placed outside the grid or the cells of a straight ship type are not a straight line
+    /// (see [`Ship::is_valid_shape`]).
     /// Returns `true` if the placement is successful.
     pub fn place_ship(&mut self, ship: Ship) -> bool {
+        let is_straight_type = match ship.type_ {
+            ShipType::Battleship(_) => false,
+            _ => true,
+        };
+        if is_straight_type && !ship.is_valid_shape(false) {
+            return false;
+        }
         let overlaps = self
             .ships
             .iter()
-            .any(|s| s.coords.iter().any(|coord| ship.coords.contains(coord)));
-        let overflows = ship
-            .coords
-            .iter()
-            .any(|coord| coord.x > self.width || coord.y > self.height);
+            .any(|s| s.coords.iter().any(|coord| ship.contains(coord)));
+        let overflows = ship.coords.iter().any(|coord| !self.contains(coord));
         if overlaps || overflows {
             false
         } else {
@@ -160,156 +599,1928 @@ impl Grid {
         }
     }
 
-    /// Returns the grid as string.
+    /// Returns a new grid with the coordinates transformed by the given function.
+    fn transform<F: Fn(Coordinate) -> Coordinate>(
+        &self,
+        width: u8,
+        height: u8,
+        transpose: bool,
+        f: F,
+    ) -> Self {
+        Self {
+            width,
+            height,
+            ships: self
+                .ships
+                .iter()
+                .map(|ship| {
+                    let type_ = if transpose {
+                        ship.type_.transposed()
+                    } else {
+                        ship.type_
+                    };
+                    Ship::new(type_, ship.coords.iter().map(|c| f(*c)).collect())
+                })
+                .collect(),
+            hits: self.hits.iter().map(|c| f(*c)).collect(),
+            origin: self.origin,
+        }
+    }
+
+    /// Returns a new grid that is flipped over its diagonal.
     ///
-    /// Only hits/misses are shown if `show_ships` is true.
-    pub fn as_string(&self, show_ships: bool) -> Result<String> {
-        let mut s = Vec::new();
-        self.display(&mut s, show_ships)?;
-        Ok(str::from_utf8(&s)?.to_string())
+    /// Width and height are swapped along with the ship orientations.
+    pub fn transpose(&self) -> Self {
+        self.transform(self.height, self.width, true, |mut c| {
+            std::mem::swap(&mut c.x, &mut c.y);
+            c
+        })
+    }
+
+    /// Returns a new grid that is flipped from left to right.
+    pub fn mirror_horizontal(&self) -> Self {
+        self.transform(self.width, self.height, false, |mut c| {
+            c.x = self.width + 1 - c.x;
+            c
+        })
     }
 
-    /// Display a point on the grid.
+    /// Returns a new grid that is flipped from top to bottom.
+    pub fn mirror_vertical(&self) -> Self {
+        self.transform(self.width, self.height, false, |mut c| {
+            c.y = self.height + 1 - c.y;
+            c
+        })
+    }
+
+    /// Sinks all of the ships on the grid, e.g. when the player is eliminated.
     ///
-    /// The point might be empty or a part of a ship.
-    fn display_point<W: Write>(
-        &self,
-        out: &mut W,
-        coordinate: Coordinate,
-        show_ships: bool,
-    ) -> IoResult<()> {
-        if let Some(ship) = self
+    /// Decoys are left intact.
+    pub fn sink_all_ships(&mut self) {
+        self.ships
+            .iter_mut()
+            .filter(|ship| !ship.decoy)
+            .for_each(|ship| ship.coords.iter_mut().for_each(|c| c.is_hit = true));
+    }
+
+    /// Checks if all the ships on the grid are sunk.
+    ///
+    /// Decoys are ignored.
+    pub fn is_defeated(&self) -> bool {
+        self.ships
+            .iter()
+            .filter(|ship| !ship.decoy)
+            .all(|ship| ship.is_sunk())
+    }
+
+    /// Returns the number of ships of each size on the grid.
+    ///
+    /// Decoys are not counted.
+    pub fn ship_count_by_size(&self) -> BTreeMap<u8, usize> {
+        let mut counts = BTreeMap::new();
+        for ship in self.ships.iter().filter(|ship| !ship.decoy) {
+            *counts.entry(ship.coords.len() as u8).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Returns the fraction of the cells that are occupied by ships.
+    ///
+    /// It is comparable with the [`Config::fleet_density`], decoys are not counted.
+    ///
+    /// [`Config::fleet_density`]: crate::config::Config::fleet_density
+    pub fn coverage_ratio(&self) -> f32 {
+        let cell_count = u16::from(self.width) * u16::from(self.height);
+        if cell_count == 0 {
+            return 0.0;
+        }
+        let ship_cells = self
             .ships
             .iter()
-            .find(|ship| ship.coords.contains(&coordinate))
-        {
-            let value = if ship
-                .coords
-                .iter()
-                .find(|c| *c == &coordinate)
-                .map(|c| c.is_hit) == Some(true)
-            {
-                HIT_POINT.to_string()
-            } else if show_ships {
-                ship.type_.to_string()
+            .filter(|ship| !ship.decoy)
+            .map(|ship| ship.coords.len())
+            .sum::<usize>();
+        ship_cells as f32 / f32::from(cell_count)
+    }
+
+    /// Checks if a shot was already fired at the given coordinate, whether it hit or missed.
+    pub fn is_shot_at(&self, coordinate: &Coordinate) -> bool {
+        self.hits.contains(coordinate)
+    }
+
+    /// Returns the number of shots that landed on a ship.
+    pub fn hit_count(&self) -> usize {
+        self.hits.iter().filter(|c| self.ship_at(c).is_some()).count()
+    }
+
+    /// Returns the number of shots that landed on water.
+    pub fn miss_count(&self) -> usize {
+        self.hits.len() - self.hit_count()
+    }
+
+    /// Returns the checksum of the ships and the shots on the grid.
+    ///
+    /// It is sent along with the board states so that the clients can detect
+    /// when their view is out of sync with the server.
+    pub fn state_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        (self.width, self.height).hash(&mut hasher);
+        for ship in &self.ships {
+            ship.decoy.hash(&mut hasher);
+            for c in &ship.coords {
+                (c.x, c.y, c.is_hit).hash(&mut hasher);
+            }
+        }
+        for c in &self.hits {
+            (c.x, c.y).hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    /// Returns the compact bitmaps of the hit and the missed cells.
+    ///
+    /// The cells are numbered row by row from `A1` and each cell is a single bit,
+    /// starting from the least significant bit of the first byte.
+    /// It is meant for the binary protocol where sending the text grid is too expensive.
+    pub fn tracking_bitfields(&self) -> (Vec<u8>, Vec<u8>) {
+        let cell_count = usize::from(self.width) * usize::from(self.height);
+        let mut hits = vec![0; (cell_count + 7) / 8];
+        let mut misses = hits.clone();
+        for coordinate in &self.hits {
+            if !self.contains(coordinate) {
+                continue;
+            }
+            let index = usize::from(coordinate.y - 1) * usize::from(self.width)
+                + usize::from(coordinate.x - 1);
+            let bitfield = if self.ship_at(coordinate).is_some() {
+                &mut hits
             } else {
-                MISSED_POINT.to_string()
+                &mut misses
+            };
+            bitfield[index / 8] |= 1 << (index % 8);
+        }
+        (hits, misses)
+    }
+
+    /// Returns the snapshot of the ships and the shots on the grid, e.g. for saving a game.
+    ///
+    /// It is a single line of the size, the ships and the shots that are separated by `;`,
+    /// e.g. `5x5;2h:A1*-B1 ~1v:C3;A1 E5`. Each ship is given with its size and orientation,
+    /// the hit cells are marked with [`HIT_MARKER`] and the decoys are prefixed with `~`.
+    /// Also see [`Grid::from_snapshot`].
+    pub fn to_snapshot(&self) -> String {
+        let ships = self
+            .ships
+            .iter()
+            .map(|ship| {
+                let coords = ship
+                    .coords
+                    .iter()
+                    .map(|c| {
+                        let marker = if c.is_hit { HIT_MARKER.to_string() } else { String::new() };
+                        format!("{}{}", c, marker)
+                    })
+                    .collect::<Vec<String>>();
+                format!(
+                    "{}{}{}:{}",
+                    if ship.decoy { "~" } else { "" },
+                    ship.type_.size(),
+                    match ship.type_.orientation() {
+                        Some(Orientation::Horizontal) => 'h',
+                        _ => 'v',
+                    },
+                    coords.join("-")
+                )
+            })
+            .collect::<Vec<String>>();
+        let hits = self.hits.iter().map(|c| c.to_string()).collect::<Vec<String>>();
+        format!("{}x{};{};{}", self.width, self.height, ships.join(" "), hits.join(" "))
+    }
+
+    /// Restores the grid from a snapshot that is returned by [`Grid::to_snapshot`].
+    ///
+    /// Returns an error if the snapshot is malformed.
+    pub fn from_snapshot(s: &str) -> Result<Self> {
+        let parts = s.trim().split(';').collect::<Vec<&str>>();
+        let (size, ships, hits) = match parts.as_slice() {
+            [size, ships, hits] => (size, ships, hits),
+            _ => return Err(format!("[!] Invalid grid snapshot: {}", s).into()),
+        };
+        let mut size = size.splitn(2, 'x').map(str::parse);
+        let mut grid = match (size.next(), size.next()) {
+            (Some(Ok(width)), Some(Ok(height))) => Grid::new(width, height),
+            _ => return Err(format!("[!] Invalid grid size: {}", s).into()),
+        };
+        let parse_coordinate = |c: &str| {
+            Coordinate::try_from(c.to_string())
+                .map_err(|_| format!("[!] Invalid coordinate: {}", c))
+        };
+        for ship in ships.split(' ').filter(|ship| !ship.is_empty()) {
+            let decoy = ship.starts_with('~');
+            let mut ship_parts = ship.trim_start_matches('~').splitn(2, ':');
+            let kind = ship_parts.next().unwrap_or_default();
+            let orientation = match kind.chars().last() {
+                Some('h') => Orientation::Horizontal,
+                Some('v') => Orientation::Vertical,
+                _ => return Err(format!("[!] Invalid ship: {}", ship).into()),
             };
-            write!(out, "|{}", value)?;
+            let type_ = kind[..kind.len() - 1]
+                .parse()
+                .ok()
+                .and_then(|size| ShipType::from_size(size, orientation))
+                .ok_or_else(|| format!("[!] Invalid ship: {}", ship))?;
+            let mut coords = Vec::new();
+            for c in ship_parts.next().unwrap_or_default().split('-') {
+                let mut coordinate = parse_coordinate(c.trim_end_matches(HIT_MARKER))?;
+                coordinate.is_hit = c.ends_with(HIT_MARKER);
+                coords.push(coordinate);
+            }
+            let mut ship = Ship::new(type_, coords);
+            ship.decoy = decoy;
+            grid.ships.push(ship);
+        }
+        for c in hits.split(' ').filter(|c| !c.is_empty()) {
+            grid.hits.push(parse_coordinate(c)?);
+        }
+        Ok(grid)
+    }
+
+    /// Places a decoy on a random empty cell using the given random number generator.
+    ///
+    /// Returns `false` if there is no empty cell left.
+    pub fn place_random_decoy(&mut self, rng: &Rng) -> bool {
+        let mut coordinates = Vec::new();
+        for y in 1..=self.height {
+            for x in 1..=self.width {
+                let coordinate = Coordinate::new(x, y);
+                if !self.ships.iter().any(|ship| ship.contains(&coordinate)) {
+                    coordinates.push(coordinate);
+                }
+            }
+        }
+        if coordinates.is_empty() {
+            false
         } else {
-            write!(out, "|{}", Style::new().underline().paint(DEFAULT_POINT))?;
+            let coordinate = coordinates[rng.usize(..coordinates.len())];
+            self.place_ship(Ship::new_decoy(coordinate))
         }
-        Ok(())
     }
 
-    /// Prints the grid to the given output.
-    fn display<W: Write>(&self, out: &mut W, show_ships: bool) -> IoResult<()> {
-        let alphabet_chars = ALPHABET.chars().collect::<Vec<char>>();
-        writeln!(out)?;
-        for h in 0..self.height + 1 {
-            if h == 0 {
-                write!(out, "  ")?;
-            } else {
-                write!(
-                    out,
-                    "{}",
-                    Style::new().underline().paint(format!("{:2}", h))
-                )?;
+    /// Fires a shot at the given coordinate and records it.
+    ///
+    /// Shots outside the grid are not recorded.
+    pub fn fire_at(&mut self, coordinate: Coordinate) -> ShotOutcome {
+        if !self.contains(&coordinate) {
+            return ShotOutcome::OutOfRange;
+        }
+        self.hits.push(coordinate);
+        match self.ship_at_mut(&coordinate) {
+            Some(ship) => {
+                let was_sunk = ship.is_sunk();
+                if let Some(c) = ship.coords.iter_mut().find(|c| *c == &coordinate) {
+                    c.is_hit = true;
+                }
+                if !was_sunk && ship.is_sunk() {
+                    ShotOutcome::Sunk {
+                        kind: ship.type_,
+                        cells: ship.coords.clone(),
+                    }
+                } else {
+                    ShotOutcome::Hit
+                }
+            }
+            None => ShotOutcome::Miss,
+        }
+    }
+
+    /// Removes the last recorded shot and returns it.
+    ///
+    /// The ship cell is not hit anymore unless an earlier shot hit it too.
+    /// It is meant for building test scenarios and rewinding the games, not for the normal play.
+    pub fn undo_last_shot(&mut self) -> Option<Coordinate> {
+        let coordinate = self.hits.pop()?;
+        if !self.hits.contains(&coordinate) {
+            if let Some(ship) = self.ship_at_mut(&coordinate) {
+                if let Some(c) = ship.coords.iter_mut().find(|c| *c == &coordinate) {
+                    c.is_hit = false;
+                }
             }
-            for w in 0..self.width {
-                if h == 0 {
-                    write!(
-                        out,
-                        "|{}",
-                        Style::new()
-                            .underline()
-                            .paint(format!("{} ", alphabet_chars[w as usize].to_uppercase()))
-                    )?;
+        }
+        Some(coordinate)
+    }
+
+    /// Fires the shots in order and returns the outcome of each shot.
+    ///
+    /// It is meant for salvos and replays, repeated coordinates within the batch are skipped.
+    pub fn apply_shots(&mut self, coords: &[Coordinate]) -> Vec<ShotOutcome> {
+        coords
+            .iter()
+            .enumerate()
+            .map(|(i, coordinate)| {
+                if coords[..i].contains(coordinate) {
+                    ShotOutcome::Duplicate
                 } else {
-                    self.display_point(out, Coordinate::from((w + 1, h)), show_ships)?;
+                    self.fire_at(*coordinate)
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the ship that occupies the given coordinate.
+    pub fn ship_at(&self, coordinate: &Coordinate) -> Option<&Ship> {
+        self.ships.iter().find(|ship| ship.contains(coordinate))
+    }
+
+    /// Returns the ship that occupies the given coordinate as mutable.
+    pub fn ship_at_mut(&mut self, coordinate: &Coordinate) -> Option<&mut Ship> {
+        self.ships
+            .iter_mut()
+            .find(|ship| ship.contains(coordinate))
+    }
+
+    /// Returns the cells that are occupied by more than one ship.
+    ///
+    /// Each cell is returned once, in the order of the ships.
+    /// It is meant for debugging the placements, also see [`Grid::has_overlaps`].
+    pub fn overlap_cells(&self) -> Vec<Coordinate> {
+        let mut cells: Vec<Coordinate> = Vec::new();
+        for (i, ship) in self.ships.iter().enumerate() {
+            for coordinate in &ship.coords {
+                let is_shared = self.ships[i + 1..]
+                    .iter()
+                    .any(|other| other.contains(coordinate));
+                if is_shared && !cells.contains(coordinate) {
+                    cells.push(Coordinate::new(coordinate.x, coordinate.y));
                 }
             }
-            writeln!(out)?;
         }
-        Ok(())
+        cells
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::ship::Orientation;
+    /// Checks if any of the ships overlap with each other.
+    pub fn has_overlaps(&self) -> bool {
+        !self.overlap_cells().is_empty()
+    }
 
-    #[test]
-    fn test_coordinate() {
-        let test_cases = [
-            ((10, 10), "J10"),
-            ((1, 7), "a7"),
-            ((8, 5), "h5"),
-            ((9, 1), "I1"),
-            ((6, 8), "f8"),
-            ((26, 2), "z2"),
-        ];
-        for (coord, coord_str) in test_cases {
-            let coordinate = Coordinate::from(coord);
-            assert_eq!(Ok(coordinate), Coordinate::try_from(coord_str.to_string()));
-            assert_eq!(coord_str.to_uppercase(), coordinate.to_string())
+    /// Returns the cells that are not occupied by any ship, row by row.
+    ///
+    /// Decoys occupy their cells too. The cells are yielded lazily, also see
+    /// [`Grid::legal_placements`].
+    pub fn water_cells(&self) -> impl Iterator<Item = Coordinate> + '_ {
+        (1..=self.height)
+            .flat_map(move |y| (1..=self.width).map(move |x| Coordinate::new(x, y)))
+            .filter(move |coordinate| self.ship_at(coordinate).is_none())
+    }
+
+    /// Returns every placement of a straight ship with the given size on the current grid.
+    ///
+    /// Placements that are outside the grid or overlap with the placed ships are skipped,
+    /// so are the placements that touch them if `no_touch` is set.
+    pub fn legal_placements(&self, size: u8, no_touch: bool) -> Vec<Ship> {
+        let orientations = if size == 1 {
+            vec![Orientation::Horizontal]
+        } else {
+            vec![Orientation::Horizontal, Orientation::Vertical]
+        };
+        let mut placements = Vec::new();
+        for orientation in orientations {
+            let ship_type = match ShipType::from_size(size, orientation) {
+                Some(ship_type) => ship_type,
+                None => return Vec::new(),
+            };
+            for y in 1..=self.height {
+                for x in 1..=self.width {
+                    let coords = ship_type.get_hitbox(Coordinate::new(x, y));
+                    let is_legal = coords.iter().all(|c| {
+                        self.contains(c)
+                            && self.ships.iter().flat_map(|ship| ship.coords.iter()).all(|o| {
+                                let dx = (i16::from(c.x) - i16::from(o.x)).abs();
+                                let dy = (i16::from(c.y) - i16::from(o.y)).abs();
+                                if no_touch {
+                                    dx > 1 || dy > 1
+                                } else {
+                                    dx != 0 || dy != 0
+                                }
+                            })
+                    });
+                    if is_legal {
+                        placements.push(Ship::new(ship_type, coords));
+                    }
+                }
+            }
         }
-        assert!(Coordinate::try_from(String::from("test")).is_err());
-        assert!(Coordinate::try_from(String::from("a999")).is_err());
-        assert!(Coordinate::try_from(String::from("42")).is_err());
-        assert_eq!(
-            format!("{:?}", Coordinate::from((10, 2))),
-            "Coordinate { x: 10, y: 2 }"
-        );
+        placements
     }
 
-    #[test]
-    fn test_grid() -> Result<()> {
-        let mut grid = Grid::new(5, 5);
-        assert!(grid.place_ship(Ship::new(ShipType::Boat, vec![Coordinate::from((2, 3))])));
-        assert!(grid.place_ship(Ship::new(ShipType::Boat, vec![Coordinate::from((4, 5))])));
-        assert!(grid.place_ship(Ship::new(
-            ShipType::Destroyer(Orientation::Horizontal),
-            vec![Coordinate::try_from(String::from("D1")).unwrap()]
-        )));
-        assert!(!grid.place_ship(Ship::new(
-            ShipType::Battleship(Orientation::Vertical),
-            ShipType::Battleship(Orientation::Vertical).get_hitbox(Coordinate::from((2, 4)))
-        )));
-        assert!(grid.place_ship(Ship::new(
-            ShipType::Boat,
+    /// Places the ships of the fleet that are not placed yet on random legal cells.
+    ///
+    /// The ships that are already placed are kept, the new ships do not overlap with them
+    /// (or touch them if `no_touch` is set). Returns `false` if the rest of the fleet does not fit.
+    pub fn place_remaining_randomly(&mut self, fleet: &[u8], no_touch: bool, rng: &Rng) -> bool {
+        const MAX_ATTEMPTS: usize = 100;
+        let ship_count = self.ships.len();
+        let mut remaining = self.remaining_to_place(fleet);
+        remaining.sort_unstable_by(|a, b| b.cmp(a));
+        for _ in 0..MAX_ATTEMPTS {
+            let is_placed = remaining.iter().all(|size| {
+                let mut placements = self.legal_placements(*size, no_touch);
+                !placements.is_empty()
+                    && self.place_ship(placements.swap_remove(rng.usize(..placements.len())))
+            });
+            if is_placed {
+                return true;
+            }
+            self.ships.truncate(ship_count);
+        }
+        false
+    }
+
+    /// Returns how close the given coordinate is to the nearest ship cell that is not hit yet.
+    ///
+    /// The distance is measured as the Chebyshev distance (diagonal moves count as one).
+    pub fn proximity_hint(&self, coordinate: &Coordinate) -> Proximity {
+        let distance = self
+            .ships
+            .iter()
+            .flat_map(|ship| ship.coords.iter())
+            .filter(|c| !c.is_hit)
+            .map(|c| {
+                let dx = (i16::from(c.x) - i16::from(coordinate.x)).abs();
+                let dy = (i16::from(c.y) - i16::from(coordinate.y)).abs();
+                dx.max(dy)
+            })
+            .min();
+        match distance {
+            Some(0) | Some(1) => Proximity::Hot,
+            Some(2) => Proximity::Warm,
+            _ => Proximity::Cold,
+        }
+    }
+
+    /// Returns the cells that are adjacent to the ship, diagonals included.
+    ///
+    /// Cells that are outside the grid or a part of the ship itself are skipped.
+    pub fn cells_around_ship(&self, ship: &Ship) -> Vec<Coordinate> {
+        let mut cells = Vec::new();
+        for c in &ship.coords {
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    let (x, y) = (i16::from(c.x) + dx, i16::from(c.y) + dy);
+                    let neighbor = match (u8::try_from(x), u8::try_from(y)) {
+                        (Ok(x), Ok(y)) => Coordinate::in_grid(x, y, self.width, self.height),
+                        _ => None,
+                    };
+                    if let Some(neighbor) = neighbor {
+                        if !ship.contains(&neighbor) && !cells.contains(&neighbor) {
+                            cells.push(neighbor);
+                        }
+                    }
+                }
+            }
+        }
+        cells
+    }
+
+    /// Checks if any of the ships touch each other, even diagonally.
+    ///
+    /// Decoys are counted as ships.
+    pub fn ships_touch(&self) -> bool {
+        self.ships.iter().any(|ship| {
+            self.cells_around_ship(ship)
+                .iter()
+                .any(|c| self.ship_at(c).is_some())
+        })
+    }
+
+    /// Returns the number of ship cells that are next to the given coordinate (0-8).
+    ///
+    /// Diagonal neighbors are counted too, unlike [`Grid::proximity_hint`]
+    /// it tells the exact count instead of a category.
+    pub fn adjacent_ship_count(&self, coordinate: &Coordinate) -> usize {
+        self.ships
+            .iter()
+            .flat_map(|ship| ship.coords.iter())
+            .filter(|c| {
+                let dx = (i16::from(c.x) - i16::from(coordinate.x)).abs();
+                let dy = (i16::from(c.y) - i16::from(coordinate.y)).abs();
+                dx <= 1 && dy <= 1 && (dx, dy) != (0, 0)
+            })
+            .count()
+    }
+
+    /// Marks the water cells around the ship at the given coordinate as missed shots.
+    ///
+    /// Cells that are already shot or occupied by another ship are skipped.
+    /// Returns the newly marked cells.
+    pub fn reveal_around_ship(&mut self, coordinate: &Coordinate) -> Vec<Coordinate> {
+        let revealed = match self.ship_at(coordinate) {
+            Some(ship) => self
+                .cells_around_ship(ship)
+                .into_iter()
+                .filter(|c| self.ship_at(c).is_none() && !self.hits.contains(c))
+                .collect::<Vec<Coordinate>>(),
+            None => return Vec::new(),
+        };
+        self.hits.extend(revealed.iter().copied());
+        revealed
+    }
+
+    /// Validates the ships on the grid against the given fleet.
+    ///
+    /// Checks that every ship is inside the grid, ships do not overlap
+    /// (or touch each other if `no_touch` is set) and the ship sizes match the fleet exactly.
+    /// Returns an error that describes the first problem. Decoys are not counted as a part of the fleet.
+    pub fn validate_against_fleet(&self, fleet: &[u8], no_touch: bool) -> Result<()> {
+        for ship in &self.ships {
+            if let Some(coordinate) = ship
+                .coords
+                .iter()
+                .find(|c| !self.contains(c))
+            {
+                return Err(format!("Ship at {} is outside the grid.", coordinate).into());
+            }
+        }
+        for (i, ship) in self.ships.iter().enumerate() {
+            for other in &self.ships[i + 1..] {
+                if let Some(coordinate) = ship.coords.iter().find(|c| other.contains(c)) {
+                    return Err(format!("Ships overlap at {}.", coordinate).into());
+                }
+                if no_touch {
+                    let around = self.cells_around_ship(other);
+                    if let Some(coordinate) = ship.coords.iter().find(|c| around.contains(c)) {
+                        return Err(format!("Ships touch each other at {}.", coordinate).into());
+                    }
+                }
+            }
+        }
+        let mut counts = self.ship_count_by_size();
+        for size in fleet {
+            match counts.get_mut(size) {
+                Some(count) if *count > 0 => *count -= 1,
+                _ => return Err(format!("Missing a size-{} ship.", size).into()),
+            }
+        }
+        if let Some((size, _)) = counts.iter().find(|(_, count)| **count > 0) {
+            return Err(format!("Unexpected size-{} ship.", size).into());
+        }
+        Ok(())
+    }
+
+    /// Returns the ship sizes of the given fleet that are not placed on the grid yet.
+    ///
+    /// Sizes are returned in the order of the fleet.
+    pub fn remaining_to_place(&self, fleet: &[u8]) -> Vec<u8> {
+        let mut placed = self
+            .ships
+            .iter()
+            .map(|ship| ship.coords.len())
+            .collect::<Vec<usize>>();
+        fleet
+            .iter()
+            .filter(|size| {
+                if let Some(i) = placed.iter().position(|s| *s == usize::from(**size)) {
+                    placed.remove(i);
+                    false
+                } else {
+                    true
+                }
+            })
+            .copied()
+            .collect()
+    }
+
+    /// Checks if all ships of the given fleet are placed on the grid.
+    pub fn placement_complete(&self, fleet: &[u8]) -> bool {
+        self.remaining_to_place(fleet).is_empty()
+    }
+
+    /// Returns a random ship coordinate that is not hit yet.
+    ///
+    /// Returns `None` if all the ships are sunk.
+    pub fn random_intact_coordinate(&self) -> Option<Coordinate> {
+        let coordinates = self
+            .ships
+            .iter()
+            .flat_map(|ship| ship.coords.iter())
+            .filter(|coord| !coord.is_hit)
+            .collect::<Vec<&Coordinate>>();
+        if coordinates.is_empty() {
+            None
+        } else {
+            Some(*coordinates[fastrand::usize(..coordinates.len())])
+        }
+    }
+
+    /// Returns a random coordinate that is not shot yet.
+    ///
+    /// Returns `None` if every cell is already shot.
+    pub fn random_unfired_coordinate(&self) -> Option<Coordinate> {
+        let coordinates = (1..=self.height)
+            .flat_map(|y| (1..=self.width).map(move |x| Coordinate::new(x, y)))
+            .filter(|coordinate| !self.is_shot_at(coordinate))
+            .collect::<Vec<Coordinate>>();
+        if coordinates.is_empty() {
+            None
+        } else {
+            Some(coordinates[fastrand::usize(..coordinates.len())])
+        }
+    }
+
+    /// Checks if there is a ship in the given column.
+    ///
+    /// Columns start from 1 (`A`). Decoys are counted as ships.
+    pub fn scan_col(&self, column: u8) -> bool {
+        self.ships
+            .iter()
+            .any(|ship| ship.coords.iter().any(|c| c.x == column))
+    }
+
+    /// Parses the coordinate that is entered by a player, e.g. `C3`.
+    ///
+    /// The row is counted from the [`Grid::origin`], rows outside the grid are kept as is.
+    pub fn parse_coordinate(&self, s: &str) -> Option<Coordinate> {
+        let mut coordinate = Coordinate::try_from(s.to_string()).ok()?;
+        coordinate.y = self.row_number(coordinate.y);
+        Some(coordinate)
+    }
+
+    /// Returns the label of the coordinate that is shown to the players, e.g. `C3`.
+    ///
+    /// It is the inverse of [`Grid::parse_coordinate`].
+    pub fn coordinate_label(&self, coordinate: Coordinate) -> String {
+        Coordinate::new(coordinate.x, self.row_number(coordinate.y)).to_string()
+    }
+
+    /// Converts between the stored row and the row number from the [`Grid::origin`].
+    ///
+    /// The conversion is its own inverse.
+    fn row_number(&self, y: u8) -> u8 {
+        match self.origin {
+            RowOrigin::Bottom if y >= 1 && y <= self.height => self.height + 1 - y,
+            _ => y,
+        }
+    }
+
+    /// Returns the grid as string.
+    ///
+    /// Only hits/misses are shown if `show_ships` is true.
+    pub fn as_string(&self, show_ships: bool) -> Result<String> {
+        self.as_string_with_glyphs(show_ships, &GlyphSet::default())
+    }
+
+    /// Returns the grid as string using the given glyphs.
+    ///
+    /// Also see [`Grid::as_string`].
+    pub fn as_string_with_glyphs(&self, show_ships: bool, glyphs: &GlyphSet) -> Result<String> {
+        let mut s = Vec::new();
+        self.display(&mut s, |coordinate| self.point_glyph(coordinate, show_ships, glyphs))?;
+        Ok(str::from_utf8(&s)?.to_string())
+    }
+
+    /// Returns the rows of the grid as strings, without the headers and the row labels.
+    ///
+    /// It is meant for custom layouts, e.g. showing two grids side by side.
+    /// Also see [`Grid::as_string`].
+    pub fn rows(&self, reveal_ships: bool) -> Vec<String> {
+        self.rows_with_glyphs(reveal_ships, &GlyphSet::default())
+    }
+
+    /// Returns the rows of the grid as strings using the given glyphs.
+    ///
+    /// Also see [`Grid::rows`].
+    pub fn rows_with_glyphs(&self, reveal_ships: bool, glyphs: &GlyphSet) -> Vec<String> {
+        (1..=self.height)
+            .map(|y| self.row(y, |coordinate| self.point_glyph(coordinate, reveal_ships, glyphs)))
+            .collect()
+    }
+
+    /// Returns the rows of the tracking view of the grid as strings.
+    ///
+    /// Also see [`Grid::rows`] and [`Grid::as_tracking_string`].
+    pub fn tracking_rows(
+        &self,
+        last_shot: Option<Coordinate>,
+        show_ships: bool,
+        glyphs: &GlyphSet,
+    ) -> Vec<String> {
+        (1..=self.height)
+            .map(|y| {
+                self.row(y, |coordinate| {
+                    self.tracking_glyph(coordinate, last_shot, show_ships, glyphs)
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the header of the grid with the column labels.
+    ///
+    /// It is padded from the left by the width of the row labels.
+    pub fn header(&self) -> String {
+        let labels = ALPHABET
+            .chars()
+            .take(usize::from(self.width))
+            .map(|c| {
+                let label = format!("{} ", c.to_uppercase());
+                format!("|{}", Style::new().underline().paint(label))
+            })
+            .collect::<String>();
+        format!("{}{}", " ".repeat(self.label_width()), labels)
+    }
+
+    /// Returns the label of the given row, right-aligned to the widest one.
+    ///
+    /// Rows start from 1 at the top, the label is counted from the [`Grid::origin`].
+    pub fn row_label(&self, y: u8) -> String {
+        let label = format!("{:width$}", self.row_number(y), width = self.label_width());
+        Style::new().underline().paint(label).to_string()
+    }
+
+    /// Returns the visible width of the grid lines including the row labels.
+    pub fn display_width(&self) -> usize {
+        self.label_width() + 3 * usize::from(self.width)
+    }
+
+    /// Returns the tracking view of the grid as string.
+    ///
+    /// Only the hits and misses are shown, ships are hidden.
+    /// The last shot is shown with a distinct character if it is given.
+    pub fn as_tracking_string(
+        &self,
+        last_shot: Option<Coordinate>,
+        glyphs: &GlyphSet,
+    ) -> Result<String> {
+        self.tracking_string(last_shot, false, glyphs)
+    }
+
+    /// Returns the tracking view of the grid with the ships that are not hit yet.
+    ///
+    /// Also see [`Grid::as_tracking_string`].
+    pub fn as_revealed_tracking_string(
+        &self,
+        last_shot: Option<Coordinate>,
+        glyphs: &GlyphSet,
+    ) -> Result<String> {
+        self.tracking_string(last_shot, true, glyphs)
+    }
+
+    /// Returns the tracking view of the grid as string, optionally with the ships.
+    fn tracking_string(
+        &self,
+        last_shot: Option<Coordinate>,
+        show_ships: bool,
+        glyphs: &GlyphSet,
+    ) -> Result<String> {
+        let mut s = Vec::new();
+        self.display(&mut s, |coordinate| {
+            self.tracking_glyph(coordinate, last_shot, show_ships, glyphs)
+        })?;
+        Ok(str::from_utf8(&s)?.to_string())
+    }
+
+    /// Returns the grid as string surrounded by a box border.
+    ///
+    /// The optional title is shown above the border.
+    /// Also see [`Grid::as_string`].
+    pub fn as_bordered_string(&self, show_ships: bool, title: Option<&str>) -> Result<String> {
+        let grid = self.as_string(show_ships)?;
+        let border = BORDER_HORIZONTAL.repeat(self.display_width());
+        let mut s = String::from("\n");
+        if let Some(title) = title {
+            s += &format!("{}\n", title);
+        }
+        s += &format!("{}{}{}\n", BORDER_TOP_LEFT, border, BORDER_TOP_RIGHT);
+        for line in grid.lines().skip(1) {
+            s += &format!("{}{}{}\n", BORDER_VERTICAL, line, BORDER_VERTICAL);
+        }
+        s += &format!("{}{}{}\n", BORDER_BOTTOM_LEFT, border, BORDER_BOTTOM_RIGHT);
+        Ok(s)
+    }
+
+    /// Returns the glyph of a point on the grid.
+    ///
+    /// The point might be empty or a part of a ship.
+    fn point_glyph(&self, coordinate: Coordinate, show_ships: bool, glyphs: &GlyphSet) -> String {
+        if let Some(ship) = self.ship_at(&coordinate) {
+            let is_hit = ship
+                .coords
+                .iter()
+                .find(|c| *c == &coordinate)
+                .map(|c| c.is_hit) == Some(true);
+            if is_hit && ship.is_sunk() {
+                glyphs.sunk.clone()
+            } else if is_hit {
+                glyphs.hit.clone()
+            } else if show_ships {
+                match &glyphs.ship {
+                    Some(ship_glyph) => ship_glyph.clone(),
+                    None => ship.type_.to_string(),
+                }
+            } else {
+                glyphs.miss.clone()
+            }
+        } else {
+            Style::new().underline().paint(&glyphs.water).to_string()
+        }
+    }
+
+    /// Returns the glyph of a point on the tracking view of the grid.
+    ///
+    /// The point might be empty, a hit or a miss.
+    /// Ships that are not hit yet are only shown if `show_ships` is set.
+    fn tracking_glyph(
+        &self,
+        coordinate: Coordinate,
+        last_shot: Option<Coordinate>,
+        show_ships: bool,
+        glyphs: &GlyphSet,
+    ) -> String {
+        if !self.hits.contains(&coordinate) {
+            return match self.ship_at(&coordinate) {
+                Some(_) if show_ships => self.point_glyph(coordinate, true, glyphs),
+                _ => Style::new().underline().paint(&glyphs.water).to_string(),
+            };
+        }
+        let is_last_shot = last_shot == Some(coordinate);
+        match self.ship_at(&coordinate) {
+            Some(_) if is_last_shot => glyphs.last_hit.clone(),
+            Some(ship) if ship.is_sunk() => glyphs.sunk.clone(),
+            Some(_) => glyphs.hit.clone(),
+            None if is_last_shot => glyphs.last_miss.clone(),
+            None => glyphs.miss.clone(),
+        }
+    }
+
+    /// Returns the width of the row labels.
+    ///
+    /// Row numbers are right-aligned to the widest one so that the columns stay aligned.
+    fn label_width(&self) -> usize {
+        self.height.to_string().len().max(2)
+    }
+
+    /// Returns a row of the grid, each point is displayed with the glyph of the given function.
+    ///
+    /// Rows start from 1.
+    fn row<F: Fn(Coordinate) -> String>(&self, y: u8, glyph: F) -> String {
+        (1..=self.width)
+            .map(|x| format!("|{}", glyph(Coordinate::new(x, y))))
+            .collect()
+    }
+
+    /// Prints the grid to the given output.
+    ///
+    /// Each point is displayed with the glyph that is returned by the given function.
+    fn display<W: Write, F: Fn(Coordinate) -> String>(
+        &self,
+        out: &mut W,
+        glyph: F,
+    ) -> IoResult<()> {
+        writeln!(out)?;
+        writeln!(out, "{}", self.header())?;
+        for h in 1..=self.height {
+            writeln!(out, "{}{}", self.row_label(h), self.row(h, &glyph))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ship::{Orientation, STANDARD_FLEET};
+
+    /// Removes the ANSI escape sequences from the given string.
+    fn strip_ansi(s: &str) -> String {
+        let mut output = String::new();
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            if c == '\u{1b}' {
+                chars.by_ref().find(|c| *c == 'm');
+            } else {
+                output.push(c);
+            }
+        }
+        output
+    }
+
+    #[test]
+    fn test_coordinate_in_grid() {
+        assert!(!Coordinate::new(2, 3).is_hit);
+        assert_eq!(Some(Coordinate::new(1, 1)), Coordinate::in_grid(1, 1, 3, 2));
+        assert_eq!(Some(Coordinate::new(3, 2)), Coordinate::in_grid(3, 2, 3, 2));
+        assert_eq!(None, Coordinate::in_grid(0, 1, 3, 2));
+        assert_eq!(None, Coordinate::in_grid(1, 0, 3, 2));
+        assert_eq!(None, Coordinate::in_grid(4, 2, 3, 2));
+        assert_eq!(None, Coordinate::in_grid(3, 3, 3, 2));
+    }
+
+    #[test]
+    fn test_grid_contains() {
+        let grid = Grid::new(3, 2);
+        assert!(grid.contains(&Coordinate::new(1, 1)));
+        assert!(grid.contains(&Coordinate::new(3, 2)));
+        assert!(!grid.contains(&Coordinate::new(0, 1)));
+        assert!(!grid.contains(&Coordinate::new(3, 3)));
+    }
+
+    #[test]
+    fn test_coordinate() {
+        let test_cases = [
+            ((10, 10), "J10"),
+            ((1, 7), "a7"),
+            ((8, 5), "h5"),
+            ((9, 1), "I1"),
+            ((6, 8), "f8"),
+            ((26, 2), "z2"),
+        ];
+        for (coord, coord_str) in test_cases {
+            let coordinate = Coordinate::from(coord);
+            assert_eq!(Ok(coordinate), Coordinate::try_from(coord_str.to_string()));
+            assert_eq!(coord_str.to_uppercase(), coordinate.to_string())
+        }
+        assert!(Coordinate::try_from(String::from("test")).is_err());
+        assert!(Coordinate::try_from(String::from("a999")).is_err());
+        assert!(Coordinate::try_from(String::from("42")).is_err());
+        assert_eq!(
+            Ok(Coordinate::from((3, 5))),
+            Coordinate::try_from(String::from("C5\r\n"))
+        );
+        assert_eq!(
+            Ok(Coordinate::from((3, 5))),
+            Coordinate::try_from(String::from("c5\r"))
+        );
+        assert_eq!(
+            format!("{:?}", Coordinate::from((10, 2))),
+            "Coordinate { x: 10, y: 2 }"
+        );
+    }
+
+    #[test]
+    fn test_grid() -> Result<()> {
+        let mut grid = Grid::new(5, 5);
+        assert!(grid.place_ship(Ship::new(ShipType::Boat, vec![Coordinate::from((2, 3))])));
+        assert!(grid.place_ship(Ship::new(ShipType::Boat, vec![Coordinate::from((4, 5))])));
+        assert!(grid.place_ship(Ship::new(
+            ShipType::Destroyer(Orientation::Horizontal),
+            vec![Coordinate::try_from(String::from("D1")).expect("invalid coordinate")]
+        )));
+        assert!(!grid.place_ship(Ship::new(
+            ShipType::Battleship(Orientation::Vertical),
+            ShipType::Battleship(Orientation::Vertical).get_hitbox(Coordinate::from((2, 4)))
+        )));
+        assert!(grid.place_ship(Ship::new(
+            ShipType::Boat,
             vec![{
-                let mut coordinate = Coordinate::try_from(String::from("B5")).unwrap();
+                let mut coordinate =
+                    Coordinate::try_from(String::from("B5")).expect("invalid coordinate");
                 coordinate.is_hit = true;
                 coordinate
             }]
         )));
         assert_eq!(
             r#"
-   A B C D E 
-1  • • • ▭ • 
-2  • • • • • 
-3  • △ • • • 
-4  • • • • • 
-5  • ☒ • △ • 
+  |A |B |C |D |E 
+ 1|  |  |  |👾|  
+ 2|  |  |  |  |  
+ 3|  |⛵|  |  |  
+ 4|  |  |  |  |  
+ 5|  |🔥|  |⛵|  
 "#,
-            grid.as_string(true)?
+            strip_ansi(&grid.as_string(true)?)
         );
         assert_eq!(
             r#"
-   A B C D E 
-1  • • • ✕ • 
-2  • • • • • 
-3  • ✕ • • • 
-4  • • • • • 
-5  • ☒ • ✕ • 
+  |A |B |C |D |E 
+ 1|  |  |  |🌀|  
+ 2|  |  |  |  |  
+ 3|  |🌀|  |  |  
+ 4|  |  |  |  |  
+ 5|  |🔥|  |🌀|  
 "#,
-            grid.as_string(false)?
+            strip_ansi(&grid.as_string(false)?)
         );
 
         let grid = Grid::new_random(15, 15);
         assert!(!grid.ships.is_empty());
         Ok(())
     }
+
+    #[test]
+    fn test_tall_grid() -> Result<()> {
+        let mut grid = Grid::new(3, 12);
+        assert!(grid.place_ship(Ship::new(ShipType::Boat, vec![Coordinate::from((2, 9))])));
+        assert!(grid.place_ship(Ship::new(ShipType::Boat, vec![Coordinate::from((3, 12))])));
+        assert_eq!(
+            r#"
+  |A |B |C 
+ 1|  |  |  
+ 2|  |  |  
+ 3|  |  |  
+ 4|  |  |  
+ 5|  |  |  
+ 6|  |  |  
+ 7|  |  |  
+ 8|  |  |  
+ 9|  |⛵|  
+10|  |  |  
+11|  |  |  
+12|  |  |⛵
+"#,
+            strip_ansi(&grid.as_string(true)?)
+        );
+        let label_ends = strip_ansi(&grid.as_string(true)?)
+            .lines()
+            .skip(1)
+            .map(|line| line.find('|'))
+            .collect::<Vec<Option<usize>>>();
+        assert!(label_ends.iter().all(|end| *end == Some(2)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_proximity_hint() {
+        let mut grid = Grid::new(10, 10);
+        assert_eq!(Proximity::Cold, grid.proximity_hint(&Coordinate::from((1, 1))));
+        grid.place_ship(Ship::new(
+            ShipType::Destroyer(Orientation::Horizontal),
+            ShipType::Destroyer(Orientation::Horizontal).get_hitbox(Coordinate::from((5, 5))),
+        ));
+        assert_eq!(Proximity::Hot, grid.proximity_hint(&Coordinate::from((4, 4))));
+        assert_eq!(Proximity::Hot, grid.proximity_hint(&Coordinate::from((7, 5))));
+        assert_eq!(Proximity::Warm, grid.proximity_hint(&Coordinate::from((8, 7))));
+        assert_eq!(Proximity::Warm, grid.proximity_hint(&Coordinate::from((5, 3))));
+        assert_eq!(Proximity::Cold, grid.proximity_hint(&Coordinate::from((1, 1))));
+        assert_eq!(Proximity::Cold, grid.proximity_hint(&Coordinate::from((9, 5))));
+        grid.ships[0].coords[1].is_hit = true;
+        assert_eq!(Proximity::Warm, grid.proximity_hint(&Coordinate::from((7, 5))));
+        assert_eq!("hot", Proximity::Hot.to_string());
+    }
+
+    #[test]
+    fn test_validate_against_fleet() {
+        let fleet = [3, 2];
+        let ship = |ship_type: ShipType, coordinate: (u8, u8)| {
+            Ship::new(ship_type, ship_type.get_hitbox(Coordinate::from(coordinate)))
+        };
+        let submarine = ShipType::Submarine(Orientation::Horizontal);
+        let destroyer = ShipType::Destroyer(Orientation::Vertical);
+        let validate = |ships: Vec<Ship>, no_touch: bool| -> StdResult<(), String> {
+            let mut grid = Grid::new(5, 5);
+            grid.ships = ships;
+            grid.validate_against_fleet(&fleet, no_touch)
+                .map_err(|e| e.to_string())
+        };
+        assert_eq!(
+            Ok(()),
+            validate(vec![ship(submarine, (1, 1)), ship(destroyer, (5, 4))], true)
+        );
+        assert_eq!(
+            Err(String::from("Ship at F1 is outside the grid.")),
+            validate(vec![ship(submarine, (4, 1)), ship(destroyer, (1, 4))], false)
+        );
+        assert_eq!(
+            Err(String::from("Ships overlap at B1.")),
+            validate(vec![ship(submarine, (1, 1)), ship(destroyer, (2, 1))], false)
+        );
+        assert_eq!(
+            Ok(()),
+            validate(vec![ship(submarine, (1, 1)), ship(destroyer, (4, 2))], false)
+        );
+        assert_eq!(
+            Err(String::from("Ships touch each other at C1.")),
+            validate(vec![ship(submarine, (1, 1)), ship(destroyer, (4, 2))], true)
+        );
+        assert_eq!(
+            Err(String::from("Missing a size-2 ship.")),
+            validate(vec![ship(submarine, (1, 1))], false)
+        );
+        assert_eq!(
+            Err(String::from("Unexpected size-1 ship.")),
+            validate(
+                vec![
+                    ship(submarine, (1, 1)),
+                    ship(destroyer, (5, 4)),
+                    ship(ShipType::Boat, (1, 5))
+                ],
+                false
+            )
+        );
+        assert_eq!(
+            Ok(()),
+            validate(
+                vec![
+                    ship(submarine, (1, 1)),
+                    ship(destroyer, (5, 4)),
+                    Ship::new_decoy(Coordinate::from((1, 5)))
+                ],
+                false
+            )
+        );
+    }
+
+    #[test]
+    fn test_placement_complete() {
+        let fleet = [4, 3, 3, 2];
+        let mut grid = Grid::new(10, 10);
+        assert_eq!(fleet.to_vec(), grid.remaining_to_place(&fleet));
+        assert!(grid.place_ship(Ship::new(
+            ShipType::Submarine(Orientation::Vertical),
+            ShipType::Submarine(Orientation::Vertical).get_hitbox(Coordinate::from((1, 1)))
+        )));
+        assert!(grid.place_ship(Ship::new(
+            ShipType::Destroyer(Orientation::Vertical),
+            ShipType::Destroyer(Orientation::Vertical).get_hitbox(Coordinate::from((5, 5)))
+        )));
+        assert_eq!(vec![4, 3], grid.remaining_to_place(&fleet));
+        assert!(!grid.placement_complete(&fleet));
+        assert!(grid.place_ship(Ship::new(
+            ShipType::Cruiser(Orientation::Horizontal),
+            ShipType::Cruiser(Orientation::Horizontal).get_hitbox(Coordinate::from((3, 10)))
+        )));
+        assert!(grid.place_ship(Ship::new(
+            ShipType::Submarine(Orientation::Horizontal),
+            ShipType::Submarine(Orientation::Horizontal).get_hitbox(Coordinate::from((7, 1)))
+        )));
+        assert!(grid.remaining_to_place(&fleet).is_empty());
+        assert!(grid.placement_complete(&fleet));
+        assert!(!grid.is_defeated());
+        grid.ships
+            .iter_mut()
+            .for_each(|ship| ship.coords.iter_mut().for_each(|c| c.is_hit = true));
+        assert!(grid.is_defeated());
+        assert!(grid.place_random_decoy(&Rng::new()));
+        assert!(grid.ships[grid.ships.len() - 1].decoy);
+        assert!(grid.is_defeated());
+    }
+
+    #[test]
+    fn test_random_intact_coordinate() {
+        let mut grid = Grid::new(5, 5);
+        assert_eq!(None, grid.random_intact_coordinate());
+        grid.place_ship(Ship::new(
+            ShipType::Destroyer(Orientation::Horizontal),
+            ShipType::Destroyer(Orientation::Horizontal).get_hitbox(Coordinate::from((1, 1))),
+        ));
+        grid.place_ship(Ship::new(ShipType::Boat, vec![Coordinate::from((4, 4))]));
+        grid.ships[0].coords[0].is_hit = true;
+        for _ in 0..10 {
+            let coordinate = grid.random_intact_coordinate().expect("no intact cell is found");
+            assert!([Coordinate::from((2, 1)), Coordinate::from((4, 4))].contains(&coordinate));
+        }
+        grid.ships
+            .iter_mut()
+            .for_each(|ship| ship.coords.iter_mut().for_each(|c| c.is_hit = true));
+        assert_eq!(None, grid.random_intact_coordinate());
+    }
+
+    #[test]
+    fn test_random_unfired_coordinate() {
+        let mut grid = Grid::new(2, 2);
+        for (x, y) in [(1, 1), (2, 1), (2, 2)] {
+            grid.hits.push(Coordinate::from((x, y)));
+        }
+        for _ in 0..10 {
+            assert_eq!(Some(Coordinate::from((1, 2))), grid.random_unfired_coordinate());
+        }
+        grid.hits.push(Coordinate::from((1, 2)));
+        assert_eq!(None, grid.random_unfired_coordinate());
+    }
+
+    #[test]
+    fn test_bordered_grid() -> Result<()> {
+        let mut grid = Grid::new(3, 3);
+        assert!(grid.place_ship(Ship::new(ShipType::Boat, vec![Coordinate::from((2, 2))])));
+        assert_eq!(
+            r#"
+Bob's fleet
+┌───────────┐
+│  |A |B |C │
+│ 1|  |  |  │
+│ 2|  |⛵|  │
+│ 3|  |  |  │
+└───────────┘
+"#,
+            strip_ansi(&grid.as_bordered_string(true, Some("Bob's fleet"))?)
+        );
+        assert_eq!(
+            r#"
+┌───────────┐
+│  |A |B |C │
+│ 1|  |  |  │
+│ 2|  |🌀|  │
+│ 3|  |  |  │
+└───────────┘
+"#,
+            strip_ansi(&grid.as_bordered_string(false, None)?)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_grid_from_ascii() -> Result<()> {
+        let grid = Grid::from_ascii(
+            r#"
+            #..#
+            #...
+            ....
+            .##.
+            "#,
+        )?;
+        let mut expected = Grid::new(4, 4);
+        for (ship_type, coordinate) in [
+            (ShipType::Destroyer(Orientation::Vertical), (1, 1)),
+            (ShipType::Boat, (4, 1)),
+            (ShipType::Destroyer(Orientation::Horizontal), (2, 4)),
+        ] {
+            let ship = Ship::new(ship_type, ship_type.get_hitbox(Coordinate::from(coordinate)));
+            assert!(expected.place_ship(ship));
+        }
+        assert_eq!(expected, grid);
+        assert_eq!(expected.as_string(true)?, grid.as_string(true)?);
+
+        let grid = Grid::from_ascii("##.\n##.\n##.")?;
+        assert_eq!(
+            Some(ShipType::Battleship(Orientation::Vertical)),
+            grid.ships.first().map(|ship| ship.type_)
+        );
+
+        let error = |layout: &str| Grid::from_ascii(layout).map(|_| ()).map_err(|e| e.to_string());
+        assert_eq!(Err(String::from("[!] Ship at A1 has an invalid shape.")), error("##\n#."));
+        assert_eq!(Err(String::from("[!] Row 2 has a different width.")), error("..\n..."));
+        assert_eq!(Err(String::from("[!] Invalid character in layout: x")), error(".x"));
+        assert_eq!(Err(String::from("[!] Invalid layout size: 0x0")), error(""));
+        Ok(())
+    }
+
+    #[test]
+    fn test_ship_at() -> Result<()> {
+        let mut grid = Grid::from_ascii(".###\n....\n#...")?;
+        let submarine = ShipType::Submarine(Orientation::Horizontal);
+        assert_eq!(Some(submarine), grid.ship_at(&Coordinate::from((3, 1))).map(|s| s.type_));
+        assert_eq!(Some(ShipType::Boat), grid.ship_at(&Coordinate::from((1, 3))).map(|s| s.type_));
+        assert!(grid.ship_at(&Coordinate::from((2, 2))).is_none());
+
+        // Cells at the ends of the ship and right next to them.
+        assert!(grid.ship_at(&Coordinate::from((2, 1))).is_some());
+        assert!(grid.ship_at(&Coordinate::from((4, 1))).is_some());
+        assert!(grid.ship_at(&Coordinate::from((1, 1))).is_none());
+        assert!(grid.ship_at(&Coordinate::from((5, 1))).is_none());
+
+        if let Some(ship) = grid.ship_at_mut(&Coordinate::from((1, 3))) {
+            ship.coords[0].is_hit = true;
+        }
+        assert_eq!(Some(true), grid.ship_at(&Coordinate::from((1, 3))).map(Ship::is_sunk));
+        Ok(())
+    }
+
+    #[test]
+    fn test_overlap_cells() -> Result<()> {
+        let mut grid = Grid::from_ascii("###.\n....\n....")?;
+        assert!(grid.overlap_cells().is_empty());
+        assert!(!grid.has_overlaps());
+
+        // Ships that bypass the checks of `Grid::place_ship`.
+        let destroyer = ShipType::Destroyer(Orientation::Horizontal);
+        grid.ships.push(Ship::new(destroyer, destroyer.get_hitbox(Coordinate::from((2, 1)))));
+        grid.ships.push(Ship::new(ShipType::Boat, vec![Coordinate::from((3, 1))]));
+        assert_eq!(vec![Coordinate::from((2, 1)), Coordinate::from((3, 1))], grid.overlap_cells());
+        assert!(grid.has_overlaps());
+        Ok(())
+    }
+
+    #[test]
+    fn test_water_cells() -> Result<()> {
+        let mut grid = Grid::from_ascii(
+            r#"
+            ###.
+            ....
+            #...
+            "#,
+        )?;
+        assert_eq!(8, grid.water_cells().count());
+        assert_eq!(Some(Coordinate::new(4, 1)), grid.water_cells().next());
+        assert!(grid.water_cells().all(|c| c.x <= grid.width && c.y <= grid.height));
+        grid.place_ship(Ship::new_decoy(Coordinate::new(4, 3)));
+        assert_eq!(7, grid.water_cells().count());
+        assert_eq!(0, Grid::new(0, 0).water_cells().count());
+        Ok(())
+    }
+
+    #[test]
+    fn test_legal_placements() -> Result<()> {
+        let grid = Grid::new(3, 3);
+        assert_eq!(12, grid.legal_placements(2, false).len());
+        assert_eq!(9, grid.legal_placements(1, false).len());
+        assert!(grid.legal_placements(4, false).is_empty());
+        assert!(grid.legal_placements(0, false).is_empty());
+
+        let grid = Grid::from_ascii("#..\n...\n...")?;
+        assert_eq!(10, grid.legal_placements(2, false).len());
+        assert_eq!(4, grid.legal_placements(2, true).len());
+        assert!(grid
+            .legal_placements(2, true)
+            .iter()
+            .all(|ship| ship.coords.iter().all(|c| c.x == 3 || c.y == 3)));
+
+        let mut grid = Grid::new(2, 2);
+        for (x, y) in [(1, 1), (2, 1), (1, 2), (2, 2)] {
+            grid.place_ship(Ship::new(ShipType::Boat, vec![Coordinate::from((x, y))]));
+        }
+        assert!(grid.legal_placements(1, false).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_hit_count() -> Result<()> {
+        let mut grid = Grid::from_ascii("##.\n...\n..#")?;
+        assert_eq!((0, 0), (grid.hit_count(), grid.miss_count()));
+        for (x, y) in [(1, 1), (3, 1), (2, 2), (3, 3), (1, 3)] {
+            grid.hits.push(Coordinate::from((x, y)));
+        }
+        assert_eq!(2, grid.hit_count());
+        assert_eq!(3, grid.miss_count());
+        Ok(())
+    }
+
+    #[test]
+    fn test_tracking_bitfields() -> Result<()> {
+        let mut grid = Grid::from_ascii("##.\n...\n..#")?;
+        let shots = [(1, 1), (3, 1), (2, 2), (3, 3), (1, 3)];
+        for (x, y) in shots.iter() {
+            grid.hits.push(Coordinate::from((*x, *y)));
+        }
+        let (hits, misses) = grid.tracking_bitfields();
+        assert_eq!(vec![0b0000_0001, 0b1], hits);
+        assert_eq!(vec![0b0101_0100, 0b0], misses);
+
+        let decode = |bitfield: &[u8]| {
+            (0..9)
+                .filter(|i| bitfield[i / 8] & (1 << (i % 8)) != 0)
+                .map(|i| Coordinate::from((i as u8 % 3 + 1, i as u8 / 3 + 1)))
+                .collect::<Vec<Coordinate>>()
+        };
+        let (decoded_hits, decoded_misses) = (decode(&hits), decode(&misses));
+        assert_eq!(grid.hit_count(), decoded_hits.len());
+        assert_eq!(grid.miss_count(), decoded_misses.len());
+        assert!(decoded_hits.iter().all(|c| grid.hits.contains(c) && grid.ship_at(c).is_some()));
+        assert!(decoded_misses.iter().all(|c| grid.hits.contains(c) && grid.ship_at(c).is_none()));
+        assert_eq!((vec![0], vec![0]), Grid::new(2, 2).tracking_bitfields());
+        Ok(())
+    }
+
+    #[test]
+    fn test_snapshot() -> Result<()> {
+        let mut grid = Grid::from_ascii("##.\n...\n..#")?;
+        grid.ships[1].decoy = true;
+        for (x, y) in [(1, 1), (2, 2), (3, 1)].iter() {
+            grid.fire_at(Coordinate::from((*x, *y)));
+        }
+        let snapshot = grid.to_snapshot();
+        let restored = Grid::from_snapshot(&snapshot)?;
+        assert_eq!(grid, restored);
+        assert_eq!(grid.hits, restored.hits);
+        for (ship, restored_ship) in grid.ships.iter().zip(restored.ships.iter()) {
+            assert_eq!(ship.type_, restored_ship.type_);
+            assert_eq!(ship.decoy, restored_ship.decoy);
+            let is_hit = |ship: &Ship| ship.coords.iter().map(|c| c.is_hit).collect::<Vec<bool>>();
+            assert_eq!(is_hit(ship), is_hit(restored_ship));
+        }
+        let is_hit = restored.ships[0].coords.iter().map(|c| c.is_hit).collect::<Vec<bool>>();
+        assert_eq!(vec![true, false], is_hit);
+        assert_eq!(snapshot, restored.to_snapshot());
+
+        assert!(Grid::from_snapshot("3x3;;").is_ok());
+        assert!(Grid::from_snapshot("3x3;2h:A1").is_err());
+        assert!(Grid::from_snapshot("3x3;9h:A1;").is_err());
+        assert!(Grid::from_snapshot("3x3;1v:A1;Z").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_place_malformed_ship() {
+        let mut grid = Grid::new(5, 5);
+        let destroyer = ShipType::Destroyer(Orientation::Horizontal);
+        let gapped = vec![Coordinate::from((1, 1)), Coordinate::from((3, 1))];
+        assert!(!grid.place_ship(Ship::new(destroyer, gapped)));
+        let battleship = ShipType::Battleship(Orientation::Horizontal);
+        let block = battleship.get_hitbox(Coordinate::from((1, 1)));
+        assert!(grid.place_ship(Ship::new(battleship, block)));
+        assert_eq!(1, grid.ships.len());
+    }
+
+    #[test]
+    fn test_scan_col() -> Result<()> {
+        let grid = Grid::from_ascii(".#.\n.#.\n...")?;
+        assert!(!grid.scan_col(1));
+        assert!(grid.scan_col(2));
+        assert!(!grid.scan_col(3));
+        Ok(())
+    }
+
+    #[test]
+    fn test_grid_eq() -> Result<()> {
+        let submarine = ShipType::Submarine(Orientation::Vertical);
+        let mut grid = Grid::new(4, 4);
+        grid.ships = vec![
+            Ship::new(submarine, submarine.get_hitbox(Coordinate::new(1, 1))),
+            Ship::new(ShipType::Boat, vec![Coordinate::new(4, 4)]),
+        ];
+        grid.hits = vec![Coordinate::new(1, 2), Coordinate::new(3, 3)];
+        let mut other = Grid::new(4, 4);
+        let mut coords = submarine.get_hitbox(Coordinate::new(1, 1));
+        coords.reverse();
+        other.ships = vec![
+            Ship::new(ShipType::Boat, vec![Coordinate::new(4, 4)]),
+            Ship::new(submarine, coords),
+        ];
+        other.hits = vec![Coordinate::new(3, 3), Coordinate::new(1, 2)];
+        assert_eq!(grid, other);
+
+        other.hits.pop();
+        assert_ne!(grid, other);
+        other.hits.push(Coordinate::new(1, 2));
+        other.ships[0].decoy = true;
+        assert_ne!(grid, other);
+        other.ships[0] = Ship::new(ShipType::Boat, vec![Coordinate::new(4, 3)]);
+        assert_ne!(grid, other);
+        assert_ne!(grid, Grid::new(4, 3));
+        Ok(())
+    }
+
+    #[test]
+    fn test_glyph_set() -> Result<()> {
+        let mut grid = Grid::from_ascii("#..\n...\n.##")?;
+        grid.ships[0].coords[0].is_hit = true;
+        grid.ships[1].coords[0].is_hit = true;
+        grid.hits = vec![
+            Coordinate::from((1, 1)),
+            Coordinate::from((2, 2)),
+            Coordinate::from((2, 3)),
+            Coordinate::from((3, 1)),
+        ];
+        let glyphs = "~~,##,XX,OO,**,!!,??".parse::<GlyphSet>()?;
+        assert_eq!(
+            r#"
+  |A |B |C 
+ 1|**|~~|~~
+ 2|~~|~~|~~
+ 3|~~|XX|##
+"#,
+            strip_ansi(&grid.as_string_with_glyphs(true, &glyphs)?)
+        );
+        assert_eq!(
+            r#"
+  |A |B |C 
+ 1|**|~~|??
+ 2|~~|OO|~~
+ 3|~~|XX|~~
+"#,
+            strip_ansi(&grid.as_tracking_string(Some(Coordinate::from((3, 1))), &glyphs)?)
+        );
+        let glyphs = "..,,XX".parse::<GlyphSet>()?;
+        assert_eq!(String::from(".."), glyphs.water);
+        assert_eq!(None, glyphs.ship);
+        assert_eq!(String::from("XX"), glyphs.hit);
+        assert_eq!(GlyphSet::default().miss, glyphs.miss);
+        assert!("a,b,c,d,e,f,g,h".parse::<GlyphSet>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_shots() -> Result<()> {
+        let mut grid = Grid::from_ascii(
+            r#"
+            ##..
+            ....
+            "#,
+        )?;
+        let coords = [(1, 1), (3, 1), (1, 1), (5, 1), (2, 1)]
+            .iter()
+            .copied()
+            .map(Coordinate::from)
+            .collect::<Vec<Coordinate>>();
+        assert_eq!(
+            vec![
+                ShotOutcome::Hit,
+                ShotOutcome::Miss,
+                ShotOutcome::Duplicate,
+                ShotOutcome::OutOfRange,
+                ShotOutcome::Sunk {
+                    kind: ShipType::Destroyer(Orientation::Horizontal),
+                    cells: vec![Coordinate::from((1, 1)), Coordinate::from((2, 1))],
+                },
+            ],
+            grid.apply_shots(&coords)
+        );
+        assert_eq!(3, grid.hits.len());
+        assert!(grid.is_defeated());
+        Ok(())
+    }
+
+    #[test]
+    fn test_place_remaining_randomly() {
+        let fleet = [3, 2, 2];
+        let rng = Rng::with_seed(7);
+        let mut grid = Grid::new(5, 5);
+        let ship_type = ShipType::Submarine(Orientation::Horizontal);
+        grid.place_ship(Ship::new(ship_type, ship_type.get_hitbox(Coordinate::from((2, 3)))));
+        assert!(grid.place_remaining_randomly(&fleet, true, &rng));
+        assert!(grid.placement_complete(&fleet));
+        assert!(grid.validate_against_fleet(&fleet, true).is_ok());
+        assert_eq!(ship_type, grid.ships[0].type_);
+
+        // The fleet does not fit around the ships, so nothing is placed.
+        let mut grid = Grid::new(3, 3);
+        grid.place_ship(Ship::new(ShipType::Boat, vec![Coordinate::from((2, 2))]));
+        assert!(!grid.place_remaining_randomly(&[1, 2], true, &rng));
+        assert_eq!(1, grid.ships.len());
+    }
+
+    #[test]
+    fn test_state_hash() -> Result<()> {
+        let mut grid = Grid::from_ascii(
+            r#"
+            ##..
+            ....
+            "#,
+        )?;
+        let hash = grid.state_hash();
+        assert_eq!(hash, Grid::from_ascii("##..\n....")?.state_hash());
+        grid.hits.push(Coordinate::from((4, 2)));
+        let missed_hash = grid.state_hash();
+        assert_ne!(hash, missed_hash);
+        grid.ships[0].coords[0].is_hit = true;
+        assert_ne!(missed_hash, grid.state_hash());
+        Ok(())
+    }
+
+    #[test]
+    fn test_ship_count_by_size() -> Result<()> {
+        let mut grid = Grid::new_random_with_fleet(10, 10, &STANDARD_FLEET, &Rng::with_seed(42))?;
+        let expected = [(2, 1), (3, 2), (4, 1), (5, 1)].iter().copied().collect::<BTreeMap<_, _>>();
+        assert_eq!(expected, grid.ship_count_by_size());
+        grid.place_random_decoy(&Rng::with_seed(42));
+        assert_eq!(expected, grid.ship_count_by_size());
+        assert!(Grid::new(5, 5).ship_count_by_size().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_coverage_ratio() -> Result<()> {
+        let mut grid = Grid::from_ascii(
+            r#"
+            ###.
+            ....
+            #...
+            ....
+            "#,
+        )?;
+        assert!((grid.coverage_ratio() - 0.25).abs() < f32::EPSILON);
+        grid.place_ship(Ship::new_decoy(Coordinate::from((4, 4))));
+        assert!((grid.coverage_ratio() - 0.25).abs() < f32::EPSILON);
+        assert!(Grid::new(0, 0).coverage_ratio().abs() < f32::EPSILON);
+        Ok(())
+    }
+
+    #[test]
+    fn test_fire_at_sunk() -> Result<()> {
+        let mut grid = Grid::from_ascii(
+            r#"
+            .#..
+            .#..
+            .#..
+            "#,
+        )?;
+        let cells = vec![
+            Coordinate::from((2, 1)),
+            Coordinate::from((2, 2)),
+            Coordinate::from((2, 3)),
+        ];
+        assert_eq!(ShotOutcome::Hit, grid.fire_at(cells[2]));
+        assert_eq!(ShotOutcome::Hit, grid.fire_at(cells[0]));
+        assert_eq!(
+            ShotOutcome::Sunk {
+                kind: ShipType::Submarine(Orientation::Vertical),
+                cells: cells.clone(),
+            },
+            grid.fire_at(cells[1])
+        );
+        assert_eq!(ShotOutcome::Hit, grid.fire_at(cells[1]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_undo_last_shot() -> Result<()> {
+        let mut grid = Grid::from_ascii(
+            r#"
+            ##..
+            ....
+            "#,
+        )?;
+        assert_eq!(None, grid.undo_last_shot());
+        grid.fire_at(Coordinate::from((1, 1)));
+        let hash = grid.state_hash();
+        for coordinate in [(2, 1), (1, 1), (3, 2)] {
+            grid.fire_at(Coordinate::from(coordinate));
+        }
+        assert!(grid.is_defeated());
+        assert_eq!(Some(Coordinate::from((3, 2))), grid.undo_last_shot());
+        assert_eq!(Some(Coordinate::from((1, 1))), grid.undo_last_shot());
+        assert!(grid.ships[0].coords[0].is_hit);
+        assert_eq!(Some(Coordinate::from((2, 1))), grid.undo_last_shot());
+        assert_eq!(hash, grid.state_hash());
+        assert!(!grid.ships[0].coords[1].is_hit);
+        Ok(())
+    }
+
+    #[test]
+    fn test_rows() -> Result<()> {
+        let mut grid = Grid::from_ascii(
+            r#"
+            #...
+            ....
+            ...#
+            "#,
+        )?;
+        grid.fire_at(Coordinate::from((4, 3)));
+        let rows = grid.rows(true);
+        assert_eq!(usize::from(grid.height), rows.len());
+        for row in &rows {
+            let cells = strip_ansi(row);
+            assert_eq!(usize::from(grid.width), cells.split('|').skip(1).count());
+        }
+        assert_eq!(format!("|{}|  |  |  ", ShipType::Boat), strip_ansi(&rows[0]));
+        assert_eq!(format!("|  |  |  |{}", HIT_POINT), strip_ansi(&grid.rows(false)[2]));
+        assert!(!strip_ansi(&grid.rows(false)[0]).contains(&ShipType::Boat.to_string()));
+
+        let lines = grid.as_string(true)?;
+        for (line, row) in lines.lines().skip(2).zip(&rows) {
+            assert!(line.ends_with(row.as_str()));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_row_origin() -> Result<()> {
+        for (origin, y, label) in [(RowOrigin::Top, 2, " 2|"), (RowOrigin::Bottom, 4, " 4|")] {
+            let mut grid = Grid::new(3, 5);
+            grid.origin = origin;
+            let coordinate = grid.parse_coordinate("B2").ok_or("invalid coordinate")?;
+            assert_eq!(Coordinate::new(2, y), coordinate);
+            assert_eq!("B2", grid.coordinate_label(coordinate));
+            for s in &["A1", "C5", "c3", "B9"] {
+                let coordinate = grid.parse_coordinate(s).ok_or("invalid coordinate")?;
+                let label = grid.coordinate_label(coordinate);
+                assert_eq!(s.to_uppercase(), label);
+            }
+
+            // The cell is rendered on the row with the same label.
+            grid.fire_at(Coordinate::new(1, 2));
+            let lines = strip_ansi(&grid.as_tracking_string(None, &GlyphSet::default())?);
+            let row = lines.lines().find(|line| line.contains(MISSED_POINT)).unwrap_or_default();
+            assert!(row.starts_with(label));
+        }
+        assert_eq!(Ok(RowOrigin::Bottom), "Bottom".parse());
+        assert!("left".parse::<RowOrigin>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_ships_touch() -> Result<()> {
+        let grid = Grid::from_ascii(
+            r#"
+            #...
+            ..##
+            "#,
+        )?;
+        assert!(!grid.ships_touch());
+        let grid = Grid::from_ascii(
+            r#"
+            #...
+            .##.
+            "#,
+        )?;
+        assert!(grid.ships_touch());
+        Ok(())
+    }
+
+    #[test]
+    fn test_adjacent_ship_count() -> Result<()> {
+        let grid = Grid::from_ascii(
+            r#"
+            ##..
+            ....
+            ...#
+            "#,
+        )?;
+        assert_eq!(2, grid.adjacent_ship_count(&Coordinate::from((1, 2))));
+        assert_eq!(2, grid.adjacent_ship_count(&Coordinate::from((3, 2))));
+        assert_eq!(1, grid.adjacent_ship_count(&Coordinate::from((1, 1))));
+        assert_eq!(0, grid.adjacent_ship_count(&Coordinate::from((1, 3))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_cells_around_ship() {
+        let grid = Grid::new(5, 5);
+        let ship_type = ShipType::Submarine(Orientation::Horizontal);
+        let cells_around = |coordinate: (u8, u8)| {
+            let ship = Ship::new(ship_type, ship_type.get_hitbox(Coordinate::from(coordinate)));
+            let mut cells = grid.cells_around_ship(&ship);
+            cells.sort_by_key(|c| (c.y, c.x));
+            cells.iter().map(Coordinate::to_string).collect::<Vec<String>>().join(" ")
+        };
+        assert_eq!("A2 B2 C2 D2 E2 A3 E3 A4 B4 C4 D4 E4", cells_around((2, 3)));
+        assert_eq!("B4 C4 D4 E4 B5", cells_around((3, 5)));
+    }
+
+    #[test]
+    fn test_reveal_around_ship() -> Result<()> {
+        let mut grid = Grid::from_ascii(
+            r#"
+            ....
+            .##.
+            ....
+            ...#
+            "#,
+        )?;
+        grid.hits = vec![
+            Coordinate::from((1, 1)),
+            Coordinate::from((2, 2)),
+            Coordinate::from((3, 2)),
+        ];
+        let revealed = grid.reveal_around_ship(&Coordinate::from((2, 2)));
+        assert_eq!(9, revealed.len());
+        assert_eq!(12, grid.hits.len());
+        assert!(!revealed.contains(&Coordinate::from((1, 1))));
+        assert_eq!(
+            r#"
+  |A |B |C |D 
+ 1|🌀|🌀|🌀|🌀
+ 2|🌀|🔥|🔥|🌀
+ 3|🌀|🌀|🌀|🌀
+ 4|  |  |  |  
+"#,
+            strip_ansi(&grid.as_tracking_string(None, &GlyphSet::default())?)
+        );
+        assert!(grid.reveal_around_ship(&Coordinate::from((3, 3))).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_tracking_grid() -> Result<()> {
+        let mut grid = Grid::new(3, 3);
+        let ship_type = ShipType::Destroyer(Orientation::Horizontal);
+        assert!(grid.place_ship(Ship::new(
+            ship_type,
+            ship_type.get_hitbox(Coordinate::from((1, 1)))
+        )));
+        grid.hits = vec![
+            Coordinate::from((1, 1)),
+            Coordinate::from((3, 3)),
+            Coordinate::from((2, 1)),
+        ];
+        assert_eq!(
+            r#"
+  |A |B |C 
+ 1|🔥|💥|  
+ 2|  |  |  
+ 3|  |  |🌀
+"#,
+            strip_ansi(&grid.as_tracking_string(Some(Coordinate::from((2, 1))), &GlyphSet::default())?)
+        );
+        assert_eq!(
+            r#"
+  |A |B |C 
+ 1|🔥|🔥|  
+ 2|  |  |  
+ 3|  |  |💦
+"#,
+            strip_ansi(&grid.as_tracking_string(Some(Coordinate::from((3, 3))), &GlyphSet::default())?)
+        );
+        assert_eq!(
+            r#"
+  |A |B |C 
+ 1|🔥|🔥|  
+ 2|  |  |  
+ 3|  |  |🌀
+"#,
+            strip_ansi(&grid.as_tracking_string(None, &GlyphSet::default())?)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_random_grid_with_fleet() -> Result<()> {
+        let fleet = [5, 4, 3, 3, 2];
+        let grid = Grid::new_random_with_fleet(10, 10, &fleet, &Rng::new())?;
+        assert_eq!(
+            fleet.to_vec(),
+            grid.ships.iter().map(|ship| ship.type_.size()).collect::<Vec<u8>>()
+        );
+        assert!(Grid::new_random_with_fleet(2, 2, &fleet, &Rng::new()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_seed() -> Result<()> {
+        let grid = Grid::from_seed(10, 10, &STANDARD_FLEET, 7)?;
+        let same_grid = Grid::from_seed(10, 10, &STANDARD_FLEET, 7)?;
+        assert_eq!(grid, same_grid);
+        assert_eq!(grid.as_string(true)?, same_grid.as_string(true)?);
+        grid.validate_against_fleet(&STANDARD_FLEET, false)?;
+        assert_ne!(grid, Grid::from_seed(10, 10, &STANDARD_FLEET, 8)?);
+        assert_eq!(
+            "[!] The fleet [5, 4, 3, 3, 2] does not fit on a 4x4 grid.",
+            Grid::from_seed(4, 4, &STANDARD_FLEET, 7).expect_err("the fleet fits").to_string()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_placement_style() -> Result<()> {
+        let fleet = [3, 2];
+        let rng = Rng::with_seed(42);
+        let mut distances = Vec::new();
+        for style in [PlacementStyle::Uniform, PlacementStyle::CenterBiased] {
+            let mut total = 0;
+            for _ in 0..200 {
+                let grid = Grid::new_random_with_style(10, 10, &fleet, style, &rng)?;
+                assert!(grid.validate_against_fleet(&fleet, false).is_ok());
+                total += grid
+                    .ships
+                    .iter()
+                    .flat_map(|ship| ship.coords.iter())
+                    .map(|c| grid.center_weight(c))
+                    .sum::<usize>();
+            }
+            distances.push(total);
+        }
+        assert!(distances[1] > distances[0] * 11 / 10, "{:?}", distances);
+        assert_eq!(Ok(PlacementStyle::CenterBiased), "Center-Biased".parse());
+        assert!(Grid::new_random_with_style(2, 2, &fleet, PlacementStyle::CenterBiased, &rng)
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_transform() -> Result<()> {
+        let mut grid = Grid::new(4, 3);
+        assert!(grid.place_ship(Ship::new(
+            ShipType::Submarine(Orientation::Horizontal),
+            ShipType::Submarine(Orientation::Horizontal).get_hitbox(Coordinate::from((1, 1)))
+        )));
+        grid.ships[0].coords[1].is_hit = true;
+        grid.hits = vec![Coordinate::from((2, 1)), Coordinate::from((4, 3))];
+
+        let transposed = grid.transpose();
+        assert_eq!((3, 4), (transposed.width, transposed.height));
+        assert_eq!(
+            ShipType::Submarine(Orientation::Vertical),
+            transposed.ships[0].type_
+        );
+        assert_eq!(
+            ShipType::Submarine(Orientation::Vertical).get_hitbox(Coordinate::from((1, 1))),
+            transposed.ships[0].coords
+        );
+        assert!(transposed.ships[0].coords[1].is_hit);
+        assert_eq!(
+            vec![Coordinate::from((1, 2)), Coordinate::from((3, 4))],
+            transposed.hits
+        );
+        let original = transposed.transpose();
+        assert_eq!(grid.as_string(true)?, original.as_string(true)?);
+        assert_eq!(grid.hits, original.hits);
+        assert_eq!(grid.ships[0].type_, original.ships[0].type_);
+
+        let mirrored = grid.mirror_horizontal();
+        assert_eq!(
+            vec![
+                Coordinate::from((4, 1)),
+                Coordinate::from((3, 1)),
+                Coordinate::from((2, 1))
+            ],
+            mirrored.ships[0].coords
+        );
+        assert_eq!(grid.as_string(true)?, mirrored.mirror_horizontal().as_string(true)?);
+        let mirrored = grid.mirror_vertical();
+        assert_eq!(
+            vec![Coordinate::from((2, 3)), Coordinate::from((4, 1))],
+            mirrored.hits
+        );
+        assert_eq!(grid.as_string(true)?, mirrored.mirror_vertical().as_string(true)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_seeded_grid() -> Result<()> {
+        let generate = |seed| -> Result<Vec<String>> {
+            let rng = Rng::with_seed(seed);
+            (0..3)
+                .map(|_| Grid::new_random_with_rng(10, 10, &rng).as_string(true))
+                .collect()
+        };
+        assert_eq!(generate(12345)?, generate(12345)?);
+        assert_ne!(generate(12345)?, generate(54321)?);
+        Ok(())
+    }
 }
@@ -2,17 +2,32 @@
 
 #![warn(missing_docs, clippy::unwrap_used)]
 
+pub mod compression;
+pub mod config;
 pub mod game;
 pub mod grid;
+pub mod lobby;
+pub mod matchmaking;
 pub mod player;
+pub mod rating;
+pub mod share;
 pub mod ship;
+pub mod spectator;
+pub mod status;
 
-use crate::game::Game;
-use crate::grid::{Grid, ALPHABET};
+use crate::config::Config;
+use crate::grid::ALPHABET;
+use crate::game::{Game, MAX_PLAYERS};
+use crate::lobby::{Lobby, SharedGame};
+use crate::matchmaking::PreferredMode;
 use crate::player::Player;
+use std::fs;
 use std::io::{Error as IoError, ErrorKind};
-use std::net::TcpListener;
-use std::sync::{Arc, Mutex};
+use std::iter;
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex, PoisonError};
 use std::thread;
 
 /// ASCII art for the banner.
@@ -29,73 +44,417 @@ Welcome to Battleship!"#;
 pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
 
 /// Runs the game.
-pub fn run(socket_addr: &str, grid_width: u8, grid_height: u8) -> Result<()> {
+///
+/// Multiple socket addresses can be given as a comma-separated list,
+/// e.g. `0.0.0.0:1234,[::]:1234` for listening on both IPv4 and IPv6.
+/// Also see [`bind`] and [`serve`].
+pub fn run(socket_addr: &str, config: Config) -> Result<()> {
+    let listeners = bind(socket_addr)?;
+    serve(listeners, config)
+}
+
+/// Binds TCP listeners to the given comma-separated socket addresses.
+pub fn bind(socket_addrs: &str) -> Result<Vec<TcpListener>> {
+    let listeners = socket_addrs
+        .split(',')
+        .map(str::trim)
+        .filter(|socket_addr| !socket_addr.is_empty())
+        .map(|socket_addr| -> Result<TcpListener> {
+            let listener = TcpListener::bind(socket_addr)?;
+            println!("[+] Server is listening on {}", listener.local_addr()?);
+            Ok(listener)
+        })
+        .collect::<Result<Vec<TcpListener>>>()?;
+    if listeners.is_empty() {
+        return Err("[!] No socket address is given.".into());
+    }
+    Ok(listeners)
+}
+
+/// Serves the game on the given listeners.
+///
+/// Connections from all of the listeners join the same lobby,
+/// new games are created as the players arrive.
+pub fn serve(listeners: Vec<TcpListener>, config: Config) -> Result<()> {
     // Prepare the game.
-    if usize::from(grid_width) > ALPHABET.len() || usize::from(grid_height) > ALPHABET.len() {
+    let default_size = (config.grid_width, config.grid_height);
+    let mut grid_sizes = config.grid_sizes.iter().copied().chain(iter::once(default_size));
+    if grid_sizes.any(|(width, height)| {
+        usize::from(width) > ALPHABET.len() || usize::from(height) > ALPHABET.len()
+    }) {
         return Err("[!] Invalid grid dimensions.".into());
     }
-    let game = Arc::new(Mutex::new(Game::default()));
+    if config.players_per_game < 2 || config.players_per_game > MAX_PLAYERS {
+        return Err("[!] Invalid number of players per game.".into());
+    }
+    if config.fleet_density.map_or(false, |density| !(0.0..=1.0).contains(&density)) {
+        return Err("[!] Invalid fleet density.".into());
+    }
+    // The games of the lobby would overwrite the save of each other.
+    if config.save_path.is_some() && config.max_games != Some(1) {
+        return Err("[!] Saving the game requires the maximum number of games to be 1.".into());
+    }
+    Game::new(config.clone()).check_grid_sizes()?;
+    // Players are grouped by their preferred mode if there can be more than one game.
+    let lobby = if config.ask_game_mode && config.max_games != Some(1) {
+        Lobby::with_policy(config.clone(), Box::new(PreferredMode))
+    } else {
+        Lobby::new(config.clone())
+    };
+    let lobby = Arc::new(Mutex::new(lobby));
 
-    // Start listening for connections.
-    let listener = TcpListener::bind(socket_addr)?;
-    println!("[+] Server is listening on {}", socket_addr);
+    // Restore the game that was in progress before the restart.
+    if let Some(path) = config.save_path.as_ref().filter(|path| Path::new(path).exists()) {
+        match Game::load(path, config.clone()) {
+            Ok(game) => {
+                let names = game.players.iter().map(|p| p.name()).collect::<Vec<&str>>();
+                println!("[+] Restored the saved game of {} from {}", names.join(", "), path);
+                let game = lobby.lock().expect("failed to retrieve lobby").restore(game);
+                thread::spawn(move || {
+                    play_guarded(&game, |game| {
+                        let i = game.current_player;
+                        game.resume(i)
+                    })
+                });
+            }
+            Err(e) => {
+                // The invalid save is kept for inspection but not loaded again.
+                let invalid_path = format!("{}.invalid", path);
+                eprintln!("[!] Failed to restore the saved game from {}: {}", path, e);
+                match fs::rename(path, &invalid_path) {
+                    Ok(()) => eprintln!("[!] Moved the saved game to {}", invalid_path),
+                    Err(e) => eprintln!("[!] Failed to move the saved game: {}", e),
+                }
+            }
+        }
+    }
+
+    // Start the status endpoint.
+    if let Some(status_addr) = &config.status_socket_addr {
+        let listener = TcpListener::bind(status_addr)?;
+        println!("[+] Status endpoint is listening on {}", listener.local_addr()?);
+        let lobby = Arc::clone(&lobby);
+        thread::spawn(move || status::serve(listener, lobby));
+    }
 
     // Handle connections.
-    for stream in listener.incoming() {
-        match stream {
-            Ok(stream) => {
-                println!("[+] New connection: {}", stream.peer_addr()?);
-                let mut player = Player::new(stream);
-                if game.try_lock().is_err() {
-                    player.send("Lobby is full. Please wait.\n")?;
-                    continue;
-                }
-                let game = Arc::clone(&game);
-                thread::spawn(move || {
-                    // Add a player to the game.
-                    let add_new_player = || -> Result<()> {
-                        player.greet()?;
-                        let mut game = game.lock().expect("failed to retrieve game");
-                        game.add_player(player)?;
-                        // Start the game when ready.
-                        if game.is_ready() {
-                            // Assign random boards to the players.
-                            game.players
-                                .iter_mut()
-                                .try_for_each::<_, Result<()>>(|player| {
-                                    player.grid = Grid::new_random(grid_width, grid_height);
-                                    println!(
-                                        "[#] {}'s grid:{}",
-                                        player.name,
-                                        player.grid.as_string(true)?
-                                    );
-                                    Ok(())
-                                })?;
-                            // Start the game loop.
-                            game.start(grid_width, grid_height)?;
-                        }
-                        Ok(())
-                    };
-
-                    // Handle errors.
-                    if let Err(e) = add_new_player() {
-                        eprintln!("[!] Gameplay error: {}", e);
-                        if let Ok(io_error) = e.downcast::<IoError>() {
-                            if io_error.kind() == ErrorKind::BrokenPipe {
-                                let mut game = game.lock().expect("failed to retrieve game");
-                                game.players.iter_mut().for_each(|player| {
-                                    let _ = player.send("Your opponent left the game.\n");
-                                });
-                                game.players.clear();
-                            }
+    let handles = listeners
+        .into_iter()
+        .map(|listener| {
+            let lobby = Arc::clone(&lobby);
+            let config = config.clone();
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    match stream {
+                        Ok(stream) => handle_connection(stream, &lobby, &config),
+                        Err(e) => {
+                            eprintln!("[!] Connection failed: {}", e);
                         }
                     }
-                });
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for handle in handles {
+        if handle.join().is_err() {
+            eprintln!("[!] Listener thread panicked.");
+        }
+    }
+    Ok(())
+}
+
+/// Handles a new connection.
+///
+/// The player joins a game in a new thread.
+fn handle_connection(stream: TcpStream, lobby: &Arc<Mutex<Lobby>>, config: &Config) {
+    match stream.peer_addr() {
+        Ok(peer_addr) => println!("[+] New connection: {}", peer_addr),
+        Err(e) => {
+            eprintln!("[!] Connection failed: {}", e);
+            return;
+        }
+    }
+    let mut player = Player::with_keepalive(stream, config.keepalive_interval);
+    player.max_name_length = config.max_name_length;
+    let lobby = Arc::clone(lobby);
+    let config = config.clone();
+    thread::spawn(move || {
+        // Ask for the player name.
+        if let Err(e) = player.greet_with_motd(&config.motd) {
+            eprintln!("[!] Player disconnected before joining: {}", e);
+            return;
+        }
+
+        // Ask for the preferred game mode.
+        if config.ask_game_mode && !player.spectator {
+            if let Err(e) = player.ask_game_mode() {
+                eprintln!("[!] Player disconnected before joining: {}", e);
+                return;
+            }
+        }
+
+        // Let the spectator watch a game.
+        if player.spectator {
+            if let Err(e) = lobby.lock().expect("failed to retrieve lobby").spectate(player) {
+                eprintln!("[!] Failed to watch the game: {}", e);
+            }
+            return;
+        }
+
+        // Return the player to the game that is waiting for them to reconnect.
+        let is_awaited = lobby
+            .lock()
+            .expect("failed to retrieve lobby")
+            .awaits_reconnection(&player.name);
+        let mut player = if is_awaited {
+            let token = player.send("Enter your reconnection token: ").and_then(|_| player.read());
+            let reconnected = token.and_then(|token| {
+                lobby.lock().expect("failed to retrieve lobby").reconnect(player, &token)
+            });
+            match reconnected {
+                Ok(Some(player)) => player,
+                Ok(None) => return,
+                Err(e) => {
+                    eprintln!("[!] Player disconnected before reconnecting: {}", e);
+                    return;
+                }
+            }
+        } else {
+            player
+        };
+
+        // Show the summary of the last game before joining the next one.
+        if player.wants_summary {
+            let summary = lobby
+                .lock()
+                .expect("failed to retrieve lobby")
+                .last_game_summary(&player.name)
+                .unwrap_or_else(|| String::from("There is no summary of your last game.\n"));
+            if let Err(e) = player.send(&summary) {
+                eprintln!("[!] Player disconnected before joining: {}", e);
+                return;
             }
+        }
+
+        // Add a player to a game, or let them watch one if the server is at capacity.
+        let game = match lobby.lock().expect("failed to retrieve lobby").join_or_spectate(player) {
+            Ok(Some(game)) => game,
+            Ok(None) => return,
             Err(e) => {
-                eprintln!("[!] Connection failed: {}", e);
+                eprintln!("[!] Failed to join the game: {}", e);
+                return;
             }
+        };
+        play_guarded(&game, |game| start_when_ready(game, &config));
+
+        // Fill the game with bots if the player is still waiting after the timeout.
+        if let Some(timeout) = config.bot_backfill_timeout {
+            thread::sleep(timeout);
+            play_guarded(&game, |game| {
+                if game.backfill_with_bots()? {
+                    start_when_ready(game, &config)?;
+                }
+                Ok(())
+            });
         }
+    });
+}
+
+/// Prepares the grids and starts the game if the players are ready.
+fn start_when_ready(game: &mut Game, config: &Config) -> Result<()> {
+    if game.is_ready() {
+        let (grid_width, grid_height) = (config.grid_width, config.grid_height);
+        let seed = config.seed.unwrap_or_else(|| fastrand::u64(..));
+        if config.manual_placement {
+            // Let the players place their ships.
+            // The seed is still used for picking the first player.
+            game.seed = seed;
+            game.place_ships_manually(grid_width, grid_height)?;
+        } else {
+            // Assign random boards to the players.
+            game.assign_random_grids(grid_width, grid_height, seed)?;
+        }
+        // Start the game loop.
+        game.start()?;
     }
     Ok(())
 }
+
+/// Plays the game with the given function and handles the errors.
+///
+/// Players are told that their opponent left if one of them is disconnected.
+/// Panics are caught at the thread boundary so that the players are notified
+/// instead of being dropped silently, the players are removed from the game afterwards.
+fn play_guarded<F: FnOnce(&mut Game) -> Result<()>>(game: &SharedGame, play: F) {
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut game = game.lock().expect("failed to retrieve game");
+        play(&mut game)
+    }));
+    let message = match result {
+        Ok(Ok(())) => return,
+        Ok(Err(e)) => {
+            eprintln!("[!] Gameplay error: {}", e);
+            match e.downcast::<IoError>() {
+                Ok(io_error)
+                    if [
+                        ErrorKind::BrokenPipe,
+                        ErrorKind::ConnectionReset,
+                        ErrorKind::ConnectionAborted,
+                        ErrorKind::UnexpectedEof,
+                    ]
+                    .contains(&io_error.kind()) =>
+                {
+                    "Your opponent left the game.\n"
+                }
+                _ => return,
+            }
+        }
+        Err(payload) => {
+            let payload = payload
+                .downcast_ref::<&str>()
+                .map(|payload| payload.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| String::from("unknown panic"));
+            eprintln!("[!] Game thread panicked: {}", payload);
+            "The game encountered an error and will attempt to recover.\n"
+        }
+    };
+    let mut game = game.lock().unwrap_or_else(PoisonError::into_inner);
+    game.players.iter_mut().for_each(|player| {
+        let _ = player.send(message);
+    });
+    game.players.clear();
+    game.update_progress(false);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    /// Reads from the stream until the given text is received.
+    fn read_until(stream: &mut TcpStream, text: &str) -> Result<String> {
+        let mut received = String::new();
+        let mut buffer = [0; 1024];
+        while !received.contains(text) {
+            let n = stream.read(&mut buffer)?;
+            if n == 0 {
+                return Err(format!("connection closed before receiving {:?}", text).into());
+            }
+            received += &String::from_utf8_lossy(&buffer[..n]);
+        }
+        Ok(received)
+    }
+
+    #[test]
+    fn test_dual_stack() -> Result<()> {
+        let listeners = bind("127.0.0.1:0, [::1]:0")?;
+        let addrs = listeners
+            .iter()
+            .map(|listener| listener.local_addr())
+            .collect::<std::io::Result<Vec<_>>>()?;
+        assert!(addrs[0].is_ipv4());
+        assert!(addrs[1].is_ipv6());
+        thread::spawn(move || serve(listeners, Config::default()).map_err(|e| e.to_string()));
+        for addr in addrs {
+            let mut client = TcpStream::connect(addr)?;
+            assert!(read_until(&mut client, "Please enter your name: ")?.contains(BANNER));
+        }
+        assert!(bind("").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_fleet_does_not_fit() -> Result<()> {
+        let listeners = bind("127.0.0.1:0")?;
+        let addr = listeners[0].local_addr()?;
+        let config = Config {
+            grid_width: 4,
+            grid_height: 4,
+            manual_placement: true,
+            ..Config::default()
+        };
+        let error = serve(listeners, config).expect_err("the fleet should not fit");
+        assert_eq!("[!] The fleet [5, 4, 3, 3, 2] does not fit on a 4x4 grid.", error.to_string());
+        assert!(TcpStream::connect(addr).is_err());
+
+        let config = Config {
+            grid_width: 2,
+            grid_height: 2,
+            ..Config::default()
+        };
+        let error = serve(bind("127.0.0.1:0")?, config).expect_err("the ships should not fit");
+        assert_eq!("[!] The ships do not fit on a 2x2 grid.", error.to_string());
+
+        for density in [f32::INFINITY, f32::NAN, -0.1, 1.5].iter() {
+            let config = Config {
+                fleet_density: Some(*density),
+                ..Config::default()
+            };
+            let error = serve(bind("127.0.0.1:0")?, config).expect_err("invalid density");
+            assert_eq!("[!] Invalid fleet density.", error.to_string());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_save() -> Result<()> {
+        let path = std::env::temp_dir().join(format!("battleship-{}.save", fastrand::u64(..)));
+        let path = path.to_string_lossy().to_string();
+        fs::write(&path, "turn\t0\nplayer\tAlice")?;
+        let mut config = Config {
+            save_path: Some(path.clone()),
+            ..Config::default()
+        };
+        let error = serve(bind("127.0.0.1:0")?, config.clone()).expect_err("more than one game");
+        assert_eq!(
+            "[!] Saving the game requires the maximum number of games to be 1.",
+            error.to_string()
+        );
+
+        let listeners = bind("127.0.0.1:0")?;
+        let addr = listeners[0].local_addr()?;
+        config.max_games = Some(1);
+        thread::spawn(move || serve(listeners, config).map_err(|e| e.to_string()));
+
+        // The server starts without the saved game and moves it aside.
+        let mut client = TcpStream::connect(addr)?;
+        read_until(&mut client, "Please enter your name: ")?;
+        assert!(!Path::new(&path).exists());
+        let invalid_path = format!("{}.invalid", path);
+        assert_eq!("turn\t0\nplayer\tAlice", fs::read_to_string(&invalid_path)?);
+        fs::remove_file(invalid_path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_game_panic() -> Result<()> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let mut lobby = Lobby::default();
+        let mut clients = Vec::new();
+        let mut games = Vec::new();
+        for _ in 0..2 {
+            clients.push(TcpStream::connect(listener.local_addr()?)?);
+            games.push(lobby.join(Player::new(listener.accept()?.0))?);
+        }
+        assert!(Arc::ptr_eq(&games[0], &games[1]));
+
+        // A panic in the game thread is caught and the players are notified.
+        let game = Arc::clone(&games[0]);
+        thread::spawn(move || play_guarded(&game, |_| panic!("forced panic")))
+            .join()
+            .expect("panic is not caught");
+        for client in clients.iter_mut() {
+            let mut output = String::new();
+            client.read_to_string(&mut output)?;
+            assert!(output.ends_with("will attempt to recover.\n"));
+        }
+
+        // New players are routed to a new game instead of the failed game.
+        let _client = TcpStream::connect(listener.local_addr()?)?;
+        let game = lobby.join(Player::new(listener.accept()?.0))?;
+        assert!(!Arc::ptr_eq(&games[0], &game));
+        assert_eq!(2, lobby.progress().len());
+        Ok(())
+    }
+}
@@ -0,0 +1,437 @@
+//! Registry of the games.
+
+use crate::config::Config;
+use crate::game::{Game, Progress, SharedReconnections};
+use crate::matchmaking::{ArrivalOrder, MatchmakingPolicy};
+use crate::player::{Participant, Player};
+use crate::rating::SharedRatings;
+use crate::spectator::SharedGallery;
+use crate::Result;
+use std::sync::{Arc, Mutex, TryLockError};
+
+/// Game that is shared between the threads.
+pub type SharedGame = Arc<Mutex<Game>>;
+
+/// Registry of the games that are served.
+///
+/// Each game is behind its own lock so that the games run independently.
+/// The lobby itself is only locked while routing new players.
+#[derive(Debug)]
+pub struct Lobby {
+    /// Configuration of the new games.
+    config: Config,
+    /// Policy for grouping the players into games.
+    policy: Box<dyn MatchmakingPolicy>,
+    /// Games with their shared progress and spectators.
+    games: Vec<(SharedGame, Arc<Mutex<Progress>>, SharedGallery)>,
+    /// Ratings of the players that are shared between the games.
+    ratings: SharedRatings,
+    /// Players that are waiting to reconnect to their games.
+    reconnections: SharedReconnections,
+}
+
+impl Default for Lobby {
+    fn default() -> Self {
+        Self::new(Config::default())
+    }
+}
+
+impl Lobby {
+    /// Constructs a new instance of [`Lobby`].
+    ///
+    /// The games are filled in the order of arrival, see [`ArrivalOrder`].
+    pub fn new(config: Config) -> Self {
+        Self::with_policy(config, Box::new(ArrivalOrder))
+    }
+
+    /// Constructs a new instance of [`Lobby`] with the given matchmaking policy.
+    pub fn with_policy(config: Config, policy: Box<dyn MatchmakingPolicy>) -> Self {
+        Self {
+            config,
+            policy,
+            games: Vec::new(),
+            ratings: SharedRatings::default(),
+            reconnections: SharedReconnections::default(),
+        }
+    }
+
+    /// Adds the player to a game that is waiting for players.
+    ///
+    /// Games that are currently running are skipped without waiting for their lock.
+    /// The game is chosen by the matchmaking policy among the waiting games.
+    /// A new game is created if the policy chooses none,
+    /// unless the [`Config::max_games`] limit is reached.
+    /// The rating of the player is shown before joining.
+    /// Returns the game that the player joined.
+    pub fn join<P: Participant + 'static>(&mut self, mut player: P) -> Result<SharedGame> {
+        if let Ok(ratings) = self.ratings.lock() {
+            player.send(&format!("Your rating is {}.\n", ratings.get(player.name())))?;
+        }
+        {
+            let mut waiting_games = self
+                .games
+                .iter()
+                .filter_map(|(game, _, _)| game.try_lock().ok().map(|lock| (game, lock)))
+                .filter(|(_, waiting_game)| !waiting_game.is_ready())
+                .collect::<Vec<_>>();
+            let groups = waiting_games
+                .iter()
+                .map(|(_, waiting_game)| waiting_game.players.as_slice())
+                .collect::<Vec<&[Box<dyn Participant>]>>();
+            let choice = self.policy.choose(&player, &groups);
+            if let Some((game, waiting_game)) = choice.and_then(|i| waiting_games.get_mut(i)) {
+                waiting_game.add_player(Box::new(player))?;
+                return Ok(Arc::clone(game));
+            }
+        }
+        if let Some(max_games) = self.config.max_games {
+            if self.active_game_count() >= max_games {
+                player.send("Server at capacity, try later.\n")?;
+                return Err("[!] Server is at capacity.".into());
+            }
+        }
+        let mut new_game = Game::new(self.config.clone());
+        new_game.ratings = Arc::clone(&self.ratings);
+        new_game.reconnections = Arc::clone(&self.reconnections);
+        new_game.add_player(Box::new(player))?;
+        let progress = Arc::clone(&new_game.progress);
+        let gallery = Arc::clone(&new_game.gallery);
+        let game = Arc::new(Mutex::new(new_game));
+        self.games.push((Arc::clone(&game), progress, gallery));
+        Ok(game)
+    }
+
+    /// Adds the player to a game like [`Lobby::join`], or lets them watch a game in progress
+    /// if the server is at capacity and [`Config::spectate_when_full`] is set.
+    ///
+    /// Returns `None` if the player is watching.
+    pub fn join_or_spectate(&mut self, mut player: Player) -> Result<Option<SharedGame>> {
+        if self.config.spectate_when_full && self.is_at_capacity() {
+            player.spectator = true;
+            player.send("Server at capacity, watching a game instead.\n")?;
+            println!("[+] {} is watching since the server is at capacity.", player.name);
+            self.spectate(player)?;
+            return Ok(None);
+        }
+        self.join(player).map(Some)
+    }
+
+    /// Checks if the [`Config::max_games`] limit is reached and no game is waiting for players.
+    fn is_at_capacity(&self) -> bool {
+        let is_waiting = self.games.iter().any(|(game, _, _)| {
+            game.try_lock().map(|game| !game.is_ready()).unwrap_or(false)
+        });
+        match self.config.max_games {
+            Some(max_games) => !is_waiting && self.active_game_count() >= max_games,
+            None => false,
+        }
+    }
+
+    /// Adds a game that is restored with [`Game::load`] to the lobby.
+    ///
+    /// Its players take their places back by connecting with the same names
+    /// and their reconnection tokens, see [`Lobby::reconnect`].
+    pub fn restore(&mut self, mut game: Game) -> SharedGame {
+        if let Ok(mut reconnections) = self.reconnections.lock() {
+            for i in game.disconnected_since.keys() {
+                let token = game.reconnection_token(*i).unwrap_or_default().to_string();
+                reconnections.insert(game.players[*i].name().to_string(), (token, None));
+            }
+        }
+        game.reconnections = Arc::clone(&self.reconnections);
+        game.ratings = Arc::clone(&self.ratings);
+        let progress = Arc::clone(&game.progress);
+        let gallery = Arc::clone(&game.gallery);
+        let game = Arc::new(Mutex::new(game));
+        self.games.push((Arc::clone(&game), progress, gallery));
+        game
+    }
+
+    /// Checks if a game is waiting for the player with the given name to reconnect.
+    pub fn awaits_reconnection(&self, name: &str) -> bool {
+        match self.reconnections.lock() {
+            Ok(reconnections) => reconnections.get(name).map_or(false, |r| r.1.is_none()),
+            Err(_) => false,
+        }
+    }
+
+    /// Returns the player to the game that is waiting for them to reconnect.
+    ///
+    /// The player takes their place on their next turn, see [`Config::disconnect_grace`].
+    /// The given token has to match the reconnection token of the player,
+    /// see [`Game::reconnection_token`]. The player is told if it does not and the connection
+    /// is closed. The player is given back if no game is waiting for them.
+    pub fn reconnect(&mut self, mut player: Player, token: &str) -> Result<Option<Player>> {
+        let mut reconnections = match self.reconnections.lock() {
+            Ok(reconnections) => reconnections,
+            Err(_) => return Ok(Some(player)),
+        };
+        match reconnections.get_mut(&player.name) {
+            Some((expected, slot)) if slot.is_none() => {
+                if token.is_empty() || token != expected {
+                    eprintln!("[!] {} entered an invalid reconnection token.", player.name);
+                    player.send("Invalid reconnection token.\n")?;
+                    return Ok(None);
+                }
+                player.send("Reconnecting to your game...\n")?;
+                println!("[+] {} is reconnecting.", player.name);
+                *slot = Some(Box::new(player));
+                Ok(None)
+            }
+            _ => Ok(Some(player)),
+        }
+    }
+
+    /// Lets the spectator watch a game that is in progress.
+    ///
+    /// The spectator is told to try again later if there is no game in progress.
+    pub fn spectate(&mut self, mut spectator: Player) -> Result<()> {
+        let gallery = self.games.iter().find_map(|(_, progress, gallery)| {
+            progress
+                .lock()
+                .ok()
+                .filter(|progress| progress.in_progress)
+                .map(|_| gallery)
+        });
+        match gallery.map(|gallery| gallery.lock()) {
+            Some(Ok(mut gallery)) => gallery.add_spectator(spectator, self.config.spectator_replay),
+            _ => spectator.send("There is no game to watch. Please try again later.\n"),
+        }
+    }
+
+    /// Returns the summary of the last game that the player played.
+    ///
+    /// Summaries are only kept until the next game starts, see [`Progress::last_game`].
+    pub fn last_game_summary(&self, name: &str) -> Option<String> {
+        self.games.iter().rev().find_map(|(_, progress, _)| {
+            let progress = progress.lock().ok()?;
+            let summary = progress.last_game.as_ref()?;
+            if summary.players.iter().any(|player| player == name) {
+                Some(summary.describe())
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Returns the number of games that have players.
+    ///
+    /// Games that are locked are counted as running.
+    fn active_game_count(&self) -> usize {
+        self.games
+            .iter()
+            .filter(|(game, _, _)| match game.try_lock() {
+                Ok(game) => !game.players.is_empty(),
+                Err(TryLockError::WouldBlock) => true,
+                Err(TryLockError::Poisoned(_)) => false,
+            })
+            .count()
+    }
+
+    /// Returns the progress of each game.
+    pub fn progress(&self) -> Vec<Progress> {
+        self.games
+            .iter()
+            .filter_map(|(_, progress, _)| progress.lock().ok().map(|progress| progress.clone()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{GameMode, DEFAULT_PLAYERS_PER_GAME};
+    use crate::matchmaking::PreferredMode;
+    use crate::player::connect_player;
+    use std::io::Read;
+    use std::thread;
+
+    /// Groups the quick match players separately from the other players.
+    #[derive(Debug)]
+    struct QuickMatchPolicy;
+
+    impl MatchmakingPolicy for QuickMatchPolicy {
+        fn choose(
+            &self,
+            player: &dyn Participant,
+            groups: &[&[Box<dyn Participant>]],
+        ) -> Option<usize> {
+            groups.iter().position(|group| {
+                group.iter().all(|other| other.quick_match() == player.quick_match())
+            })
+        }
+    }
+
+    #[test]
+    fn test_matchmaking_policy() -> Result<()> {
+        let mut lobby = Lobby::with_policy(Config::default(), Box::new(QuickMatchPolicy));
+        let mut clients = Vec::new();
+        let mut games = Vec::new();
+        let players = [("Alice", false), ("Bob", true), ("Carol", false), ("Dan", true)];
+        for (name, quick_match) in players {
+            let (mut player, client) = connect_player(name)?;
+            player.quick_match = quick_match;
+            clients.push(client);
+            games.push(lobby.join(player)?);
+        }
+        assert!(Arc::ptr_eq(&games[0], &games[2]));
+        assert!(Arc::ptr_eq(&games[1], &games[3]));
+        assert!(!Arc::ptr_eq(&games[0], &games[1]));
+        let names = |game: &SharedGame| {
+            let game = game.lock().expect("failed to retrieve game");
+            game.players.iter().map(|player| player.name().to_string()).collect::<Vec<String>>()
+        };
+        assert_eq!(vec!["Alice", "Carol"], names(&games[0]));
+        assert_eq!(vec!["Bob", "Dan"], names(&games[1]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_preferred_mode() -> Result<()> {
+        let mut lobby = Lobby::with_policy(Config::default(), Box::new(PreferredMode));
+        let mut clients = Vec::new();
+        let mut games = Vec::new();
+        let players = [
+            ("Alice", Some(GameMode::Radar)),
+            ("Bob", Some(GameMode::Practice)),
+            ("Carol", Some(GameMode::Radar)),
+            ("Dan", None),
+            ("Erin", Some(GameMode::Practice)),
+        ];
+        for (name, mode) in players.iter() {
+            let (mut player, client) = connect_player(name)?;
+            player.game_mode = *mode;
+            clients.push(client);
+            games.push(lobby.join(player)?);
+        }
+        let names = |game: &SharedGame| {
+            let game = game.lock().expect("failed to retrieve game");
+            game.players.iter().map(|player| player.name().to_string()).collect::<Vec<String>>()
+        };
+        assert_eq!(vec!["Alice", "Carol", "Dan"], names(&games[0]));
+        assert_eq!(vec!["Bob", "Erin"], names(&games[1]));
+        assert!(games[0].lock().expect("failed to retrieve game").config.radar);
+        assert!(games[1].lock().expect("failed to retrieve game").config.practice_mode);
+        Ok(())
+    }
+
+    #[test]
+    fn test_max_games() -> Result<()> {
+        let mut lobby = Lobby::new(Config {
+            max_games: Some(1),
+            ..Config::default()
+        });
+        let mut clients = Vec::new();
+        for i in 0..DEFAULT_PLAYERS_PER_GAME {
+            let (player, client) = connect_player(&format!("player{}", i))?;
+            clients.push(client);
+            lobby.join(player)?;
+        }
+        let (player, mut client) = connect_player("late player")?;
+        assert!(lobby.join(player).is_err());
+        let mut output = String::new();
+        client.read_to_string(&mut output)?;
+        assert_eq!("Your rating is 1200.\nServer at capacity, try later.\n", output);
+        assert_eq!(1, lobby.progress().len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_spectate_when_full() -> Result<()> {
+        let mut lobby = Lobby::new(Config {
+            max_games: Some(1),
+            spectate_when_full: true,
+            ..Config::default()
+        });
+        let mut clients = Vec::new();
+        for i in 0..DEFAULT_PLAYERS_PER_GAME {
+            let (player, client) = connect_player(&format!("player{}", i))?;
+            clients.push(client);
+            assert!(lobby.join_or_spectate(player)?.is_some());
+        }
+        lobby.games[0].1.lock().expect("failed to retrieve progress").in_progress = true;
+        let (player, mut client) = connect_player("late player")?;
+        assert!(lobby.join_or_spectate(player)?.is_none());
+        let gallery = Arc::clone(&lobby.games[0].2);
+        assert_eq!(1, gallery.lock().expect("failed to retrieve gallery").spectator_count());
+        gallery.lock().expect("failed to retrieve gallery").clear();
+        let mut output = String::new();
+        client.read_to_string(&mut output)?;
+        assert!(output.starts_with("Server at capacity, watching a game instead.\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_reconnect() -> Result<()> {
+        let mut lobby = Lobby::default();
+        let (player, _client) = connect_player("Alice")?;
+        assert!(!lobby.awaits_reconnection("Alice"));
+        assert!(lobby.reconnect(player, "")?.is_some());
+
+        // Only the player with the token takes the place back.
+        let token = String::from("0123456789abcdef");
+        let reconnections = Arc::clone(&lobby.reconnections);
+        reconnections
+            .lock()
+            .expect("failed to retrieve reconnections")
+            .insert(String::from("Alice"), (token.clone(), None));
+        assert!(lobby.awaits_reconnection("Alice"));
+        for invalid_token in ["", "fedcba9876543210"].iter() {
+            let (player, mut client) = connect_player("Alice")?;
+            assert!(lobby.reconnect(player, invalid_token)?.is_none());
+            let mut output = String::new();
+            client.read_to_string(&mut output)?;
+            assert_eq!("Invalid reconnection token.\n", output);
+            assert!(lobby.awaits_reconnection("Alice"));
+        }
+        let (player, _client) = connect_player("Alice")?;
+        assert!(lobby.reconnect(player, &token)?.is_none());
+        assert!(!lobby.awaits_reconnection("Alice"));
+        let reconnections = reconnections.lock().expect("failed to retrieve reconnections");
+        assert!(reconnections.get("Alice").map_or(false, |r| r.1.is_some()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_independent_games() -> Result<()> {
+        let lobby = Arc::new(Mutex::new(Lobby::default()));
+        let mut clients = Vec::new();
+        let mut games = Vec::new();
+        for i in 0..DEFAULT_PLAYERS_PER_GAME * 2 {
+            let (player, client) = connect_player(&format!("player{}", i))?;
+            clients.push(client);
+            let game = lobby.lock().expect("failed to retrieve lobby").join(player)?;
+            games.push(game);
+        }
+        let (first_games, second_games) = games.split_at(DEFAULT_PLAYERS_PER_GAME);
+        let (first_game, second_game) = (&first_games[0], &second_games[0]);
+        assert!(first_games.iter().all(|game| Arc::ptr_eq(game, first_game)));
+        assert!(second_games.iter().all(|game| Arc::ptr_eq(game, second_game)));
+        assert!(!Arc::ptr_eq(first_game, second_game));
+
+        // Hold the lock of the first game as if it was running.
+        let _running_game = first_game.lock().expect("failed to retrieve game");
+
+        // The second game and the lobby are still available from another thread.
+        let second_game = Arc::clone(second_game);
+        let thread_lobby = Arc::clone(&lobby);
+        let (player, client) = connect_player("late player")?;
+        clients.push(client);
+        let joined_game = thread::spawn(move || {
+            let player_count = second_game.try_lock().map(|game| game.players.len()).ok();
+            let joined_game = thread_lobby
+                .lock()
+                .expect("failed to retrieve lobby")
+                .join(player)
+                .map_err(|e| e.to_string());
+            (player_count, joined_game)
+        })
+        .join()
+        .expect("failed to join thread");
+        assert_eq!(Some(DEFAULT_PLAYERS_PER_GAME), joined_game.0);
+        let joined_game = joined_game.1?;
+        assert!(games.iter().all(|game| !Arc::ptr_eq(game, &joined_game)));
+        assert_eq!(3, lobby.lock().expect("failed to retrieve lobby").progress().len());
+        Ok(())
+    }
+}
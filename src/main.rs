@@ -1,5 +1,8 @@
+use battleship::config::Config;
 use std::env;
 use std::process;
+use std::str::FromStr;
+use std::time::Duration;
 
 /// Default TCP socket address to serve the game.
 const DEFAULT_SOCKET_ADDR: &str = "127.0.0.1:1234";
@@ -9,6 +12,99 @@ const SOCKET_ADDR_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_socket");
 const GRID_WIDTH_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_grid_width");
 /// Environment variable for setting the grid height.
 const GRID_HEIGHT_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_grid_height");
+/// Environment variable for setting the seed of the random grids.
+const SEED_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_seed");
+/// Environment variable for enabling the practice mode.
+const PRACTICE_MODE_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_practice_mode");
+/// Environment variable for setting the number of players in a game.
+const PLAYERS_PER_GAME_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_players_per_game");
+/// Environment variable for setting the grid sizes of the players.
+const GRID_SIZES_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_grid_sizes");
+/// Environment variable for setting the handicaps.
+const HANDICAPS_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_handicaps");
+/// Environment variable for setting the fleet density.
+const FLEET_DENSITY_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_fleet_density");
+/// Environment variable for setting the distribution of the random ships.
+const PLACEMENT_STYLE_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_placement_style");
+/// Environment variable for setting the origin of the row numbers.
+const ROW_ORIGIN_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_row_origin");
+/// Environment variable for enabling the manual ship placement.
+const MANUAL_PLACEMENT_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_manual_placement");
+/// Environment variable for hiding the number of remaining ships.
+const HIDE_SHIP_COUNT_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_hide_ship_count");
+/// Environment variable for enabling the proximity hints on misses.
+const PROXIMITY_HINTS_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_proximity_hints");
+/// Environment variable for enabling the radar on misses.
+const RADAR_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_radar");
+/// Environment variable for announcing the number of shots on sinking a ship.
+const SINK_SHOT_COUNT_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_sink_shot_count");
+/// Environment variable for forbidding ships to touch each other.
+const NO_TOUCH_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_no_touch");
+/// Environment variable for marking the cells around sunk ships.
+const REVEAL_ON_SINK_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_reveal_on_sink");
+/// Environment variable for enabling the column reveal phase.
+const COLUMN_REVEAL_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_column_reveal");
+/// Environment variable for enabling the decoys.
+const DECOYS_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_decoys");
+/// Environment variable for setting the invalid input policy.
+const INVALID_INPUT_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_invalid_input");
+/// Environment variable for picking the first player randomly.
+const RANDOM_FIRST_PLAYER_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_random_first_player");
+/// Environment variable for enabling the confirmation of the shots.
+const CONFIRM_SHOTS_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_confirm_shots");
+/// Environment variable for setting the number of shots per turn.
+const SHOTS_PER_TURN_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_shots_per_turn");
+/// Environment variable for setting the limit of consecutive invalid inputs.
+const INVALID_INPUT_LIMIT_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_invalid_input_limit");
+/// Environment variable for setting the penalty of too many invalid inputs.
+const INVALID_INPUT_PENALTY_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_invalid_input_penalty");
+/// Environment variable for setting the re-prompt limit, `0` disables it.
+const REPROMPT_LIMIT_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_reprompt_limit");
+/// Environment variable for setting the delay between the turns in milliseconds.
+const TURN_DELAY_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_turn_delay");
+/// Environment variable for replaying the moves to the spectators.
+const SPECTATOR_REPLAY_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_spectator_replay");
+/// Environment variable for announcing the number of spectators to the players.
+const ANNOUNCE_SPECTATORS_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_announce_spectators");
+/// Environment variable for letting the players watch a game when the server is at capacity.
+const SPECTATE_WHEN_FULL_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_spectate_when_full");
+/// Environment variable for setting the display policy of the opponent grids.
+const DISPLAY_POLICY_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_display_policy");
+/// Environment variable for showing the grids side by side.
+const SIDE_BY_SIDE_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_side_by_side");
+/// Environment variable for showing the status line before the grids.
+const STATUS_LINE_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_status_line");
+/// Environment variable for setting the message of the day.
+const MOTD_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_motd");
+/// Environment variable for setting the maximum length of the player names.
+const MAX_NAME_LENGTH_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_max_name_length");
+/// Environment variable for asking the players for their preferred game mode.
+const ASK_GAME_MODE_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_ask_game_mode");
+/// Environment variable for setting the glyphs of the grids.
+const GLYPHS_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_glyphs");
+/// Environment variable for setting the path of the game log.
+const GAME_LOG_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_game_log");
+/// Environment variable for setting the path of the saved game.
+const SAVE_PATH_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_save_path");
+/// Environment variable for limiting the number of simultaneous games.
+const MAX_GAMES_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_max_games");
+/// Environment variable for setting the timeout of filling the games with bots.
+const BOT_BACKFILL_TIMEOUT_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_bot_backfill_timeout");
+/// Environment variable for setting the keepalive interval of the players.
+const KEEPALIVE_INTERVAL_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_keepalive_interval");
+/// Environment variable for setting the grace period of the disconnected players.
+const DISCONNECT_GRACE_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_disconnect_grace");
+/// Environment variable for setting the time limit of the turns.
+const TURN_TIMEOUT_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_turn_timeout");
+/// Environment variable for setting the TCP socket address of the status endpoint.
+const STATUS_SOCKET_ADDR_ENV: &str = concat!(env!("CARGO_PKG_NAME"), "_status_socket");
+
+/// Parses the value of the given environment variable.
+fn parse_env<T: FromStr>(name: &str) -> Option<T> {
+    env::var(name.to_uppercase())
+        .ok()
+        .and_then(|v| v.parse::<T>().ok())
+}
 
 fn main() {
     // Get the TCP address from environment.
@@ -16,19 +112,136 @@ fn main() {
         .unwrap_or_else(|_| DEFAULT_SOCKET_ADDR.to_string());
 
     // Get the game configuration from environment.
-    let (grid_width, grid_height) = (
-        env::var(GRID_WIDTH_ENV.to_uppercase())
-            .ok()
-            .and_then(|v| v.parse::<u8>().ok())
-            .unwrap_or(10),
-        env::var(GRID_HEIGHT_ENV.to_uppercase())
-            .ok()
-            .and_then(|v| v.parse::<u8>().ok())
-            .unwrap_or(10),
-    );
+    let mut config = Config::default();
+    if let Some(grid_width) = parse_env(GRID_WIDTH_ENV) {
+        config.grid_width = grid_width;
+    }
+    if let Some(grid_height) = parse_env(GRID_HEIGHT_ENV) {
+        config.grid_height = grid_height;
+    }
+    config.seed = parse_env(SEED_ENV);
+    if let Some(practice_mode) = parse_env(PRACTICE_MODE_ENV) {
+        config.practice_mode = practice_mode;
+    }
+    if let Some(players_per_game) = parse_env(PLAYERS_PER_GAME_ENV) {
+        config.players_per_game = players_per_game;
+    }
+    if let Ok(grid_sizes) = env::var(GRID_SIZES_ENV.to_uppercase()) {
+        let default_size = (config.grid_width, config.grid_height);
+        config.grid_sizes = grid_sizes
+            .split(',')
+            .map(|size| {
+                let mut dimensions = size.trim().splitn(2, 'x').map(str::trim);
+                match (dimensions.next().map(str::parse), dimensions.next().map(str::parse)) {
+                    (Some(Ok(width)), Some(Ok(height))) => (width, height),
+                    _ => default_size,
+                }
+            })
+            .collect();
+    }
+    if let Ok(handicaps) = env::var(HANDICAPS_ENV.to_uppercase()) {
+        config.handicaps = handicaps
+            .split(',')
+            .map(|bonus| bonus.trim().parse().unwrap_or(0))
+            .collect();
+    }
+    config.fleet_density = parse_env(FLEET_DENSITY_ENV);
+    if let Some(placement_style) = parse_env(PLACEMENT_STYLE_ENV) {
+        config.placement_style = placement_style;
+    }
+    if let Some(row_origin) = parse_env(ROW_ORIGIN_ENV) {
+        config.row_origin = row_origin;
+    }
+    if let Some(manual_placement) = parse_env(MANUAL_PLACEMENT_ENV) {
+        config.manual_placement = manual_placement;
+    }
+    if let Some(hide_ship_count) = parse_env(HIDE_SHIP_COUNT_ENV) {
+        config.hide_ship_count = hide_ship_count;
+    }
+    if let Some(proximity_hints) = parse_env(PROXIMITY_HINTS_ENV) {
+        config.proximity_hints = proximity_hints;
+    }
+    if let Some(radar) = parse_env(RADAR_ENV) {
+        config.radar = radar;
+    }
+    if let Some(sink_shot_count) = parse_env(SINK_SHOT_COUNT_ENV) {
+        config.sink_shot_count = sink_shot_count;
+    }
+    if let Some(no_touch) = parse_env(NO_TOUCH_ENV) {
+        config.no_touch = no_touch;
+    }
+    if let Some(reveal_on_sink) = parse_env(REVEAL_ON_SINK_ENV) {
+        config.reveal_on_sink = reveal_on_sink;
+    }
+    if let Some(column_reveal) = parse_env(COLUMN_REVEAL_ENV) {
+        config.column_reveal = column_reveal;
+    }
+    if let Some(decoys) = parse_env(DECOYS_ENV) {
+        config.decoys = decoys;
+    }
+    if let Some(invalid_input) = parse_env(INVALID_INPUT_ENV) {
+        config.invalid_input = invalid_input;
+    }
+    if let Some(random_first_player) = parse_env(RANDOM_FIRST_PLAYER_ENV) {
+        config.random_first_player = random_first_player;
+    }
+    if let Some(confirm_shots) = parse_env(CONFIRM_SHOTS_ENV) {
+        config.confirm_shots = confirm_shots;
+    }
+    if let Some(shots_per_turn) = parse_env(SHOTS_PER_TURN_ENV) {
+        config.shots_per_turn = shots_per_turn;
+    }
+    config.invalid_input_limit = parse_env(INVALID_INPUT_LIMIT_ENV);
+    if let Some(invalid_input_penalty) = parse_env(INVALID_INPUT_PENALTY_ENV) {
+        config.invalid_input_penalty = invalid_input_penalty;
+    }
+    if let Some(reprompt_limit) = parse_env(REPROMPT_LIMIT_ENV) {
+        config.reprompt_limit = Some(reprompt_limit).filter(|limit| *limit > 0);
+    }
+    if let Some(turn_delay) = parse_env(TURN_DELAY_ENV) {
+        config.turn_delay = Duration::from_millis(turn_delay);
+    }
+    if let Some(spectator_replay) = parse_env(SPECTATOR_REPLAY_ENV) {
+        config.spectator_replay = spectator_replay;
+    }
+    if let Some(announce_spectators) = parse_env(ANNOUNCE_SPECTATORS_ENV) {
+        config.announce_spectators = announce_spectators;
+    }
+    if let Some(spectate_when_full) = parse_env(SPECTATE_WHEN_FULL_ENV) {
+        config.spectate_when_full = spectate_when_full;
+    }
+    if let Some(display_policy) = parse_env(DISPLAY_POLICY_ENV) {
+        config.display_policy = display_policy;
+    }
+    if let Some(side_by_side) = parse_env(SIDE_BY_SIDE_ENV) {
+        config.side_by_side = side_by_side;
+    }
+    if let Some(status_line) = parse_env(STATUS_LINE_ENV) {
+        config.status_line = status_line;
+    }
+    if let Ok(motd) = env::var(MOTD_ENV.to_uppercase()) {
+        config.motd = motd;
+    }
+    if let Some(max_name_length) = parse_env(MAX_NAME_LENGTH_ENV) {
+        config.max_name_length = max_name_length;
+    }
+    if let Some(ask_game_mode) = parse_env(ASK_GAME_MODE_ENV) {
+        config.ask_game_mode = ask_game_mode;
+    }
+    if let Some(glyphs) = parse_env(GLYPHS_ENV) {
+        config.glyphs = glyphs;
+    }
+    config.game_log_path = env::var(GAME_LOG_ENV.to_uppercase()).ok();
+    config.save_path = env::var(SAVE_PATH_ENV.to_uppercase()).ok();
+    config.max_games = parse_env(MAX_GAMES_ENV);
+    config.bot_backfill_timeout = parse_env(BOT_BACKFILL_TIMEOUT_ENV).map(Duration::from_secs);
+    config.keepalive_interval = parse_env(KEEPALIVE_INTERVAL_ENV).map(Duration::from_secs);
+    config.disconnect_grace = parse_env(DISCONNECT_GRACE_ENV).map(Duration::from_secs);
+    config.turn_timeout = parse_env(TURN_TIMEOUT_ENV).map(Duration::from_secs);
+    config.status_socket_addr = env::var(STATUS_SOCKET_ADDR_ENV.to_uppercase()).ok();
 
     // Run the game.
-    match battleship::run(&socket_addr, grid_width, grid_height) {
+    match battleship::run(&socket_addr, config) {
         Ok(_) => process::exit(0),
         Err(e) => {
             eprintln!("{}", e);
@@ -0,0 +1,56 @@
+//! Matchmaking of the players.
+
+use crate::player::Participant;
+use std::fmt::Debug;
+
+/// Policy for grouping the players that are waiting into games.
+pub trait MatchmakingPolicy: Debug + Send {
+    /// Returns the index of the waiting group that the player should join.
+    ///
+    /// Each group is the players of a game that is waiting for more players.
+    /// Returning `None` (or an invalid index) starts a new game for the player.
+    fn choose(&self, player: &dyn Participant, groups: &[&[Box<dyn Participant>]]) -> Option<usize>;
+}
+
+/// Fills the games in the order of arrival.
+///
+/// Players join the first game that is waiting for players.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ArrivalOrder;
+
+impl MatchmakingPolicy for ArrivalOrder {
+    fn choose(
+        &self,
+        _player: &dyn Participant,
+        groups: &[&[Box<dyn Participant>]],
+    ) -> Option<usize> {
+        if groups.is_empty() {
+            None
+        } else {
+            Some(0)
+        }
+    }
+}
+
+/// Groups the players by their preferred game mode.
+///
+/// Players join the first game that is waiting with the same preference,
+/// players without a preference join the first game that is waiting for players.
+/// The mode of a game is decided by its first player, see [`Game::add_player`].
+///
+/// [`Game::add_player`]: crate::game::Game::add_player
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PreferredMode;
+
+impl MatchmakingPolicy for PreferredMode {
+    fn choose(
+        &self,
+        player: &dyn Participant,
+        groups: &[&[Box<dyn Participant>]],
+    ) -> Option<usize> {
+        let mode = player.game_mode();
+        groups.iter().position(|group| {
+            mode.is_none() || group.first().map(|host| host.game_mode() == mode).unwrap_or(true)
+        })
+    }
+}
@@ -1,9 +1,157 @@
 //! Player.
 
-use crate::grid::Grid;
+use crate::compression::encode_rle;
+use crate::config::{GameMode, DEFAULT_MAX_NAME_LENGTH};
+use crate::grid::{Coordinate, Grid, RowOrigin, ShotOutcome};
+use crate::status::json_string;
 use crate::{Result, BANNER};
-use std::io::{BufRead, BufReader, Write};
+use std::collections::HashMap;
+#[cfg(test)]
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::io::{BufRead, BufReader, Error as IoError, ErrorKind, Read, Result as IoResult, Write};
 use std::net::{Shutdown, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::Arc;
+#[cfg(test)]
+use std::sync::Mutex;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Version of the protocol that is announced in the handshake, see [`Player::greet`].
+pub const PROTOCOL_VERSION: u32 = 1;
+/// First word of the handshake that is sent by the clients, followed by the version and
+/// the supported features, e.g. `HELLO 1 json rle`.
+pub const HANDSHAKE_COMMAND: &str = "HELLO";
+/// First word of the reply to the handshake, followed by the version and the selected features.
+pub const HANDSHAKE_REPLY: &str = "WELCOME";
+/// Feature of the handshake for receiving the messages as JSON lines.
+pub const JSON_FEATURE: &str = "json";
+/// Feature of the handshake for receiving the grids compressed, see [`COMPRESS_COMMAND`].
+pub const RLE_FEATURE: &str = "rle";
+/// Maximum number of bytes that are read of the handshake.
+const MAX_HANDSHAKE_LENGTH: usize = 256;
+/// Maximum time to wait for the handshake before the client is treated as a legacy client.
+pub const HANDSHAKE_TIMEOUT: Duration = Duration::from_millis(200);
+/// Maximum time to wait for a write to the TCP stream.
+pub const WRITE_TIMEOUT: Duration = Duration::from_secs(10);
+/// Maximum number of bytes that are waiting to be sent to a player.
+///
+/// Players that do not read their messages fast enough are disconnected as too slow.
+pub const MAX_QUEUED_BYTES: usize = 1024 * 1024;
+/// Message that is sent to the idle players for checking the connection.
+pub const KEEPALIVE_MESSAGE: &str = " ";
+/// Message that is sent to the idle players instead of [`KEEPALIVE_MESSAGE`]
+/// if they receive the messages as JSON lines.
+pub const JSON_KEEPALIVE_MESSAGE: &str = "{\"type\":\"ping\"}\n";
+/// Command for joining a quick match instead of entering a name.
+pub const QUICK_MATCH_COMMAND: &str = "quick";
+/// Command for watching a game instead of entering a name.
+pub const SPECTATE_COMMAND: &str = "watch";
+/// Command for showing the summary of the last game before entering a name.
+pub const SUMMARY_COMMAND: &str = "summary";
+/// Command for receiving the grids compressed before entering a name.
+pub const COMPRESS_COMMAND: &str = "compress";
+/// Header of the compressed messages, followed by the length of the payload in bytes.
+pub const COMPRESSED_HEADER: &str = "RLE ";
+
+/// Counters of the traffic on a connection.
+///
+/// It is meant for diagnosing slow clients and protocol desyncs.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ConnectionStats {
+    /// Number of bytes that are sent.
+    pub bytes_sent: usize,
+    /// Number of bytes that are received.
+    pub bytes_received: usize,
+    /// Number of writes to the connection.
+    pub writes: usize,
+    /// Number of lines that are read from the connection.
+    pub reads: usize,
+}
+
+/// Participant of a game.
+///
+/// The game only talks to the participants through this trait,
+/// so it does not depend on whether the moves come from the network or a bot.
+pub trait Participant: Debug + Send {
+    /// Returns the name of the participant.
+    fn name(&self) -> &str;
+
+    /// Returns the grid of the participant.
+    fn grid(&self) -> &Grid;
+
+    /// Returns the grid of the participant for modification.
+    fn grid_mut(&mut self) -> &mut Grid;
+
+    /// Returns the number of hints left.
+    fn hints(&self) -> usize;
+
+    /// Sets the number of hints left.
+    fn set_hints(&mut self, hints: usize);
+
+    /// Returns the number of consecutive invalid inputs.
+    fn invalid_inputs(&self) -> usize;
+
+    /// Sets the number of consecutive invalid inputs.
+    fn set_invalid_inputs(&mut self, invalid_inputs: usize);
+
+    /// Checks if the participant gets a random grid instead of placing the ships.
+    fn quick_match(&self) -> bool;
+
+    /// Returns the preferred game mode of the participant, `None` if any mode is fine.
+    fn game_mode(&self) -> Option<GameMode> {
+        None
+    }
+
+    /// Tells the participant the name of the opponent that they shoot at next
+    /// and the width, height and row origin of their grid.
+    ///
+    /// It is called before each prompt for a shot, the grids of the players can differ.
+    /// Participants that read the rendered grids instead can rely on the default.
+    fn set_target(&mut self, _name: &str, _width: u8, _height: u8, _origin: RowOrigin) {}
+
+    /// Tells the participant the outcome of their shot at the given cell of the opponent.
+    ///
+    /// It is called after each shot that is fired, including the `auto` shots.
+    /// Participants that read the messages instead can rely on the default.
+    fn on_shot(&mut self, _target: &str, _coordinate: Coordinate, _outcome: &ShotOutcome) {}
+
+    /// Sends the given message to the participant.
+    fn send(&mut self, message: &str) -> Result<()>;
+
+    /// Sends the given rendered grid to the participant.
+    ///
+    /// Players that asked for compression receive it run-length encoded.
+    fn send_grid(&mut self, grid: &str) -> Result<()> {
+        self.send(grid)
+    }
+
+    /// Reads the next input of the participant.
+    fn read(&mut self) -> Result<String>;
+
+    /// Reads the next input of the participant, waiting at most the given time.
+    ///
+    /// Returns a [`TimedOut`] error if nothing is entered in time.
+    /// Participants that never keep the game waiting can rely on the default of [`read`].
+    ///
+    /// [`TimedOut`]: ErrorKind::TimedOut
+    /// [`read`]: Participant::read
+    fn read_with_timeout(&mut self, _timeout: Duration) -> Result<String> {
+        self.read()
+    }
+
+    /// Checks if the participant is still connected.
+    fn is_alive(&mut self) -> bool {
+        true
+    }
+
+    /// Disconnects the participant.
+    fn exit(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
 
 /// Representation of a player.
 #[derive(Debug)]
@@ -12,67 +160,907 @@ pub struct Player {
     pub name: String,
     /// Player's grid.
     pub grid: Grid,
+    /// Number of hints left.
+    pub hints: usize,
+    /// Whether if the player joined a quick match.
+    ///
+    /// Quick match players get a generated name and a random grid.
+    pub quick_match: bool,
+    /// Whether if the player is only watching a game.
+    pub spectator: bool,
+    /// Whether if the player asked for the summary of their last game.
+    pub wants_summary: bool,
+    /// Preferred game mode of the player, see [`Player::ask_game_mode`].
+    pub game_mode: Option<GameMode>,
+    /// Whether if the player receives the grids compressed, see [`Player::send_grid`].
+    pub compression: bool,
+    /// Whether if the player receives the messages as JSON lines, see [`Player::send`].
+    pub json: bool,
+    /// Maximum number of characters in the name, see [`Config::max_name_length`].
+    ///
+    /// [`Config::max_name_length`]: crate::config::Config::max_name_length
+    pub max_name_length: usize,
+    /// Number of consecutive invalid inputs.
+    pub invalid_inputs: usize,
+    stats: ConnectionStats,
     /// TCP connection.
     stream: TcpStream,
     reader: BufReader<TcpStream>,
+    /// Start of the line that is received before a read timed out, see [`Player::read`].
+    partial_line: String,
+    /// Queue of the messages that are written to the TCP stream by the writer thread.
+    outbox: Option<Sender<String>>,
+    /// Number of bytes in the queue that are not written yet.
+    queued_bytes: Arc<AtomicUsize>,
+    /// Whether if the writer thread sends the [`JSON_KEEPALIVE_MESSAGE`], see [`Player::json`].
+    json_keepalive: Arc<AtomicBool>,
+    writer: Option<JoinHandle<()>>,
 }
 
 impl Player {
     /// Constructs a new instance of [`Player`].
+    ///
+    /// Messages are written to the stream by a dedicated thread so that sending never blocks,
+    /// writes to the stream time out after [`WRITE_TIMEOUT`].
     pub fn new(stream: TcpStream) -> Self {
-        let reader = BufReader::new(stream.try_clone().unwrap());
+        Self::with_keepalive(stream, None)
+    }
+
+    /// Constructs a new instance of [`Player`] that is sent a [`KEEPALIVE_MESSAGE`]
+    /// whenever nothing is sent during the given interval.
+    ///
+    /// The connection is shut down if the keepalive cannot be written,
+    /// so that the player is treated as disconnected.
+    /// Also see [`Player::new`].
+    pub fn with_keepalive(stream: TcpStream, keepalive_interval: Option<Duration>) -> Self {
+        stream
+            .set_write_timeout(Some(WRITE_TIMEOUT))
+            .expect("failed to set write timeout");
+        let reader = BufReader::new(stream.try_clone().expect("failed to clone stream"));
+        let (outbox, inbox) = mpsc::channel();
+        let queued_bytes = Arc::new(AtomicUsize::new(0));
+        let json_keepalive = Arc::new(AtomicBool::new(false));
+        let writer = {
+            let stream = stream.try_clone().expect("failed to clone stream");
+            let queued_bytes = Arc::clone(&queued_bytes);
+            let json = Arc::clone(&json_keepalive);
+            thread::spawn(move || {
+                drain_outbox(stream, inbox, &queued_bytes, &json, keepalive_interval)
+            })
+        };
         Self {
             name: String::new(),
             grid: Grid::default(),
+            hints: 0,
+            quick_match: false,
+            spectator: false,
+            wants_summary: false,
+            game_mode: None,
+            compression: false,
+            json: false,
+            max_name_length: DEFAULT_MAX_NAME_LENGTH,
+            invalid_inputs: 0,
+            stats: ConnectionStats::default(),
             stream,
             reader,
+            partial_line: String::new(),
+            outbox: Some(outbox),
+            queued_bytes,
+            json_keepalive,
+            writer: Some(writer),
         }
     }
 
     /// Greets the player with a message and sets the name.
+    ///
+    /// Clients can start the connection with a handshake of [`HANDSHAKE_COMMAND`],
+    /// the server replies with [`HANDSHAKE_REPLY`] and the features that it supports of them
+    /// ([`JSON_FEATURE`] and [`RLE_FEATURE`]). Clients that do not send the handshake
+    /// within [`HANDSHAKE_TIMEOUT`] are greeted in plain text.
+    /// Entering [`QUICK_MATCH_COMMAND`] as the name joins a quick match
+    /// and entering [`SPECTATE_COMMAND`] watches a game.
+    /// Entering [`SUMMARY_COMMAND`] asks for the name again to show the last game of the player.
+    /// Entering [`COMPRESS_COMMAND`] asks for the name again to receive the grids compressed.
     pub fn greet(&mut self) -> Result<()> {
-        self.send(&format!("{}\nPlease enter your name: ", BANNER))?;
-        self.name = self.read()?;
-        if self.name.is_empty() {
+        self.greet_with_motd("")
+    }
+
+    /// Greets the player with a message and the given message of the day, then sets the name.
+    ///
+    /// The message of the day is sent before the name prompt unless it is empty.
+    /// Also see [`Player::greet`].
+    pub fn greet_with_motd(&mut self, motd: &str) -> Result<()> {
+        self.negotiate()?;
+        let mut greeting = format!("{}\n", BANNER);
+        if !motd.is_empty() {
+            greeting += motd;
+            if !motd.ends_with('\n') {
+                greeting.push('\n');
+            }
+        }
+        self.send(&format!("{}Please enter your name: ", greeting))?;
+        self.name = self.read_name()?;
+        if self.name.eq_ignore_ascii_case(COMPRESS_COMMAND) {
+            self.compression = true;
+            self.send("Grids are sent compressed. Please enter your name: ")?;
+            self.name = self.read_name()?;
+        }
+        if self.name.eq_ignore_ascii_case(SUMMARY_COMMAND) {
+            self.wants_summary = true;
+            self.send("Please enter your name: ")?;
+            self.name = self.read_name()?;
+        }
+        if self.name.eq_ignore_ascii_case(QUICK_MATCH_COMMAND) {
+            self.quick_match = true;
+            self.name = format!("Player-{}", fastrand::u16(1000..10000));
+            self.send(&format!("Joining a quick match as {}.\n", self.name))?;
+        } else if self.name.eq_ignore_ascii_case(SPECTATE_COMMAND) {
+            self.spectator = true;
+            self.name = format!("Spectator-{}", fastrand::u16(1000..10000));
+        } else if self.name.is_empty() {
             self.name = String::from("unknown player");
         }
         Ok(())
     }
 
-    /// Writes the given message to the TCP stream.
+    /// Reads the handshake of the client if it is sent within [`HANDSHAKE_TIMEOUT`]
+    /// and selects the features that are supported by both sides.
+    ///
+    /// Anything else that the client sends is left unread, e.g. the name of a legacy client.
+    fn negotiate(&mut self) -> Result<()> {
+        self.stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+        let is_handshake = match self.reader.fill_buf() {
+            Ok(buffer) => buffer.starts_with(format!("{} ", HANDSHAKE_COMMAND).as_bytes()),
+            Err(e) if [ErrorKind::WouldBlock, ErrorKind::TimedOut].contains(&e.kind()) => false,
+            Err(e) => return Err(e.into()),
+        };
+        self.stream.set_read_timeout(None)?;
+        if !is_handshake {
+            return Ok(());
+        }
+        let handshake = self.read_limited(MAX_HANDSHAKE_LENGTH)?;
+        let mut words = handshake.split_whitespace().skip(1);
+        let version = words.next().and_then(|version| version.parse::<u32>().ok());
+        let features = words.collect::<Vec<&str>>();
+        let mut selected = Vec::new();
+        if version.is_some() {
+            for feature in [JSON_FEATURE, RLE_FEATURE].iter() {
+                if features.iter().any(|f| f.eq_ignore_ascii_case(feature)) {
+                    selected.push(*feature);
+                }
+            }
+        }
+        let mut reply = format!("{} {}", HANDSHAKE_REPLY, PROTOCOL_VERSION);
+        for feature in selected.iter() {
+            reply += &format!(" {}", feature);
+        }
+        self.send(&format!("{}\n", reply))?;
+        self.json = selected.contains(&JSON_FEATURE);
+        self.json_keepalive.store(self.json, Ordering::SeqCst);
+        self.compression = selected.contains(&RLE_FEATURE);
+        Ok(())
+    }
+
+    /// Reads the name of the player.
+    ///
+    /// Control characters are removed and the name is truncated to [`Player::max_name_length`].
+    /// At most a few bytes per character are kept of the line, the rest is discarded unread.
+    fn read_name(&mut self) -> Result<String> {
+        let line = self.read_limited(self.max_name_length * 4 + 2)?;
+        Ok(line
+            .chars()
+            .filter(|c| !c.is_control())
+            .take(self.max_name_length)
+            .collect::<String>()
+            .trim()
+            .to_string())
+    }
+
+    /// Asks the player for their preferred game mode.
+    ///
+    /// An empty input means that any mode is fine, invalid modes are asked again.
+    pub fn ask_game_mode(&mut self) -> Result<()> {
+        let modes = GameMode::ALL.iter().map(GameMode::to_string).collect::<Vec<String>>();
+        let prompt = format!(
+            "Preferred game mode ({}) or press enter for any: ",
+            modes.join(", ")
+        );
+        loop {
+            self.send(&prompt)?;
+            let input = self.read()?;
+            if input.is_empty() {
+                self.game_mode = None;
+                return Ok(());
+            }
+            match input.parse() {
+                Ok(mode) => {
+                    self.game_mode = Some(mode);
+                    return Ok(());
+                }
+                Err(_) => self.send("Invalid game mode, try again.\n")?,
+            }
+        }
+    }
+
+    /// Sets the maximum time to wait for a write to the TCP stream.
+    pub fn set_write_timeout(&self, timeout: Duration) -> Result<()> {
+        Ok(self.stream.set_write_timeout(Some(timeout))?)
+    }
+
+    /// Queues the given message to be written to the TCP stream.
+    ///
+    /// It returns without waiting for the write, so a slow client does not block the game.
+    /// A [`BrokenPipe`] error is returned if the connection is closed, the write timed out
+    /// or more than [`MAX_QUEUED_BYTES`] are waiting, so that the player is treated as disconnected.
+    ///
+    /// If [`Player::json`] is set, the message is sent as a JSON object on its own line,
+    /// e.g. `{"type":"message","text":"Hit!\n"}`.
+    ///
+    /// [`BrokenPipe`]: ErrorKind::BrokenPipe
     pub fn send(&mut self, message: &str) -> Result<()> {
-        Ok(self.stream.write_all(message.as_bytes())?)
+        if self.json {
+            self.enqueue(&format!(r#"{{"type":"message","text":{}}}"#, json_string(message)))
+        } else {
+            self.enqueue(message)
+        }
+    }
+
+    /// Queues the given data to be written to the TCP stream as is, see [`Player::send`].
+    fn enqueue(&mut self, message: &str) -> Result<()> {
+        let message = if self.json {
+            format!("{}\n", message)
+        } else {
+            message.to_string()
+        };
+        if self.queued_bytes.load(Ordering::SeqCst) + message.len() > MAX_QUEUED_BYTES {
+            return Err(IoError::new(ErrorKind::BrokenPipe, "client is too slow").into());
+        }
+        self.queued_bytes.fetch_add(message.len(), Ordering::SeqCst);
+        match &self.outbox {
+            Some(outbox) if outbox.send(message.clone()).is_ok() => {
+                self.stats.bytes_sent += message.len();
+                self.stats.writes += 1;
+                Ok(())
+            }
+            _ => Err(IoError::new(ErrorKind::BrokenPipe, "connection closed").into()),
+        }
+    }
+
+    /// Sends the given rendered grid to the player.
+    ///
+    /// If [`Player::compression`] is set, the grid is run-length encoded (see [`encode_rle`])
+    /// and sent after a [`COMPRESSED_HEADER`] line with the length of the encoded grid in bytes.
+    /// If [`Player::json`] is set, the grid is sent as a JSON object with its encoding instead,
+    /// e.g. `{"type":"grid","encoding":"rle","text":"..."}`.
+    pub fn send_grid(&mut self, grid: &str) -> Result<()> {
+        if self.json {
+            let (encoding, text) = if self.compression {
+                ("rle", encode_rle(grid))
+            } else {
+                ("plain", grid.to_string())
+            };
+            self.enqueue(&format!(
+                r#"{{"type":"grid","encoding":"{}","text":{}}}"#,
+                encoding,
+                json_string(&text)
+            ))
+        } else if self.compression {
+            let encoded = encode_rle(grid);
+            self.send(&format!("{}{}\n{}", COMPRESSED_HEADER, encoded.len(), encoded))
+        } else {
+            self.send(grid)
+        }
     }
 
     /// Reads the next line from the TCP stream.
+    ///
+    /// Both `\n` and `\r\n` line endings are stripped.
+    /// Returns an [`UnexpectedEof`] error if the connection is closed.
+    /// If the read fails, the part of the line that is received so far is kept for the next read.
+    ///
+    /// [`UnexpectedEof`]: ErrorKind::UnexpectedEof
     pub fn read(&mut self) -> Result<String> {
-        let mut line = String::new();
-        self.reader.read_line(&mut line)?;
+        let mut line = std::mem::replace(&mut self.partial_line, String::new());
+        let bytes_read = match self.reader.read_line(&mut line) {
+            Ok(bytes_read) => bytes_read,
+            Err(e) => {
+                self.partial_line = line;
+                return Err(e.into());
+            }
+        };
+        if bytes_read == 0 {
+            return Err(IoError::new(ErrorKind::UnexpectedEof, "connection closed").into());
+        }
+        self.stats.bytes_received += line.len();
+        self.stats.reads += 1;
         Ok(line.trim().to_string())
     }
 
+    /// Reads the next line from the TCP stream, waiting at most the given time for the data.
+    ///
+    /// Returns a [`TimedOut`] error if the time runs out, a line that is only partially received
+    /// by then is completed by the next read. A closed connection is still reported
+    /// as [`UnexpectedEof`], see [`Player::read`].
+    ///
+    /// [`TimedOut`]: ErrorKind::TimedOut
+    /// [`UnexpectedEof`]: ErrorKind::UnexpectedEof
+    pub fn read_with_timeout(&mut self, timeout: Duration) -> Result<String> {
+        self.stream.set_read_timeout(Some(timeout))?;
+        let result = self.read();
+        self.stream.set_read_timeout(None)?;
+        match result {
+            Err(e)
+                if e.downcast_ref::<IoError>().map_or(false, |e| {
+                    [ErrorKind::WouldBlock, ErrorKind::TimedOut].contains(&e.kind())
+                }) =>
+            {
+                Err(IoError::new(ErrorKind::TimedOut, "no input in time").into())
+            }
+            result => result,
+        }
+    }
+
+    /// Reads the next line from the TCP stream, keeping at most the given number of bytes.
+    ///
+    /// The rest of a longer line is skipped without buffering it, so that a client
+    /// cannot exhaust the memory with a huge line. Also see [`Player::read`].
+    fn read_limited(&mut self, max_bytes: usize) -> Result<String> {
+        let mut line = Vec::new();
+        let mut bytes_read = (&mut self.reader)
+            .take(max_bytes as u64)
+            .read_until(b'\n', &mut line)?;
+        if bytes_read == 0 {
+            return Err(IoError::new(ErrorKind::UnexpectedEof, "connection closed").into());
+        }
+        while !line.ends_with(b"\n") {
+            let buffer = self.reader.fill_buf()?;
+            if buffer.is_empty() {
+                break;
+            }
+            let (length, is_complete) = match buffer.iter().position(|b| *b == b'\n') {
+                Some(position) => (position + 1, true),
+                None => (buffer.len(), false),
+            };
+            self.reader.consume(length);
+            bytes_read += length;
+            if is_complete {
+                break;
+            }
+        }
+        self.stats.bytes_received += bytes_read;
+        self.stats.reads += 1;
+        Ok(String::from_utf8_lossy(&line).trim().to_string())
+    }
+
+    /// Returns the traffic counters of the TCP connection.
+    pub fn stats(&self) -> ConnectionStats {
+        self.stats
+    }
+
+    /// Checks if the TCP connection is still alive.
+    ///
+    /// Peeks into the stream with a short timeout, a closed connection
+    /// will report the end of the stream.
+    /// The stream is not switched to non-blocking mode since it is shared with the writer thread.
+    pub fn is_alive(&mut self) -> bool {
+        if self.stream.set_read_timeout(Some(Duration::from_millis(1))).is_err() {
+            return false;
+        }
+        let mut buffer = [0; 1];
+        let is_alive = match self.stream.peek(&mut buffer) {
+            Ok(0) => false,
+            Ok(_) => true,
+            Err(e) => [ErrorKind::WouldBlock, ErrorKind::TimedOut].contains(&e.kind()),
+        };
+        is_alive && self.stream.set_read_timeout(None).is_ok()
+    }
+
     /// Shuts down the TCP connection.
+    ///
+    /// The messages that are already queued are written before.
     pub fn exit(&mut self) -> Result<()> {
+        self.outbox = None;
+        if let Some(writer) = self.writer.take() {
+            writer.join().map_err(|_| "[!] Writer thread panicked.")?;
+        }
         self.stream.shutdown(Shutdown::Both)?;
         Ok(())
     }
 }
 
+impl Participant for Player {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    fn grid_mut(&mut self) -> &mut Grid {
+        &mut self.grid
+    }
+
+    fn hints(&self) -> usize {
+        self.hints
+    }
+
+    fn set_hints(&mut self, hints: usize) {
+        self.hints = hints;
+    }
+
+    fn invalid_inputs(&self) -> usize {
+        self.invalid_inputs
+    }
+
+    fn set_invalid_inputs(&mut self, invalid_inputs: usize) {
+        self.invalid_inputs = invalid_inputs;
+    }
+
+    fn quick_match(&self) -> bool {
+        self.quick_match
+    }
+
+    fn game_mode(&self) -> Option<GameMode> {
+        self.game_mode
+    }
+
+    fn send(&mut self, message: &str) -> Result<()> {
+        Player::send(self, message)
+    }
+
+    fn send_grid(&mut self, grid: &str) -> Result<()> {
+        Player::send_grid(self, grid)
+    }
+
+    fn read(&mut self) -> Result<String> {
+        Player::read(self)
+    }
+
+    fn read_with_timeout(&mut self, timeout: Duration) -> Result<String> {
+        Player::read_with_timeout(self, timeout)
+    }
+
+    fn is_alive(&mut self) -> bool {
+        Player::is_alive(self)
+    }
+
+    fn exit(&mut self) -> Result<()> {
+        Player::exit(self)
+    }
+}
+
+/// Writes the message and flushes the writer so that prompts reach the client immediately.
+fn write_message<W: Write>(writer: &mut W, message: &str) -> IoResult<()> {
+    writer.write_all(message.as_bytes())?;
+    writer.flush()
+}
+
+/// Writes the queued messages to the TCP stream until the queue is closed or a write fails.
+///
+/// A keepalive is written whenever the queue is idle for the keepalive interval,
+/// as a JSON line once the JSON mode is negotiated. The connection is shut down afterwards.
+fn drain_outbox(
+    mut stream: TcpStream,
+    inbox: Receiver<String>,
+    queued_bytes: &AtomicUsize,
+    json: &AtomicBool,
+    keepalive_interval: Option<Duration>,
+) {
+    loop {
+        let message = match keepalive_interval {
+            Some(interval) => match inbox.recv_timeout(interval) {
+                Ok(message) => message,
+                Err(RecvTimeoutError::Timeout) => {
+                    if ping(&mut stream, json.load(Ordering::SeqCst)).is_err() {
+                        break;
+                    }
+                    continue;
+                }
+                Err(RecvTimeoutError::Disconnected) => break,
+            },
+            None => match inbox.recv() {
+                Ok(message) => message,
+                Err(_) => break,
+            },
+        };
+        if write_message(&mut stream, &message).is_err() {
+            break;
+        }
+        queued_bytes.fetch_sub(message.len(), Ordering::SeqCst);
+    }
+    let _ = stream.shutdown(Shutdown::Both);
+}
+
+/// Writes the keepalive message for checking if the connection is still alive.
+///
+/// Writes to a closed connection fail once the peer resets it.
+fn ping<W: Write>(writer: &mut W, json: bool) -> IoResult<()> {
+    write_message(writer, if json { JSON_KEEPALIVE_MESSAGE } else { KEEPALIVE_MESSAGE })
+}
+
 /// Shut down the TCP connection when the object goes out of scope.
+///
+/// The writer thread shuts down the connection after writing the queued messages.
 impl Drop for Player {
     fn drop(&mut self) {
         if let Ok(peer_addr) = self.stream.peer_addr() {
-            println!("[+] Ending TCP connection with {:?}", peer_addr);
-            if let Err(e) = self.exit() {
-                eprintln!("[!] Failed to end TCP connection: {}", e)
+            let stats = self.stats;
+            println!(
+                "[+] Ending TCP connection with {:?} (sent {} bytes in {} writes, received {} bytes in {} reads)",
+                peer_addr, stats.bytes_sent, stats.writes, stats.bytes_received, stats.reads
+            );
+        }
+    }
+}
+
+/// Computer player that plays without a connection.
+///
+/// It gets a random grid and hunts the ships of each opponent with the `auto` command
+/// that fires at random cells. After a hit it targets the neighbouring cells until the ship
+/// is sunk, the outcomes are told by [`Participant::on_shot`].
+/// The other prompts are answered with their defaults.
+#[derive(Debug, Default)]
+pub struct BotPlayer {
+    name: String,
+    grid: Grid,
+    hints: usize,
+    invalid_inputs: usize,
+    last_message: String,
+    /// Name of the opponent that the bot shoots next, see [`Participant::set_target`].
+    ///
+    /// It is taken by the next input, so that the other prompts are not answered with a shot.
+    target: Option<String>,
+    /// Shots of the bot at each opponent.
+    tracking: HashMap<String, Tracking>,
+}
+
+/// Shots of a [`BotPlayer`] at one of its opponents.
+#[derive(Debug, Default)]
+struct Tracking {
+    /// Empty grid with the size and the row origin of the grid of the opponent.
+    ///
+    /// The shots are labelled as on the grid of the opponent, see [`Grid::coordinate_label`].
+    grid: Grid,
+    /// Cells that are fired at.
+    shots: Vec<Coordinate>,
+    /// Hits on the ships that are not sunk yet, the latest one last.
+    hits: Vec<Coordinate>,
+}
+
+impl Tracking {
+    /// Returns the cell next to the latest hit that is not fired at yet.
+    fn next_target(&self) -> Option<Coordinate> {
+        let (width, height) = (self.grid.width, self.grid.height);
+        self.hits.iter().rev().find_map(|hit| {
+            neighbours(*hit, width, height).into_iter().find(|c| !self.shots.contains(c))
+        })
+    }
+
+    /// Removes the hits of the sunk ship with the given number of cells and the given hit.
+    ///
+    /// The ship is the hits that are connected to the given one. If there are more of them,
+    /// the ship touches another ship that is hit and only the given hit is removed,
+    /// so that the cells next to the other ship are still fired at.
+    fn sink(&mut self, hit: Coordinate, size: usize) {
+        let (width, height) = (self.grid.width, self.grid.height);
+        let mut ship = vec![hit];
+        let mut i = 0;
+        while i < ship.len() {
+            for cell in neighbours(ship[i], width, height) {
+                if self.hits.contains(&cell) && !ship.contains(&cell) {
+                    ship.push(cell);
+                }
+            }
+            i += 1;
+        }
+        if ship.len() != size {
+            ship = vec![hit];
+        }
+        self.hits.retain(|cell| !ship.contains(cell));
+    }
+}
+
+/// Returns the cells above, right of, below and left of the given one within the grid size.
+fn neighbours(cell: Coordinate, width: u8, height: u8) -> Vec<Coordinate> {
+    let mut cells = Vec::new();
+    if cell.y > 1 {
+        cells.push(Coordinate::new(cell.x, cell.y - 1));
+    }
+    if cell.x < width {
+        cells.push(Coordinate::new(cell.x + 1, cell.y));
+    }
+    if cell.y < height {
+        cells.push(Coordinate::new(cell.x, cell.y + 1));
+    }
+    if cell.x > 1 {
+        cells.push(Coordinate::new(cell.x - 1, cell.y));
+    }
+    cells
+}
+
+impl BotPlayer {
+    /// Constructs a new instance of [`BotPlayer`].
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Self::default()
+        }
+    }
+}
+
+impl Participant for BotPlayer {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    fn grid_mut(&mut self) -> &mut Grid {
+        &mut self.grid
+    }
+
+    fn hints(&self) -> usize {
+        self.hints
+    }
+
+    fn set_hints(&mut self, hints: usize) {
+        self.hints = hints;
+    }
+
+    fn invalid_inputs(&self) -> usize {
+        self.invalid_inputs
+    }
+
+    fn set_invalid_inputs(&mut self, invalid_inputs: usize) {
+        self.invalid_inputs = invalid_inputs;
+    }
+
+    fn quick_match(&self) -> bool {
+        true
+    }
+
+    fn set_target(&mut self, name: &str, width: u8, height: u8, origin: RowOrigin) {
+        let tracking = self.tracking.entry(name.to_string()).or_default();
+        tracking.grid = Grid::new(width, height);
+        tracking.grid.origin = origin;
+        self.target = Some(name.to_string());
+    }
+
+    /// Tracks the outcomes of the shots for picking the next targets:
+    /// the neighbours of a hit are fired at until its ship is sunk.
+    fn on_shot(&mut self, target: &str, coordinate: Coordinate, outcome: &ShotOutcome) {
+        let tracking = self.tracking.entry(target.to_string()).or_default();
+        if !tracking.shots.contains(&coordinate) {
+            tracking.shots.push(coordinate);
+        }
+        match outcome {
+            ShotOutcome::Hit => tracking.hits.push(coordinate),
+            ShotOutcome::Sunk { kind, .. } => {
+                tracking.hits.push(coordinate);
+                tracking.sink(coordinate, usize::from(kind.size()));
+            }
+            _ => {}
+        }
+    }
+
+    /// Keeps the message so that the next input can answer it.
+    fn send(&mut self, message: &str) -> Result<()> {
+        self.last_message = message.to_string();
+        Ok(())
+    }
+
+    /// Answers the last prompt: shoots next to the hits or with `auto`, confirms the shots
+    /// and skips the rest.
+    ///
+    /// The shots are labelled as on the grid of the target, see [`Participant::set_target`].
+    fn read(&mut self) -> Result<String> {
+        Ok(if let Some(target) = self.target.take() {
+            let tracking = self.tracking.entry(target).or_default();
+            match tracking.next_target() {
+                Some(coordinate) => {
+                    tracking.shots.push(coordinate);
+                    tracking.grid.coordinate_label(coordinate)
+                }
+                None => String::from("auto"),
+            }
+        } else if self.last_message.contains("(y/n)") {
+            String::from("y")
+        } else {
+            String::new()
+        })
+    }
+}
+
+/// Participant that holds the place of a disconnected player until they reconnect.
+///
+/// Messages to it are dropped and reading from it fails,
+/// see [`Config::disconnect_grace`].
+///
+/// [`Config::disconnect_grace`]: crate::config::Config::disconnect_grace
+#[derive(Debug, Default)]
+pub struct AbsentPlayer {
+    name: String,
+    grid: Grid,
+    hints: usize,
+    invalid_inputs: usize,
+}
+
+impl AbsentPlayer {
+    /// Constructs a new instance of [`AbsentPlayer`] that takes over the grid of the player.
+    pub fn new(player: &mut dyn Participant) -> Self {
+        Self {
+            name: player.name().to_string(),
+            grid: std::mem::replace(player.grid_mut(), Grid::default()),
+            hints: player.hints(),
+            invalid_inputs: 0,
+        }
+    }
+
+    /// Constructs a new instance of [`AbsentPlayer`] with the given name, grid and hints,
+    /// e.g. for the players of a restored game.
+    pub fn restored(name: &str, grid: Grid, hints: usize) -> Self {
+        Self {
+            name: name.to_string(),
+            grid,
+            hints,
+            invalid_inputs: 0,
+        }
+    }
+}
+
+impl Participant for AbsentPlayer {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    fn grid_mut(&mut self) -> &mut Grid {
+        &mut self.grid
+    }
+
+    fn hints(&self) -> usize {
+        self.hints
+    }
+
+    fn set_hints(&mut self, hints: usize) {
+        self.hints = hints;
+    }
+
+    fn invalid_inputs(&self) -> usize {
+        self.invalid_inputs
+    }
+
+    fn set_invalid_inputs(&mut self, invalid_inputs: usize) {
+        self.invalid_inputs = invalid_inputs;
+    }
+
+    fn quick_match(&self) -> bool {
+        false
+    }
+
+    fn send(&mut self, _message: &str) -> Result<()> {
+        Ok(())
+    }
+
+    fn read(&mut self) -> Result<String> {
+        Err(IoError::new(ErrorKind::NotConnected, "player is disconnected").into())
+    }
+
+    fn is_alive(&mut self) -> bool {
+        false
+    }
+}
+
+/// Participant that reads its inputs from a script and records the sent messages.
+///
+/// It makes the game loop testable without the network,
+/// reading past the end of the script fails as if the player disconnected.
+#[cfg(test)]
+#[derive(Debug, Default)]
+pub struct ScriptedPlayer {
+    name: String,
+    grid: Grid,
+    hints: usize,
+    invalid_inputs: usize,
+    inputs: VecDeque<String>,
+    sent: Arc<Mutex<Vec<String>>>,
+    disconnected: bool,
+}
+
+#[cfg(test)]
+impl ScriptedPlayer {
+    /// Constructs a new instance of [`ScriptedPlayer`] with the given inputs.
+    pub fn new(name: &str, inputs: &[&str]) -> Self {
+        Self {
+            name: name.to_string(),
+            inputs: inputs.iter().map(|input| input.to_string()).collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Returns the messages that are sent to the player.
+    ///
+    /// They are shared so that they can be checked after the game takes the player.
+    pub fn sent(&self) -> Arc<Mutex<Vec<String>>> {
+        Arc::clone(&self.sent)
+    }
+}
+
+#[cfg(test)]
+impl Participant for ScriptedPlayer {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    fn grid_mut(&mut self) -> &mut Grid {
+        &mut self.grid
+    }
+
+    fn hints(&self) -> usize {
+        self.hints
+    }
+
+    fn set_hints(&mut self, hints: usize) {
+        self.hints = hints;
+    }
+
+    fn invalid_inputs(&self) -> usize {
+        self.invalid_inputs
+    }
+
+    fn set_invalid_inputs(&mut self, invalid_inputs: usize) {
+        self.invalid_inputs = invalid_inputs;
+    }
+
+    fn quick_match(&self) -> bool {
+        false
+    }
+
+    fn send(&mut self, message: &str) -> Result<()> {
+        self.sent.lock().map_err(|e| e.to_string())?.push(message.to_string());
+        Ok(())
+    }
+
+    fn read(&mut self) -> Result<String> {
+        match self.inputs.pop_front() {
+            Some(input) => Ok(input),
+            None => {
+                self.disconnected = true;
+                Err(IoError::new(ErrorKind::UnexpectedEof, "script is over").into())
             }
         }
     }
+
+    fn is_alive(&mut self) -> bool {
+        !self.disconnected
+    }
+}
+
+/// Returns a player with the given name that is connected to the returned client stream.
+#[cfg(test)]
+pub fn connect_player(name: &str) -> Result<(Player, TcpStream)> {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+    let client = TcpStream::connect(listener.local_addr()?)?;
+    let mut player = Player::new(listener.accept()?.0);
+    player.name = name.to_string();
+    Ok((player, client))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ship::{Orientation, ShipType};
+    use std::io::Read;
     use std::net::TcpListener;
     use std::thread;
 
@@ -80,12 +1068,317 @@ mod tests {
     #[ignore]
     fn test_player() -> Result<()> {
         let address = "0.0.0.0:3333";
-        let listener = TcpListener::bind(&address)?;
-        thread::spawn(move || listener.accept());
-        let stream = TcpStream::connect(&address)?;
+        let listener = TcpListener::bind(address)?;
+        thread::spawn(move || -> std::io::Result<()> {
+            let (mut stream, _) = listener.accept()?;
+            stream.write_all(b"\r\n")?;
+            Ok(())
+        });
+        let stream = TcpStream::connect(address)?;
         let mut player = Player::new(stream);
         player.greet()?;
         assert_eq!("unknown player", player.name);
         Ok(())
     }
+
+    #[test]
+    fn test_quick_match() -> Result<()> {
+        let (mut player, mut client) = connect_player("")?;
+        client.write_all(b"Quick\n")?;
+        player.greet()?;
+        assert!(player.quick_match);
+        assert!(player.name.starts_with("Player-"));
+        assert_eq!(11, player.name.len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_max_name_length() -> Result<()> {
+        let (mut player, mut client) = connect_player("")?;
+        player.max_name_length = 5;
+        client.write_all(b"Ali\x07ce the Great\n")?;
+        player.greet()?;
+        assert_eq!("Alice", player.name);
+
+        // The rest of a huge line is skipped without being read as the next input.
+        client.write_all(format!("{}\nB2\n", "B".repeat(100_000)).as_bytes())?;
+        player.greet()?;
+        assert_eq!("BBBBB", player.name);
+        assert_eq!("B2", player.read()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_compression() -> Result<()> {
+        let (mut player, mut client) = connect_player("")?;
+        client.write_all(b"compress\nAlice\n")?;
+        player.greet()?;
+        assert!(player.compression);
+        assert_eq!("Alice", player.name);
+        let grid_str = Grid::new(10, 10).as_string(true)?;
+        player.send_grid(&grid_str)?;
+        drop(player);
+        let mut output = String::new();
+        client.read_to_string(&mut output)?;
+        let header = output.find(COMPRESSED_HEADER).expect("compressed grid is not sent");
+        let mut lines = output[header + COMPRESSED_HEADER.len()..].splitn(2, '\n');
+        let length = lines.next().unwrap_or_default().parse::<usize>()?;
+        let payload = lines.next().unwrap_or_default();
+        assert_eq!(length, payload.len());
+        assert!(payload.len() < grid_str.len());
+        assert_eq!(grid_str, crate::compression::decode_rle(payload)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_handshake() -> Result<()> {
+        let (mut player, mut client) = connect_player("")?;
+        client.write_all(b"HELLO 1 xml json\nAlice\n")?;
+        player.greet()?;
+        assert!(player.json);
+        assert!(!player.compression);
+        assert_eq!("Alice", player.name);
+        player.send("Hit!\n")?;
+        player.send_grid("|  |\n")?;
+        drop(player);
+        let mut output = String::new();
+        client.read_to_string(&mut output)?;
+        let lines = output.lines().collect::<Vec<&str>>();
+        assert_eq!("WELCOME 1 json", lines[0]);
+        assert!(lines[1].starts_with(r#"{"type":"message","text":"#));
+        assert!(lines[1].contains("Please enter your name: "));
+        assert_eq!(
+            [
+                r#"{"type":"message","text":"Hit!\u000a"}"#,
+                r#"{"type":"grid","encoding":"plain","text":"|  |\u000a"}"#,
+            ],
+            lines[2..]
+        );
+
+        // Legacy clients that do not send the handshake are greeted in plain text.
+        let (mut player, mut client) = connect_player("")?;
+        let legacy_client = thread::spawn(move || -> std::io::Result<String> {
+            let mut output = Vec::new();
+            let mut buffer = [0; 1];
+            while !output.ends_with(b"Please enter your name: ") {
+                client.read_exact(&mut buffer)?;
+                output.push(buffer[0]);
+            }
+            client.write_all(b"Bob\n")?;
+            client.read_to_end(&mut output)?;
+            Ok(String::from_utf8_lossy(&output).to_string())
+        });
+        player.greet()?;
+        assert!(!player.json);
+        assert_eq!("Bob", player.name);
+        player.send("Hit!\n")?;
+        drop(player);
+        let output = legacy_client.join().map_err(|_| "[!] Client thread panicked.")??;
+        assert!(output.starts_with(BANNER));
+        assert!(output.ends_with("Please enter your name: Hit!\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_ask_game_mode() -> Result<()> {
+        let (mut player, mut client) = connect_player("")?;
+        client.write_all(b"blitz\nRadar\n\n")?;
+        player.ask_game_mode()?;
+        assert_eq!(Some(GameMode::Radar), player.game_mode);
+        player.ask_game_mode()?;
+        assert_eq!(None, player.game_mode);
+        drop(player);
+        let mut output = String::new();
+        client.read_to_string(&mut output)?;
+        let prompt =
+            "Preferred game mode (standard, practice, radar, teaching) or press enter for any: ";
+        assert_eq!(format!("{0}Invalid game mode, try again.\n{0}{0}", prompt), output);
+        Ok(())
+    }
+
+    #[test]
+    fn test_motd() -> Result<()> {
+        let (mut player, mut client) = connect_player("")?;
+        client.write_all(b"Alice\nBob\n")?;
+        player.greet_with_motd("Rules: 10x10, no touching")?;
+        player.greet_with_motd("")?;
+        drop(player);
+        let mut output = String::new();
+        client.read_to_string(&mut output)?;
+        let expected = format!(
+            "{}\nRules: 10x10, no touching\nPlease enter your name: {}\nPlease enter your name: ",
+            BANNER, BANNER
+        );
+        assert_eq!(expected, output);
+        Ok(())
+    }
+
+    #[test]
+    fn test_greet_disconnect() -> Result<()> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let stream = TcpStream::connect(listener.local_addr()?)?;
+        drop(listener.accept()?);
+        let mut player = Player::new(stream);
+        let error = player.greet().expect_err("player is greeted");
+        assert_eq!(
+            Some(ErrorKind::UnexpectedEof),
+            error.downcast_ref::<IoError>().map(|e| e.kind())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_timeout() -> Result<()> {
+        let (mut player, _client) = connect_player("")?;
+        player.set_write_timeout(Duration::from_millis(100))?;
+        let message = "x".repeat(1024 * 1024);
+        let error = (0..1024)
+            .find_map(|_| player.send(&message).err())
+            .expect("write did not time out");
+        assert_eq!(
+            Some(ErrorKind::BrokenPipe),
+            error.downcast_ref::<IoError>().map(|e| e.kind())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_stats() -> Result<()> {
+        let (mut player, mut client) = connect_player("")?;
+        assert_eq!(ConnectionStats::default(), player.stats());
+        player.send("Hello\n")?;
+        player.send("Your turn: ")?;
+        client.write_all(b"A1\r\n")?;
+        assert_eq!("A1", player.read()?);
+        assert_eq!(
+            ConnectionStats {
+                bytes_sent: 17,
+                bytes_received: 4,
+                writes: 2,
+                reads: 1,
+            },
+            player.stats()
+        );
+        Ok(())
+    }
+
+    /// Writer that counts the flushes.
+    #[derive(Default)]
+    struct FlushCounter {
+        written: Vec<u8>,
+        flushes: usize,
+    }
+
+    impl Write for FlushCounter {
+        fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+            self.written.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> IoResult<()> {
+            self.flushes += 1;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_write_message() -> Result<()> {
+        let mut writer = FlushCounter::default();
+        write_message(&mut writer, "Hello\n")?;
+        write_message(&mut writer, "Your turn to shoot Bob: ")?;
+        assert_eq!(b"Hello\nYour turn to shoot Bob: ".to_vec(), writer.written);
+        assert_eq!(2, writer.flushes);
+        Ok(())
+    }
+
+    #[test]
+    fn test_keepalive() -> Result<()> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let mut client = TcpStream::connect(listener.local_addr()?)?;
+        let interval = Duration::from_millis(50);
+        let mut player = Player::with_keepalive(listener.accept()?.0, Some(interval));
+        thread::sleep(interval * 3);
+        let mut buffer = [0; 1];
+        client.read_exact(&mut buffer)?;
+        assert_eq!(KEEPALIVE_MESSAGE.as_bytes(), buffer);
+
+        // The dropped connection is detected before sending the next message.
+        drop(client);
+        thread::sleep(interval * 4);
+        assert!(player.send("Your turn to shoot Bob: ").is_err());
+        assert!(player.read().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_json_keepalive() -> Result<()> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let mut client = TcpStream::connect(listener.local_addr()?)?;
+        let interval = Duration::from_millis(50);
+        let mut player = Player::with_keepalive(listener.accept()?.0, Some(interval));
+        client.write_all(b"HELLO 1 json\nAlice\n")?;
+        player.greet()?;
+        thread::sleep(interval * 3);
+        drop(player);
+        let mut output = String::new();
+        client.read_to_string(&mut output)?;
+        let lines = output.lines().skip(2).collect::<Vec<&str>>();
+        assert!(!lines.is_empty());
+        assert!(lines.iter().all(|line| *line == JSON_KEEPALIVE_MESSAGE.trim_end()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_alive() -> Result<()> {
+        let (mut player, client) = connect_player("")?;
+        assert!(player.is_alive());
+        drop(client);
+        thread::sleep(std::time::Duration::from_millis(100));
+        assert!(!player.is_alive());
+        Ok(())
+    }
+
+    #[test]
+    fn test_bot_targets_hits() -> Result<()> {
+        let mut bot = BotPlayer::new("Bot");
+        let boat = || ShotOutcome::Sunk {
+            kind: ShipType::Boat,
+            cells: Vec::new(),
+        };
+        let destroyer = || ShotOutcome::Sunk {
+            kind: ShipType::Destroyer(Orientation::Vertical),
+            cells: Vec::new(),
+        };
+        let mut shoot = |target: &str, origin, fired: &str, outcome| -> Result<String> {
+            let size = if target == "Alice" { (2, 3) } else { (5, 5) };
+            bot.set_target(target, size.0, size.1, origin);
+            bot.send(&format!("Your turn to shoot {}: ", target))?;
+            let shot = bot.read()?;
+            let mut grid = Grid::new(size.0, size.1);
+            grid.origin = origin;
+            let coordinate = grid.parse_coordinate(fired).ok_or("invalid coordinate")?;
+            bot.on_shot(target, coordinate, &outcome);
+            Ok(shot)
+        };
+        assert_eq!("auto", shoot("Alice", RowOrigin::Top, "B2", ShotOutcome::Hit)?);
+
+        // The neighbours of the hit inside the grid of the target are fired at next.
+        assert_eq!("B1", shoot("Alice", RowOrigin::Top, "B1", boat())?);
+        assert_eq!("auto", shoot("Bob", RowOrigin::Top, "A1", ShotOutcome::Miss)?);
+
+        // The touching ship that is not sunk yet is still targeted.
+        assert_eq!("B3", shoot("Alice", RowOrigin::Top, "B3", destroyer())?);
+        assert_eq!("auto", shoot("Alice", RowOrigin::Top, "A1", ShotOutcome::Miss)?);
+
+        // The shots are labelled with the row numbers of the target, B3 is above B2 here.
+        assert_eq!("auto", shoot("Bob", RowOrigin::Bottom, "B2", ShotOutcome::Hit)?);
+        assert_eq!("B3", shoot("Bob", RowOrigin::Bottom, "B3", ShotOutcome::Miss)?);
+
+        // Only the prompt for a shot is answered with a shot.
+        bot.send("Fire at B3? (y/n) ")?;
+        assert_eq!("y", bot.read()?);
+        bot.send("Please enter your name: ")?;
+        assert_eq!("", bot.read()?);
+        Ok(())
+    }
 }
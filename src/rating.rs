@@ -0,0 +1,80 @@
+//! Ratings of the players.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Rating of the players that have not played yet.
+pub const INITIAL_RATING: i32 = 1200;
+/// Maximum change of the rating against a single opponent.
+pub const K_FACTOR: f64 = 32.0;
+
+/// Ratings that are shared between the games.
+pub type SharedRatings = Arc<Mutex<Ratings>>;
+
+/// [Elo ratings] of the players, keyed by their names.
+///
+/// [Elo ratings]: https://en.wikipedia.org/wiki/Elo_rating_system
+#[derive(Debug, Default)]
+pub struct Ratings {
+    ratings: HashMap<String, i32>,
+}
+
+impl Ratings {
+    /// Returns the rating of the player.
+    pub fn get(&self, name: &str) -> i32 {
+        self.ratings.get(name).copied().unwrap_or(INITIAL_RATING)
+    }
+
+    /// Updates the ratings of the players after a game.
+    ///
+    /// Each pair of players is rated as a match of its own: the winner beats
+    /// every other player and the rest of the pairs are rated as draws.
+    pub fn record_game(&mut self, names: &[&str], winner: Option<&str>) {
+        let ratings = names.iter().map(|name| self.get(name)).collect::<Vec<i32>>();
+        for (i, name) in names.iter().enumerate() {
+            let delta = names
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(j, opponent)| {
+                    let score = if winner == Some(*name) {
+                        1.0
+                    } else if winner == Some(*opponent) {
+                        0.0
+                    } else {
+                        0.5
+                    };
+                    let exponent = f64::from(ratings[j] - ratings[i]) / 400.0;
+                    let expected = 1.0 / (1.0 + 10_f64.powf(exponent));
+                    K_FACTOR * (score - expected)
+                })
+                .sum::<f64>();
+            self.ratings.insert(name.to_string(), ratings[i] + delta.round() as i32);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ratings() {
+        let mut ratings = Ratings::default();
+        assert_eq!(INITIAL_RATING, ratings.get("Alice"));
+        ratings.record_game(&["Alice", "Bob"], Some("Alice"));
+        assert_eq!(INITIAL_RATING + 16, ratings.get("Alice"));
+        assert_eq!(INITIAL_RATING - 16, ratings.get("Bob"));
+
+        // Beating a stronger player is worth more.
+        ratings.record_game(&["Alice", "Bob"], Some("Bob"));
+        assert!(ratings.get("Bob") > INITIAL_RATING);
+        assert!(ratings.get("Alice") < INITIAL_RATING);
+
+        ratings.record_game(&["Carol", "Dan", "Erin"], None);
+        assert_eq!(INITIAL_RATING, ratings.get("Carol"));
+        ratings.record_game(&["Carol", "Dan", "Erin"], Some("Erin"));
+        assert_eq!(INITIAL_RATING + 32, ratings.get("Erin"));
+        assert_eq!(INITIAL_RATING - 16, ratings.get("Dan"));
+    }
+}
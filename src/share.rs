@@ -0,0 +1,85 @@
+//! Encoding of the shared games.
+
+use crate::Result;
+
+/// Characters of the standard [Base64] alphabet.
+///
+/// [Base64]: https://datatracker.ietf.org/doc/html/rfc4648#section-4
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+/// Character that pads the encoded string to a multiple of 4 characters.
+const BASE64_PADDING: char = '=';
+
+/// Encodes the given bytes as a Base64 string with padding.
+pub fn encode_base64(bytes: &[u8]) -> String {
+    let mut s = String::new();
+    for chunk in bytes.chunks(3) {
+        let group = chunk
+            .iter()
+            .enumerate()
+            .fold(0_u32, |group, (i, byte)| group | (u32::from(*byte) << (16 - 8 * i)));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                let index = (group >> (18 - 6 * i)) & 0x3f;
+                s.push(char::from(BASE64_ALPHABET[index as usize]));
+            } else {
+                s.push(BASE64_PADDING);
+            }
+        }
+    }
+    s
+}
+
+/// Decodes the given Base64 string.
+///
+/// Surrounding whitespace is ignored, the padding is optional.
+/// Returns an error if the string contains characters outside of the alphabet.
+pub fn decode_base64(s: &str) -> Result<Vec<u8>> {
+    let values = s
+        .trim()
+        .trim_end_matches(BASE64_PADDING)
+        .bytes()
+        .map(|c| BASE64_ALPHABET.iter().position(|a| *a == c))
+        .collect::<Option<Vec<usize>>>()
+        .ok_or("[!] Invalid Base64 string.")?;
+    let mut bytes = Vec::new();
+    for chunk in values.chunks(4) {
+        if chunk.len() == 1 {
+            return Err("[!] Invalid Base64 string.".into());
+        }
+        let group = chunk
+            .iter()
+            .enumerate()
+            .fold(0_u32, |group, (i, value)| group | ((*value as u32) << (18 - 6 * i)));
+        for i in 0..chunk.len() - 1 {
+            bytes.push((group >> (16 - 8 * i)) as u8);
+        }
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base64() -> Result<()> {
+        let test_cases = [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ];
+        for (decoded, encoded) in test_cases.iter() {
+            assert_eq!(*encoded, encode_base64(decoded.as_bytes()));
+            assert_eq!(decoded.as_bytes(), decode_base64(encoded)?.as_slice());
+        }
+        assert_eq!(b"fo".to_vec(), decode_base64("Zm8")?);
+        assert!(decode_base64("Zm9v!").is_err());
+        assert!(decode_base64("Zm9vY").is_err());
+        Ok(())
+    }
+}
@@ -1,7 +1,10 @@
 //! Ship.
 
 use crate::grid::Coordinate;
+use fastrand::Rng;
+use std::convert::TryFrom;
 use std::fmt;
+use std::result::Result as StdResult;
 
 /// The character that represents a boat.
 const BOAT: &str = "⛵";
@@ -11,6 +14,15 @@ const DESTROYER_VERTICAL: &str = "👾";
 const DESTROYER_HORIZONTAL: &str = "👾";
 /// The character that represents a part of a battleship.
 const BATTLESHIP: &str = "👾";
+/// The character that represents a part of a submarine.
+const SUBMARINE: &str = "👾";
+/// The character that represents a part of a cruiser.
+const CRUISER: &str = "👾";
+/// The character that represents a part of a carrier.
+const CARRIER: &str = "👾";
+
+/// Ship sizes of the standard fleet.
+pub const STANDARD_FLEET: [u8; 5] = [5, 4, 3, 3, 2];
 
 /// Available orientations for the ship.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -30,6 +42,12 @@ pub enum ShipType {
     Destroyer(Orientation),
     /// 3x2 or 2x3 ship.
     Battleship(Orientation),
+    /// 1x3 or 3x1 ship.
+    Submarine(Orientation),
+    /// 1x4 or 4x1 ship.
+    Cruiser(Orientation),
+    /// 1x5 or 5x1 ship.
+    Carrier(Orientation),
 }
 
 /// Default ship is Boat because it is smol ^_^
@@ -49,7 +67,10 @@ impl fmt::Display for ShipType {
                 ShipType::Boat => BOAT,
                 ShipType::Destroyer(Orientation::Vertical) => DESTROYER_VERTICAL,
                 ShipType::Destroyer(Orientation::Horizontal) => DESTROYER_HORIZONTAL,
-                ShipType::Battleship(_) => BATTLESHIP
+                ShipType::Battleship(_) => BATTLESHIP,
+                ShipType::Submarine(_) => SUBMARINE,
+                ShipType::Cruiser(_) => CRUISER,
+                ShipType::Carrier(_) => CARRIER,
             }
         )
     }
@@ -57,6 +78,8 @@ impl fmt::Display for ShipType {
 
 impl ShipType {
     /// Returns the possible variants for [`ShipType`].
+    ///
+    /// The longer straight ships are only used by the scaled fleets, see [`fleet_for_board`].
     pub fn variants() -> Vec<Self> {
         vec![
             Self::Boat,
@@ -67,10 +90,86 @@ impl ShipType {
         ]
     }
 
+    /// Returns the orientation of the ship type, `None` for [`Boat`].
+    ///
+    /// [`Boat`]: ShipType::Boat
+    pub fn orientation(&self) -> Option<Orientation> {
+        match self {
+            Self::Boat => None,
+            Self::Destroyer(orientation)
+            | Self::Submarine(orientation)
+            | Self::Cruiser(orientation)
+            | Self::Carrier(orientation)
+            | Self::Battleship(orientation) => Some(*orientation),
+        }
+    }
+
+    /// Returns the [`ShipType`] that occupies the given number of cells.
+    ///
+    /// The orientation is ignored for [`Boat`].
+    ///
+    /// [`Boat`]: ShipType::Boat
+    pub fn from_size(size: u8, orientation: Orientation) -> Option<Self> {
+        match size {
+            1 => Some(Self::Boat),
+            2 => Some(Self::Destroyer(orientation)),
+            3 => Some(Self::Submarine(orientation)),
+            4 => Some(Self::Cruiser(orientation)),
+            5 => Some(Self::Carrier(orientation)),
+            6 => Some(Self::Battleship(orientation)),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the ship type.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Boat => "Boat",
+            Self::Destroyer(_) => "Destroyer",
+            Self::Battleship(_) => "Battleship",
+            Self::Submarine(_) => "Submarine",
+            Self::Cruiser(_) => "Cruiser",
+            Self::Carrier(_) => "Carrier",
+        }
+    }
+
+    /// Returns the [`ShipType`] with the swapped orientation.
+    pub fn transposed(&self) -> Self {
+        let swap = |orientation: &Orientation| match orientation {
+            Orientation::Vertical => Orientation::Horizontal,
+            Orientation::Horizontal => Orientation::Vertical,
+        };
+        match self {
+            Self::Boat => Self::Boat,
+            Self::Destroyer(orientation) => Self::Destroyer(swap(orientation)),
+            Self::Battleship(orientation) => Self::Battleship(swap(orientation)),
+            Self::Submarine(orientation) => Self::Submarine(swap(orientation)),
+            Self::Cruiser(orientation) => Self::Cruiser(swap(orientation)),
+            Self::Carrier(orientation) => Self::Carrier(swap(orientation)),
+        }
+    }
+
+    /// Returns the number of cells that the ship occupies.
+    pub fn size(&self) -> u8 {
+        match self {
+            Self::Boat => 1,
+            Self::Destroyer(_) => 2,
+            Self::Submarine(_) => 3,
+            Self::Cruiser(_) => 4,
+            Self::Carrier(_) => 5,
+            Self::Battleship(_) => 6,
+        }
+    }
+
     /// Returns a random [`ShipType`].
     pub fn new_random() -> Self {
+        Self::new_random_with_rng(&Rng::new())
+    }
+
+    /// Returns a random [`ShipType`] using the given random number generator.
+    pub fn new_random_with_rng(rng: &Rng) -> Self {
         let types = Self::variants();
-        types[fastrand::usize(..types.len())]
+        types[rng.usize(..types.len())]
     }
 
     /// Returns the hit box of the ship.
@@ -81,20 +180,23 @@ impl ShipType {
             Self::Boat => {
                 vec![coordinate]
             }
-            Self::Destroyer(orientation) => {
-                vec![coordinate, {
+            Self::Destroyer(orientation)
+            | Self::Submarine(orientation)
+            | Self::Cruiser(orientation)
+            | Self::Carrier(orientation) => (0..self.size())
+                .map(|i| {
                     let mut coord = coordinate;
                     match orientation {
                         Orientation::Vertical => {
-                            coord.y += 1;
+                            coord.y += i;
                         }
                         Orientation::Horizontal => {
-                            coord.x += 1;
+                            coord.x += i;
                         }
                     }
                     coord
-                }]
-            }
+                })
+                .collect(),
             Self::Battleship(orientation) => {
                 let mut coordinates = Vec::new();
                 for i in 0..2 {
@@ -128,6 +230,147 @@ impl ShipType {
     }
 }
 
+/// Placement of a ship on the grid.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Placement {
+    /// Top left coordinate of the ship.
+    pub coordinate: Coordinate,
+    /// Orientation of the ship.
+    pub orientation: Orientation,
+    /// Bottom right coordinate of the ship if it is placed by its endpoints.
+    pub end: Option<Coordinate>,
+}
+
+impl Placement {
+    /// Constructs a new instance of [`Placement`] from the endpoints of a ship.
+    ///
+    /// The endpoints can be given in any order, the orientation is derived from them.
+    /// Returns `None` if the endpoints are not on the same row or column,
+    /// diagonal ships are not supported.
+    pub fn from_range(start: Coordinate, end: Coordinate) -> Option<Self> {
+        let (start, end) = if (start.x, start.y) <= (end.x, end.y) {
+            (start, end)
+        } else {
+            (end, start)
+        };
+        let orientation = if start.y == end.y {
+            Orientation::Horizontal
+        } else if start.x == end.x {
+            Orientation::Vertical
+        } else {
+            return None;
+        };
+        Some(Self {
+            coordinate: start,
+            orientation,
+            end: Some(end),
+        })
+    }
+
+    /// Returns the ship of the given size for this placement.
+    ///
+    /// Returns `None` if the placement is a range that does not span the given size
+    /// or if the ship would not fit in the coordinates.
+    pub fn to_ship(&self, size: u8) -> Option<Ship> {
+        if let Some(end) = self.end {
+            let span = (end.x - self.coordinate.x) + (end.y - self.coordinate.y) + 1;
+            if span != size {
+                return None;
+            }
+        }
+        let ship_type = ShipType::from_size(size, self.orientation)?;
+        // The cells of a ship are at most one less than its size away from the start.
+        self.coordinate.x.checked_add(size - 1)?;
+        self.coordinate.y.checked_add(size - 1)?;
+        Some(Ship::new(ship_type, ship_type.get_hitbox(self.coordinate)))
+    }
+}
+
+/// For converting between e.g. "C3 v" to a vertical placement at (3, 3)
+///
+/// Orientation is horizontal if it is not given.
+/// The endpoints of the ship can be given instead, e.g. "C3:C6", see [`Placement::from_range`].
+impl TryFrom<String> for Placement {
+    type Error = ();
+    fn try_from(value: String) -> StdResult<Self, Self::Error> {
+        if let Some(separator) = value.find(':') {
+            let start = Coordinate::try_from(value[..separator].to_string())?;
+            let end = Coordinate::try_from(value[separator + 1..].to_string())?;
+            return Self::from_range(start, end).ok_or(());
+        }
+        let mut values = value.split_whitespace();
+        let coordinate = Coordinate::try_from(values.next().ok_or(())?.to_string())?;
+        let orientation = values.next().map(|v| v.to_lowercase());
+        let orientation = match orientation.as_ref().map(String::as_str) {
+            None | Some("h") | Some("horizontal") => Orientation::Horizontal,
+            Some("v") | Some("vertical") => Orientation::Vertical,
+            _ => return Err(()),
+        };
+        if values.next().is_some() {
+            return Err(());
+        }
+        Ok(Self {
+            coordinate,
+            orientation,
+            end: None,
+        })
+    }
+}
+
+/// Checks if the fleet can fit on a board of the given size.
+///
+/// Each ship has to fit on the board in one of the orientations
+/// and the ships together cannot occupy more cells than the board has.
+pub fn fleet_fits_board(width: u8, height: u8, fleet: &[u8]) -> bool {
+    let cells = fleet.iter().map(|size| usize::from(*size)).sum::<usize>();
+    let fits = |size: u8| {
+        [Orientation::Vertical, Orientation::Horizontal].iter().any(|orientation| {
+            ShipType::from_size(size, *orientation).map_or(false, |ship_type| {
+                ship_type
+                    .get_hitbox(Coordinate::new(1, 1))
+                    .iter()
+                    .all(|c| c.x <= width && c.y <= height)
+            })
+        })
+    };
+    cells <= usize::from(width) * usize::from(height) && fleet.iter().all(|size| fits(*size))
+}
+
+/// Returns the ship sizes of a fleet for the given board size.
+///
+/// Ships are picked from the [`STANDARD_FLEET`] until they occupy
+/// roughly the given fraction (`density`) of the cells, it is expected to be between 0 and 1.
+/// Ships that do not fit on the board are ignored.
+pub fn fleet_for_board(width: u8, height: u8, density: f32) -> Vec<u8> {
+    let max_size = width.max(height);
+    let sizes = STANDARD_FLEET
+        .iter()
+        .copied()
+        .filter(|size| *size <= max_size)
+        .collect::<Vec<u8>>();
+    let target = (f32::from(width) * f32::from(height) * density).round() as usize;
+    let mut fleet = Vec::new();
+    let mut total = 0;
+    loop {
+        let mut is_added = false;
+        for size in &sizes {
+            if total + usize::from(*size) <= target {
+                fleet.push(*size);
+                total += usize::from(*size);
+                is_added = true;
+            }
+        }
+        if !is_added {
+            break;
+        }
+    }
+    if fleet.is_empty() && width > 0 && height > 0 {
+        fleet.push(ShipType::Boat.size());
+    }
+    fleet.sort_unstable_by(|a, b| b.cmp(a));
+    fleet
+}
+
 /// Representation of a ship.
 #[derive(Default, Debug)]
 pub struct Ship {
@@ -135,24 +378,110 @@ pub struct Ship {
     pub type_: ShipType,
     /// Coordinates of the ship.
     pub coords: Vec<Coordinate>,
+    /// Whether if the ship is a decoy.
+    ///
+    /// Decoys are reported as hits but they never sink
+    /// and they are not needed to be destroyed for winning the game.
+    pub decoy: bool,
 }
 
 impl Ship {
     /// Constructs a new instance of [`Ship`].
     pub fn new(type_: ShipType, coords: Vec<Coordinate>) -> Self {
-        Self { type_, coords }
+        Self {
+            type_,
+            coords,
+            decoy: false,
+        }
+    }
+
+    /// Constructs a new decoy at the given coordinate.
+    pub fn new_decoy(coordinate: Coordinate) -> Self {
+        Self {
+            decoy: true,
+            ..Self::new(ShipType::Boat, vec![coordinate])
+        }
     }
 
     /// Constructs a new instance of [`Ship`] with random properties.
     pub fn new_random(max_x: u8, max_y: u8) -> Self {
-        let ship_type = ShipType::new_random();
-        let coordinate = Coordinate::from((fastrand::u8(1..=max_x), fastrand::u8(1..=max_y)));
+        Self::new_random_with_rng(max_x, max_y, &Rng::new())
+    }
+
+    /// Constructs a new instance of [`Ship`] with random properties
+    /// using the given random number generator.
+    pub fn new_random_with_rng(max_x: u8, max_y: u8, rng: &Rng) -> Self {
+        let ship_type = ShipType::new_random_with_rng(rng);
+        let coordinate = Coordinate::new(rng.u8(1..=max_x), rng.u8(1..=max_y));
         Self::new(ship_type, ship_type.get_hitbox(coordinate))
     }
 
     /// Returns whether if the ship is sunk.
+    ///
+    /// Decoys are never sunk.
     pub fn is_sunk(&self) -> bool {
-        self.coords.iter().all(|c| c.is_hit)
+        !self.decoy && self.coords.iter().all(|c| c.is_hit)
+    }
+
+    /// Returns the number of cells that the ship occupies.
+    pub fn size(&self) -> usize {
+        self.coords.len()
+    }
+
+    /// Returns the number of cells that are not hit yet.
+    pub fn cells_remaining(&self) -> usize {
+        self.coords.iter().filter(|c| !c.is_hit).count()
+    }
+
+    /// Checks if the ship occupies the given coordinate.
+    ///
+    /// Coordinates are compared by their position, whether if they are hit is ignored.
+    pub fn contains(&self, coordinate: &Coordinate) -> bool {
+        self.coords.iter().any(|c| c == coordinate)
+    }
+
+    /// Checks if the coordinates form a single straight run without gaps.
+    ///
+    /// Diagonal runs are accepted if `allow_diagonal` is set. Single-cell ships are always valid.
+    /// Note that the [`Battleship`] is a block rather than a run.
+    ///
+    /// [`Battleship`]: ShipType::Battleship
+    pub fn is_valid_shape(&self, allow_diagonal: bool) -> bool {
+        let mut coords = self.coords.iter().map(|c| (c.x, c.y)).collect::<Vec<(u8, u8)>>();
+        coords.sort_unstable();
+        let steps = coords
+            .windows(2)
+            .map(|pair| {
+                (
+                    i16::from(pair[1].0) - i16::from(pair[0].0),
+                    i16::from(pair[1].1) - i16::from(pair[0].1),
+                )
+            })
+            .collect::<Vec<(i16, i16)>>();
+        match steps.first() {
+            None => !coords.is_empty(),
+            Some(step) => {
+                let is_straight = *step == (1, 0) || *step == (0, 1);
+                let is_diagonal = step.0 == 1 && step.1.abs() == 1;
+                (is_straight || (allow_diagonal && is_diagonal)) && steps.iter().all(|s| s == step)
+            }
+        }
+    }
+
+    /// Returns the orientation of the ship that is derived from its coordinates.
+    ///
+    /// Returns `None` for single-cell ships and for cells that are not on a straight line.
+    pub fn orientation(&self) -> Option<Orientation> {
+        let first = self.coords.first()?;
+        if self.coords.len() < 2 {
+            None
+        } else if self.coords.iter().all(|c| c.y == first.y) {
+            Some(Orientation::Horizontal)
+        } else if self.coords.iter().all(|c| c.x == first.x) {
+            Some(Orientation::Vertical)
+        } else {
+            None
+        }
     }
 }
 
@@ -212,6 +541,185 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_ship_size() {
+        let scaled_types = (3..=5).filter_map(|size| ShipType::from_size(size, Orientation::Vertical));
+        for ship_type in ShipType::variants().into_iter().chain(scaled_types) {
+            assert_eq!(
+                ship_type.size() as usize,
+                ship_type.get_hitbox(Coordinate::from((1, 1))).len()
+            );
+        }
+        assert_eq!(
+            Some(ShipType::Submarine(Orientation::Vertical)),
+            ShipType::from_size(3, Orientation::Vertical)
+        );
+        assert_eq!(
+            vec![
+                Coordinate::from((2, 3)),
+                Coordinate::from((3, 3)),
+                Coordinate::from((4, 3)),
+                Coordinate::from((5, 3))
+            ],
+            ShipType::Cruiser(Orientation::Horizontal).get_hitbox(Coordinate::from((2, 3)))
+        );
+        assert_eq!(None, ShipType::from_size(7, Orientation::Vertical));
+        assert_eq!("Cruiser", ShipType::Cruiser(Orientation::Vertical).name());
+    }
+
+    #[test]
+    fn test_fleet_for_board() {
+        assert_eq!(vec![5, 4, 3, 3, 3, 2], fleet_for_board(10, 10, 0.2));
+        for (width, height, density) in [(5, 5, 0.2), (10, 10, 0.17), (15, 12, 0.3), (26, 26, 0.2)] {
+            let fleet = fleet_for_board(width, height, density);
+            let cells = fleet.iter().map(|size| f32::from(*size)).sum::<f32>();
+            let target = f32::from(width) * f32::from(height) * density;
+            assert!(cells <= target.round());
+            assert!(target - cells < f32::from(STANDARD_FLEET[STANDARD_FLEET.len() - 1]));
+            assert!(fleet.iter().all(|size| *size <= width.max(height)));
+        }
+        assert_eq!(vec![1], fleet_for_board(2, 2, 0.0));
+    }
+
+    #[test]
+    fn test_fleet_fits_board() {
+        assert!(fleet_fits_board(10, 10, &STANDARD_FLEET));
+        assert!(fleet_fits_board(5, 4, &STANDARD_FLEET[..2]));
+        assert!(!fleet_fits_board(4, 4, &STANDARD_FLEET));
+        assert!(!fleet_fits_board(2, 2, &[1, 1, 1, 1, 1]));
+        assert!(!fleet_fits_board(1, 5, &[6]));
+        assert!(!fleet_fits_board(10, 10, &[7]));
+        assert!(fleet_fits_board(2, 3, &[6]));
+    }
+
+    #[test]
+    fn test_placement() {
+        assert_eq!(
+            Ok(Placement {
+                coordinate: Coordinate::from((3, 3)),
+                orientation: Orientation::Vertical,
+                end: None,
+            }),
+            Placement::try_from(String::from("C3 v"))
+        );
+        assert_eq!(
+            Ok(Placement {
+                coordinate: Coordinate::from((1, 10)),
+                orientation: Orientation::Horizontal,
+                end: None,
+            }),
+            Placement::try_from(String::from("a10"))
+        );
+        assert!(Placement::try_from(String::from("C3 x")).is_err());
+        assert!(Placement::try_from(String::from("C3 v h")).is_err());
+        let placement = Placement::try_from(String::from("A254 v")).expect("invalid placement");
+        assert!(placement.to_ship(4).is_none());
+        assert!(Placement::try_from(String::new()).is_err());
+        let ship = Placement::try_from(String::from("B2 vertical"))
+            .expect("invalid placement")
+            .to_ship(3)
+            .expect("ship does not fit");
+        assert_eq!(ShipType::Submarine(Orientation::Vertical), ship.type_);
+        assert_eq!(
+            vec![
+                Coordinate::from((2, 2)),
+                Coordinate::from((2, 3)),
+                Coordinate::from((2, 4))
+            ],
+            ship.coords
+        );
+    }
+
+    #[test]
+    fn test_placement_range() {
+        let placement = Placement::try_from(String::from("C3:C6")).expect("invalid placement");
+        assert_eq!(Coordinate::from((3, 3)), placement.coordinate);
+        assert_eq!(Orientation::Vertical, placement.orientation);
+        assert_eq!(
+            Some(ShipType::Cruiser(Orientation::Vertical)),
+            placement.to_ship(4).map(|ship| ship.type_)
+        );
+        assert!(placement.to_ship(3).is_none());
+        assert!(placement.to_ship(5).is_none());
+        assert_eq!(Ok(placement), Placement::try_from(String::from("c6 : c3")));
+
+        let ship = Placement::try_from(String::from("E2:B2"))
+            .expect("invalid placement")
+            .to_ship(4)
+            .expect("ship does not fit");
+        assert_eq!(ShipType::Cruiser(Orientation::Horizontal), ship.type_);
+        assert_eq!(Coordinate::from((2, 2)), ship.coords[0]);
+        let placement = Placement::try_from(String::from("B2:B2")).expect("invalid placement");
+        assert!(placement.to_ship(1).is_some());
+        assert!(Placement::try_from(String::from("C3:E5")).is_err());
+        assert!(Placement::try_from(String::from("C3:")).is_err());
+    }
+
+    #[test]
+    fn test_decoy() {
+        let mut decoy = Ship::new_decoy(Coordinate::from((2, 2)));
+        decoy.coords[0].is_hit = true;
+        assert!(!decoy.is_sunk());
+    }
+
+    #[test]
+    fn test_cells_remaining() {
+        let ship_type = ShipType::Carrier(Orientation::Horizontal);
+        let mut ship = Ship::new(ship_type, ship_type.get_hitbox(Coordinate::from((1, 1))));
+        assert_eq!(5, ship.size());
+        assert_eq!(5, ship.cells_remaining());
+        ship.coords[0].is_hit = true;
+        ship.coords[3].is_hit = true;
+        assert_eq!(3, ship.cells_remaining());
+        ship.coords.iter_mut().for_each(|coord| coord.is_hit = true);
+        assert_eq!(0, ship.cells_remaining());
+        assert_eq!(5, ship.size());
+    }
+
+    #[test]
+    fn test_contains() {
+        let ship_type = ShipType::Destroyer(Orientation::Vertical);
+        let mut ship = Ship::new(ship_type, ship_type.get_hitbox(Coordinate::from((2, 2))));
+        assert!(ship.contains(&Coordinate::from((2, 2))));
+        assert!(ship.contains(&Coordinate::from((2, 3))));
+        assert!(!ship.contains(&Coordinate::from((3, 2))));
+        assert!(!ship.contains(&Coordinate::from((2, 4))));
+        ship.coords[0].is_hit = true;
+        assert!(ship.contains(&Coordinate::from((2, 2))));
+    }
+
+    #[test]
+    fn test_orientation() {
+        for orientation in [Orientation::Horizontal, Orientation::Vertical] {
+            let ship_type = ShipType::Submarine(orientation);
+            let ship = Ship::new(ship_type, ship_type.get_hitbox(Coordinate::from((2, 2))));
+            assert_eq!(Some(orientation), ship.orientation());
+        }
+        assert_eq!(None, Ship::new_decoy(Coordinate::from((2, 2))).orientation());
+        let coords = vec![Coordinate::from((1, 1)), Coordinate::from((2, 2))];
+        assert_eq!(None, Ship::new(ShipType::Boat, coords).orientation());
+    }
+
+    #[test]
+    fn test_is_valid_shape() {
+        let ship = |cells: &[(u8, u8)]| {
+            let coords = cells.iter().map(|cell| Coordinate::from(*cell)).collect();
+            Ship::new(ShipType::Boat, coords)
+        };
+        assert!(ship(&[(2, 2)]).is_valid_shape(false));
+        assert!(ship(&[(1, 1), (2, 1), (3, 1)]).is_valid_shape(false));
+        assert!(ship(&[(2, 3), (2, 1), (2, 2)]).is_valid_shape(false));
+        assert!(!ship(&[]).is_valid_shape(false));
+        assert!(!ship(&[(1, 1), (3, 1)]).is_valid_shape(false));
+        assert!(!ship(&[(1, 1), (1, 1)]).is_valid_shape(false));
+        assert!(!ship(&[(1, 1), (2, 1), (2, 2)]).is_valid_shape(true));
+
+        let diagonal = ship(&[(1, 3), (2, 2), (3, 1)]);
+        assert!(!diagonal.is_valid_shape(false));
+        assert!(diagonal.is_valid_shape(true));
+        assert!(!ship(&[(1, 1), (2, 2), (4, 4)]).is_valid_shape(true));
+    }
+
     #[test]
     fn test_ship() {
         for _ in 0..5 {
@@ -0,0 +1,176 @@
+//! Spectators of the games.
+
+use crate::game::TurnOutcome;
+use crate::grid::{Coordinate, Grid};
+use crate::player::Player;
+use crate::Result;
+use std::convert::TryFrom;
+use std::sync::{Arc, Mutex};
+
+/// Gallery that is shared between the threads.
+pub type SharedGallery = Arc<Mutex<Gallery>>;
+
+/// Shot in the move history of a game, see [`Gallery::moves`].
+///
+/// The players are given by their indices in the game, so that their names do not matter.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Move {
+    /// Number of the shots that are fired in the game, including this one.
+    pub turn: usize,
+    /// Index of the player who fired the shot.
+    pub shooter: usize,
+    /// Index of the player who is shot at.
+    pub target: usize,
+    /// Cell that is shot at.
+    pub coordinate: Coordinate,
+    /// Either [`TurnOutcome::Missed`], [`TurnOutcome::Hit`] or [`TurnOutcome::Sunk`].
+    pub outcome: TurnOutcome,
+}
+
+impl Move {
+    /// Checks if the shot hit a ship, including the shots that sunk a ship.
+    pub fn is_hit(&self) -> bool {
+        self.outcome == TurnOutcome::Hit || self.is_sunk()
+    }
+
+    /// Checks if the shot sunk a ship.
+    pub fn is_sunk(&self) -> bool {
+        self.outcome == TurnOutcome::Sunk
+    }
+
+    /// Returns the move as a status line with the names of the players, e.g. `3 Alice>Bob A1 hit`.
+    ///
+    /// The cell is labelled as on the given grid of the target, see [`Grid::coordinate_label`].
+    pub fn describe(&self, shooter: &str, target: &str, grid: &Grid) -> String {
+        format!(
+            "{} {}>{} {} {}",
+            self.turn,
+            shooter,
+            target,
+            grid.coordinate_label(self.coordinate),
+            self.outcome_name()
+        )
+    }
+
+    /// Returns the record of the move for saving and sharing a game, e.g. `3 0 1 A1 hit`.
+    ///
+    /// Also see [`Move::from_record`].
+    pub fn to_record(&self) -> String {
+        format!(
+            "{} {} {} {} {}",
+            self.turn,
+            self.shooter,
+            self.target,
+            self.coordinate,
+            self.outcome_name()
+        )
+    }
+
+    /// Restores the move from a record that is returned by [`Move::to_record`].
+    ///
+    /// Returns an error if the record is malformed.
+    pub fn from_record(s: &str) -> Result<Self> {
+        let invalid = || format!("[!] Invalid move: {}", s);
+        let words = s.split(' ').collect::<Vec<&str>>();
+        let (turn, shooter, target, coordinate, outcome) = match words.as_slice() {
+            [turn, shooter, target, coordinate, outcome] => {
+                (turn, shooter, target, coordinate, outcome)
+            }
+            _ => return Err(invalid().into()),
+        };
+        let outcome = match *outcome {
+            "miss" => TurnOutcome::Missed,
+            "hit" => TurnOutcome::Hit,
+            "sunk" => TurnOutcome::Sunk,
+            _ => return Err(invalid().into()),
+        };
+        Ok(Self {
+            turn: turn.parse()?,
+            shooter: shooter.parse()?,
+            target: target.parse()?,
+            coordinate: Coordinate::try_from(coordinate.to_string()).map_err(|_| invalid())?,
+            outcome,
+        })
+    }
+
+    /// Returns the name of the outcome in the status lines and the records, e.g. `hit`.
+    fn outcome_name(&self) -> &'static str {
+        match self.outcome {
+            TurnOutcome::Sunk => "sunk",
+            TurnOutcome::Hit => "hit",
+            _ => "miss",
+        }
+    }
+}
+
+/// Spectators and the move history of a game.
+///
+/// It is shared with the server so that spectators can join while the game is running.
+#[derive(Debug, Default)]
+pub struct Gallery {
+    /// Moves of the game, see [`Gallery::record`].
+    pub moves: Vec<Move>,
+    /// Moves of the game as compressed status lines, see [`Move::describe`].
+    pub history: Vec<String>,
+    /// Current state of the grids as seen by the spectators.
+    pub state: String,
+    /// Spectators that are watching the game.
+    spectators: Vec<Player>,
+}
+
+impl Gallery {
+    /// Adds a spectator to the live broadcast.
+    ///
+    /// The moves so far are replayed before the current state if `replay` is set.
+    pub fn add_spectator(&mut self, mut spectator: Player, replay: bool) -> Result<()> {
+        if replay {
+            let mut message = format!("Replaying {} moves:\n", self.history.len());
+            for line in &self.history {
+                message += line;
+                message.push('\n');
+            }
+            spectator.send(&message)?;
+        }
+        spectator.send(&self.state)?;
+        spectator.send("\nWatching live.\n")?;
+        println!("[#] {} is watching the game.", spectator.name);
+        self.spectators.push(spectator);
+        Ok(())
+    }
+
+    /// Records a move and broadcasts its status line to the spectators with the new state.
+    pub fn record(&mut self, shot: Move, line: String, state: String) {
+        self.broadcast(&format!("{}\n{}\n", line, state));
+        self.moves.push(shot);
+        self.history.push(line);
+        self.state = state;
+    }
+
+    /// Sends the message to the spectators.
+    ///
+    /// Spectators that cannot receive the message are removed.
+    pub fn broadcast(&mut self, message: &str) {
+        let mut i = 0;
+        while i < self.spectators.len() {
+            if self.spectators[i].send(message).is_ok() {
+                i += 1;
+            } else {
+                let spectator = self.spectators.remove(i);
+                println!("[#] {} stopped watching the game.", spectator.name);
+            }
+        }
+    }
+
+    /// Returns the number of spectators that are watching the game.
+    pub fn spectator_count(&self) -> usize {
+        self.spectators.len()
+    }
+
+    /// Removes the spectators and the history of the game.
+    pub fn clear(&mut self) {
+        self.moves.clear();
+        self.history.clear();
+        self.state.clear();
+        self.spectators.clear();
+    }
+}
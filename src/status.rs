@@ -0,0 +1,192 @@
+//! HTTP status endpoint for monitoring.
+
+use crate::game::Progress;
+use crate::lobby::Lobby;
+use crate::Result;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+
+/// Returns the status of the server as JSON.
+///
+/// Progress of each game is summed up.
+pub fn status_json(progress: &[Progress]) -> String {
+    format!(
+        r#"{{"active_games":{},"players_connected":{},"games_completed":{}}}"#,
+        progress.iter().filter(|progress| progress.in_progress).count(),
+        progress.iter().map(|progress| progress.players.len()).sum::<usize>(),
+        progress.iter().map(|progress| progress.games_completed).sum::<usize>()
+    )
+}
+
+/// Returns the games that are in progress as JSON for the administrators.
+///
+/// Each game lists its players, the number of turns and the player whose turn it is.
+pub fn games_json(progress: &[Progress]) -> String {
+    let games = progress
+        .iter()
+        .filter(|progress| progress.in_progress)
+        .map(|progress| {
+            let players = progress
+                .players
+                .iter()
+                .map(|name| json_string(name))
+                .collect::<Vec<String>>()
+                .join(",");
+            let current_player = match &progress.current_player {
+                Some(name) => json_string(name),
+                None => String::from("null"),
+            };
+            format!(
+                r#"{{"players":[{}],"turn":{},"current_player":{}}}"#,
+                players, progress.turn_count, current_player
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(",");
+    format!(r#"{{"games":[{}]}}"#, games)
+}
+
+/// Returns the string as a quoted JSON string.
+pub(crate) fn json_string(s: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => quoted += "\\\"",
+            '\\' => quoted += "\\\\",
+            c if c.is_control() => quoted += &format!("\\u{:04x}", u32::from(c)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Serves the status endpoint on the given listener.
+///
+/// Requests to `/games` are answered with the list of the active games (see [`games_json`]),
+/// the rest of the requests with the status of the server (see [`status_json`]).
+pub fn serve(listener: TcpListener, lobby: Arc<Mutex<Lobby>>) {
+    for stream in listener.incoming() {
+        let result = stream
+            .map_err(|e| e.into())
+            .and_then(|stream| respond(stream, &lobby));
+        if let Err(e) = result {
+            eprintln!("[!] Status request failed: {}", e);
+        }
+    }
+}
+
+/// Responds to a single HTTP request.
+fn respond(mut stream: TcpStream, lobby: &Arc<Mutex<Lobby>>) -> Result<()> {
+    // Read the path from the request line and skip the headers.
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let path = request_line.split_whitespace().nth(1).unwrap_or("/").to_string();
+    let mut line = request_line;
+    while !line.trim().is_empty() {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+    }
+    let body = match lobby.lock() {
+        Ok(lobby) if path == "/games" => games_json(&lobby.progress()),
+        Ok(lobby) => status_json(&lobby.progress()),
+        Err(_) => return Err("[!] Failed to retrieve game progress.".into()),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use std::thread;
+
+    #[test]
+    fn test_status_json() {
+        let progress = [
+            Progress {
+                in_progress: true,
+                players: vec![String::from("Alice"), String::from("Bob")],
+                ships_remaining: 5,
+                games_completed: 3,
+                ..Progress::default()
+            },
+            Progress {
+                in_progress: false,
+                players: vec![String::from("Carol")],
+                ships_remaining: 0,
+                games_completed: 1,
+                ..Progress::default()
+            },
+        ];
+        assert_eq!(
+            r#"{"active_games":1,"players_connected":3,"games_completed":4}"#,
+            status_json(&progress)
+        );
+    }
+
+    #[test]
+    fn test_games_json() {
+        let progress = [
+            Progress {
+                in_progress: true,
+                players: vec![String::from("Alice"), String::from("Bob")],
+                turn_count: 12,
+                current_player: Some(String::from("Bob")),
+                ..Progress::default()
+            },
+            Progress {
+                in_progress: false,
+                players: vec![String::from("Carol")],
+                ..Progress::default()
+            },
+            Progress {
+                in_progress: true,
+                players: vec![String::from("Dan \"D\""), String::from("Erin")],
+                turn_count: 3,
+                current_player: None,
+                ..Progress::default()
+            },
+        ];
+        assert_eq!(
+            concat!(
+                r#"{"games":[{"players":["Alice","Bob"],"turn":12,"current_player":"Bob"},"#,
+                r#"{"players":["Dan \"D\"","Erin"],"turn":3,"current_player":null}]}"#
+            ),
+            games_json(&progress)
+        );
+    }
+
+    #[test]
+    fn test_status() -> Result<()> {
+        let lobby = Arc::new(Mutex::new(Lobby::default()));
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        thread::spawn(move || serve(listener, lobby));
+        let mut client = TcpStream::connect(addr)?;
+        client.write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n")?;
+        let mut response = String::new();
+        client.read_to_string(&mut response)?;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with(
+            "\r\n\r\n{\"active_games\":0,\"players_connected\":0,\"games_completed\":0}"
+        ));
+
+        let mut client = TcpStream::connect(addr)?;
+        client.write_all(b"GET /games HTTP/1.1\r\nHost: localhost\r\n\r\n")?;
+        let mut response = String::new();
+        client.read_to_string(&mut response)?;
+        assert!(response.ends_with("\r\n\r\n{\"games\":[]}"));
+        Ok(())
+    }
+}
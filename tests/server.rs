@@ -0,0 +1,102 @@
+//! End-to-end tests of the TCP server.
+
+use battleship::config::Config;
+use std::io::{Read, Result, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::thread;
+
+/// Prompt for the player name.
+const NAME_PROMPT: &str = "Please enter your name: ";
+/// Prompt for placing the only ship of the fleet.
+const PLACEMENT_PROMPT: &str = "Place your size-2 ship (e.g. C3 v): ";
+/// Prompt for shooting, followed by the name of the opponent.
+const SHOT_PROMPT: &str = "Your turn to shoot";
+
+/// Plays the game as a client until a winner is announced.
+///
+/// Every client places a destroyer at A1 and shoots at the same cells
+/// so the first player sinks both of the opponents without missing.
+/// Returns everything that is received from the server.
+fn play(addr: SocketAddr, name: &str) -> Result<String> {
+    let mut stream = TcpStream::connect(addr)?;
+    let mut shots = ["A1", "B1", "A1", "B1"].iter();
+    let mut transcript = String::new();
+    let mut handled = 0;
+    let mut buffer = [0; 1024];
+    while !transcript.contains("won") {
+        let n = stream.read(&mut buffer)?;
+        if n == 0 {
+            break;
+        }
+        transcript += &String::from_utf8_lossy(&buffer[..n]);
+
+        // Answer the prompts in the order they are received.
+        while let Some((i, prompt)) = [NAME_PROMPT, PLACEMENT_PROMPT, SHOT_PROMPT]
+            .iter()
+            .filter_map(|prompt| transcript[handled..].find(prompt).map(|i| (i, *prompt)))
+            .min()
+        {
+            let answer = match prompt {
+                NAME_PROMPT => name,
+                PLACEMENT_PROMPT => "A1 h",
+                _ => shots.next().copied().unwrap_or("A1"),
+            };
+            stream.write_all(format!("{}\n", answer).as_bytes())?;
+            handled += i + prompt.len();
+        }
+    }
+    Ok(transcript)
+}
+
+#[test]
+#[ignore] // Takes a few seconds due to the countdown, run with `cargo test -- --ignored`.
+fn test_server() -> battleship::Result<()> {
+    let listeners = battleship::bind("127.0.0.1:0")?;
+    let addr = listeners[0].local_addr()?;
+    let config = Config {
+        grid_width: 5,
+        grid_height: 5,
+        fleet_density: Some(0.08),
+        manual_placement: true,
+        ..Config::default()
+    };
+    thread::spawn(move || battleship::serve(listeners, config).map_err(|e| e.to_string()));
+
+    let names = ["Alice", "Bob", "Carol"];
+    let transcripts = names
+        .iter()
+        .copied()
+        .map(|name| thread::spawn(move || play(addr, name).map_err(|e| e.to_string())))
+        .collect::<Vec<_>>()
+        .into_iter()
+        .map(|handle| handle.join().expect("client thread panicked"))
+        .collect::<std::result::Result<Vec<String>, String>>()?;
+
+    // The first player to shoot wins without giving the turn away.
+    let winner = transcripts
+        .iter()
+        .position(|transcript| transcript.contains("You won!"))
+        .expect("no winner is announced");
+    let winner_name = names[winner];
+    let winner_transcript = &transcripts[winner];
+    assert_eq!(4, winner_transcript.matches(SHOT_PROMPT).count());
+    assert_eq!(4, winner_transcript.matches("Hit!\n").count());
+    assert!(winner_transcript.contains("Game seed: "));
+    for (i, transcript) in transcripts.iter().enumerate() {
+        assert!(transcript.contains("Welcome to Battleship!"));
+        assert_eq!(1, transcript.matches(PLACEMENT_PROMPT).count());
+        if i != winner {
+            assert!(transcript.contains(&format!("{} won.\n", winner_name)));
+            assert!(transcript.contains(&format!("{}'s turn.\n", winner_name)));
+            assert!(!transcript.contains(SHOT_PROMPT));
+        }
+    }
+    assert_eq!(
+        2,
+        transcripts
+            .iter()
+            .filter(|transcript| transcript.contains(&format!("{} is firing at A1\n", winner_name)))
+            .count()
+    );
+    Ok(())
+}